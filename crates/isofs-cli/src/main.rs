@@ -9,7 +9,11 @@ fn main() -> Result<(), isofs::error::Error> {
 
   match cli.command {
     cli::Command::Create { output, directory } => {
-      let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+      // Rock Ridge keeps the captured permissions, e.g. executable bits, intact.
+      let mut writer = IsoWriter::new(IsoWriterOptions {
+        rock_ridge: Some(RockRidgeOptions::default()),
+        ..IsoWriterOptions::compatibility()
+      });
 
       writer.upsert_filesystem(
         Filesystem::capture("", &directory)?,
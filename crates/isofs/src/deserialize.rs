@@ -0,0 +1,2174 @@
+//! Inverse of [crate::serialize], reading specification types back from their on-disk representation.
+
+use crate::{serialize::Endianness, spec::*};
+
+type Result<T> = std::result::Result<T, IsoDeserializeError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IsoDeserializeError {
+  #[error("Input buffer too small")]
+  InputBufferTooSmall { expected: usize, actual: usize },
+  #[error("Invalid value {value:#04x} for {field}")]
+  InvalidValue { field: &'static str, value: u8 },
+  #[error("Invalid digits: {0:?}")]
+  InvalidDigits(Vec<u8>),
+}
+
+/// Determines how directory records are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectoryRecordContext {
+  /// Determines how file identifiers are interpreted.
+  pub compatibility_mode: CompatibilityMode,
+  /// Bytes preceding the SUSP entries of each System Use area, as announced by the `SP` entry of
+  /// the root directory's `.` record.
+  pub bytes_skipped: u8,
+}
+
+impl From<CompatibilityMode> for DirectoryRecordContext {
+  fn from(compatibility_mode: CompatibilityMode) -> Self {
+    Self {
+      compatibility_mode,
+      bytes_skipped: 0,
+    }
+  }
+}
+
+pub trait IsoDeserialize: Sized {
+  type Context;
+
+  /// Number of bytes occupied by the value at the start of `input`.
+  ///
+  /// Variable length structures determine this from their length field, if present.
+  fn extent(cx: &Self::Context, input: &[u8]) -> usize;
+
+  /// # Safety
+  ///
+  /// `input` must be at least [IsoDeserialize::extent] bytes long.
+  unsafe fn deserialize_unchecked(cx: &mut Self::Context, input: &[u8]) -> Result<Self>;
+
+  fn deserialize(cx: &mut Self::Context, input: &[u8]) -> Result<Self> {
+    let extent = Self::extent(cx, input);
+
+    if input.len() < extent {
+      return Err(IsoDeserializeError::InputBufferTooSmall {
+        expected: extent,
+        actual: input.len(),
+      });
+    }
+
+    unsafe { Self::deserialize_unchecked(cx, input) }
+  }
+}
+
+/// Reads a both-endian (ISO 9660 7.2.3) 16-bit value, trusting the little endian half.
+fn both_endian_u16(input: &[u8]) -> u16 {
+  u16::from_le_bytes([input[0], input[1]])
+}
+
+/// Reads a both-endian (ISO 9660 7.3.3) 32-bit value, trusting the little endian half.
+fn both_endian_u32(input: &[u8]) -> u32 {
+  u32::from_le_bytes([input[0], input[1], input[2], input[3]])
+}
+
+fn digits(input: &[u8]) -> Result<u16> {
+  input.iter().try_fold(0u16, |acc, &b| match b {
+    b'0'..=b'9' => Ok(acc * 10 + (b - b'0') as u16),
+    // Unrecorded dates are sometimes filled with spaces or zeroes instead of `0` digits.
+    b' ' | 0 => Ok(acc * 10),
+    _ => Err(IsoDeserializeError::InvalidDigits(input.to_vec())),
+  })
+}
+
+fn expect_descriptor_type(input: &[u8], ty: VolumeDescriptorType) -> Result<()> {
+  if input[0] != u8::from(ty) {
+    return Err(IsoDeserializeError::InvalidValue {
+      field: "volume_descriptor_type",
+      value: input[0],
+    });
+  }
+
+  Ok(())
+}
+
+impl IsoDeserialize for Identifier {
+  type Context = IdentifierKind;
+
+  fn extent(_: &IdentifierKind, input: &[u8]) -> usize {
+    input.len().min(255)
+  }
+
+  unsafe fn deserialize_unchecked(kind: &mut IdentifierKind, input: &[u8]) -> Result<Self> {
+    let input = &input[..Self::extent(kind, input)];
+
+    match kind {
+      // Fixed-length fields within the volume descriptors are padded with filler.
+      IdentifierKind::ACharacters
+      | IdentifierKind::DCharacters
+      | IdentifierKind::A1Characters
+      | IdentifierKind::D1Characters => Ok(Identifier::from_padded_bytes(*kind, input)),
+      _ => Ok(Identifier::from_bytes(*kind, input)),
+    }
+  }
+}
+
+impl<const LENGTH: usize> IsoDeserialize for EscapeSequences<LENGTH> {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    LENGTH
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let mut data = [0u8; LENGTH];
+    data.copy_from_slice(&input[..LENGTH]);
+    Ok(Self(data))
+  }
+}
+
+impl IsoDeserialize for VariadicEscapeSequences {
+  type Context = ();
+
+  fn extent((): &(), input: &[u8]) -> usize {
+    input.len()
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input.to_vec()))
+  }
+}
+
+impl IsoDeserialize for FileFlags {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self::from_bits_retain(input[0]))
+  }
+}
+
+impl IsoDeserialize for Permissions {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self::from_bits_retain(u16::from_le_bytes([
+      input[0], input[1],
+    ])))
+  }
+}
+
+impl IsoDeserialize for VolumeFlags {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self::from_bits_retain(input[0]))
+  }
+}
+
+impl IsoDeserialize for OwnerIdentification {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(u16::from_le_bytes([input[0], input[1]])))
+  }
+}
+
+impl IsoDeserialize for GroupIdentification {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(u16::from_le_bytes([input[0], input[1]])))
+  }
+}
+
+impl IsoDeserialize for RecordFormat {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(input[0].into())
+  }
+}
+
+impl IsoDeserialize for RecordAttributes {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(input[0].into())
+  }
+}
+
+impl IsoDeserialize for ExtendedAttributeRecordVersion {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(input[0].into())
+  }
+}
+
+impl IsoDeserialize for StandardIdentifier {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    5
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self::from([
+      input[0], input[1], input[2], input[3], input[4],
+    ]))
+  }
+}
+
+impl IsoDeserialize for VolumeDescriptorVersion {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(input[0].into())
+  }
+}
+
+impl IsoDeserialize for FileStructureVersion {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(input[0].into())
+  }
+}
+
+impl IsoDeserialize for DigitsYear {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    4
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..4])?))
+  }
+}
+
+impl IsoDeserialize for DigitsMonth {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for DigitsDay {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for DigitsHour {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for DigitsMinute {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for DigitsSecond {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for DigitsHundreths {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(digits(&input[..2])? as u8))
+  }
+}
+
+impl IsoDeserialize for NumericalYear {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalMonth {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalDay {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalHour {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalMinute {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalSecond {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0]))
+  }
+}
+
+impl IsoDeserialize for NumericalGmtOffset {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[0] as i8))
+  }
+}
+
+impl IsoDeserialize for DigitsDate {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    17
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      year: DigitsYear::deserialize_unchecked(&mut (), &input[0..4])?,
+      month: DigitsMonth::deserialize_unchecked(&mut (), &input[4..6])?,
+      day: DigitsDay::deserialize_unchecked(&mut (), &input[6..8])?,
+      hour: DigitsHour::deserialize_unchecked(&mut (), &input[8..10])?,
+      minute: DigitsMinute::deserialize_unchecked(&mut (), &input[10..12])?,
+      second: DigitsSecond::deserialize_unchecked(&mut (), &input[12..14])?,
+      hundreths: DigitsHundreths::deserialize_unchecked(&mut (), &input[14..16])?,
+      gmt_offset: NumericalGmtOffset::deserialize_unchecked(&mut (), &input[16..17])?,
+    })
+  }
+}
+
+impl IsoDeserialize for NumericalDate {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    7
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      years_since_1900: NumericalYear::deserialize_unchecked(&mut (), &input[0..1])?,
+      month: NumericalMonth::deserialize_unchecked(&mut (), &input[1..2])?,
+      day: NumericalDay::deserialize_unchecked(&mut (), &input[2..3])?,
+      hour: NumericalHour::deserialize_unchecked(&mut (), &input[3..4])?,
+      minute: NumericalMinute::deserialize_unchecked(&mut (), &input[4..5])?,
+      second: NumericalSecond::deserialize_unchecked(&mut (), &input[5..6])?,
+      gmt_offset: NumericalGmtOffset::deserialize_unchecked(&mut (), &input[6..7])?,
+    })
+  }
+}
+
+impl IsoDeserialize for PrimaryVolumeDescriptor {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2048
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    expect_descriptor_type(input, VolumeDescriptorType::Primary)?;
+
+    let a_characters = |range: std::ops::Range<usize>| {
+      Identifier::deserialize_unchecked(&mut IdentifierKind::ACharacters, &input[range])
+    };
+    let d_characters = |range: std::ops::Range<usize>| {
+      Identifier::deserialize_unchecked(&mut IdentifierKind::DCharacters, &input[range])
+    };
+
+    Ok(Self {
+      standard_identifier: StandardIdentifier::deserialize_unchecked(&mut (), &input[1..6])?,
+      version: VolumeDescriptorVersion::deserialize_unchecked(&mut (), &input[6..7])?,
+      system_identifier: a_characters(8..40)?,
+      volume_identifier: d_characters(40..72)?,
+      volume_space_size: both_endian_u32(&input[80..88]),
+      volume_set_size: both_endian_u16(&input[120..124]),
+      volume_sequence_number: both_endian_u16(&input[124..128]),
+      logical_block_size: both_endian_u16(&input[128..132]),
+      path_table_size: both_endian_u32(&input[132..140]),
+      type_l_path_table_location: u32::from_le_bytes(input[140..144].try_into().unwrap()),
+      optional_type_l_path_table_location: u32::from_le_bytes(input[144..148].try_into().unwrap()),
+      type_m_path_table_location: u32::from_be_bytes(input[148..152].try_into().unwrap()),
+      optional_type_m_path_table_location: u32::from_be_bytes(input[152..156].try_into().unwrap()),
+      root_directory_record: RootDirectoryRecord::deserialize_unchecked(&mut (), &input[156..190])?,
+      volume_set_identifier: d_characters(190..318)?,
+      publisher_identifier: d_characters(318..446)?,
+      data_preparer_identifier: d_characters(446..574)?,
+      application_identifier: a_characters(574..702)?,
+      copyright_file_identifier: d_characters(702..739)?,
+      abstract_file_identifier: d_characters(739..776)?,
+      bibliographic_file_identifier: d_characters(776..813)?,
+      creation_date: DigitsDate::deserialize_unchecked(&mut (), &input[813..830])?,
+      modification_date: DigitsDate::deserialize_unchecked(&mut (), &input[830..847])?,
+      expiration_date: DigitsDate::deserialize_unchecked(&mut (), &input[847..864])?,
+      effective_date: DigitsDate::deserialize_unchecked(&mut (), &input[864..881])?,
+      file_structure_version: FileStructureVersion::deserialize_unchecked(
+        &mut (),
+        &input[881..882],
+      )?,
+      application_use: input[883..1395].try_into().unwrap(),
+    })
+  }
+}
+
+impl IsoDeserialize for SupplementaryVolumeDescriptor {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2048
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    expect_descriptor_type(input, VolumeDescriptorType::Supplementary)?;
+
+    let a_characters = |range: std::ops::Range<usize>| {
+      Identifier::deserialize_unchecked(&mut IdentifierKind::ACharacters, &input[range])
+    };
+    let d_characters = |range: std::ops::Range<usize>| {
+      Identifier::deserialize_unchecked(&mut IdentifierKind::DCharacters, &input[range])
+    };
+
+    Ok(Self {
+      standard_identifier: StandardIdentifier::deserialize_unchecked(&mut (), &input[1..6])?,
+      version: VolumeDescriptorVersion::deserialize_unchecked(&mut (), &input[6..7])?,
+      volume_flags: VolumeFlags::deserialize_unchecked(&mut (), &input[7..8])?,
+      system_identifier: a_characters(8..40)?,
+      volume_identifier: d_characters(40..72)?,
+      volume_space_size: both_endian_u32(&input[80..88]),
+      escape_sequences: EscapeSequences::deserialize_unchecked(&mut (), &input[88..120])?,
+      volume_set_size: both_endian_u16(&input[120..124]),
+      volume_sequence_number: both_endian_u16(&input[124..128]),
+      logical_block_size: both_endian_u16(&input[128..132]),
+      path_table_size: both_endian_u32(&input[132..140]),
+      type_l_path_table_location: u32::from_le_bytes(input[140..144].try_into().unwrap()),
+      optional_type_l_path_table_location: u32::from_le_bytes(input[144..148].try_into().unwrap()),
+      type_m_path_table_location: u32::from_be_bytes(input[148..152].try_into().unwrap()),
+      optional_type_m_path_table_location: u32::from_be_bytes(input[152..156].try_into().unwrap()),
+      root_directory_record: RootDirectoryRecord::deserialize_unchecked(&mut (), &input[156..190])?,
+      volume_set_identifier: d_characters(190..318)?,
+      publisher_identifier: d_characters(318..446)?,
+      data_preparer_identifier: d_characters(446..574)?,
+      application_identifier: a_characters(574..702)?,
+      copyright_file_identifier: d_characters(702..739)?,
+      abstract_file_identifier: d_characters(739..776)?,
+      bibliographic_file_identifier: d_characters(776..813)?,
+      creation_date: DigitsDate::deserialize_unchecked(&mut (), &input[813..830])?,
+      modification_date: DigitsDate::deserialize_unchecked(&mut (), &input[830..847])?,
+      expiration_date: DigitsDate::deserialize_unchecked(&mut (), &input[847..864])?,
+      effective_date: DigitsDate::deserialize_unchecked(&mut (), &input[864..881])?,
+      file_structure_version: FileStructureVersion::deserialize_unchecked(
+        &mut (),
+        &input[881..882],
+      )?,
+      application_use: input[883..1395].try_into().unwrap(),
+    })
+  }
+}
+
+impl IsoDeserialize for VolumePartitionDescriptor {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2048
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    expect_descriptor_type(input, VolumeDescriptorType::Partition)?;
+
+    Ok(Self {
+      standard_identifier: StandardIdentifier::deserialize_unchecked(&mut (), &input[1..6])?,
+      version: VolumeDescriptorVersion::deserialize_unchecked(&mut (), &input[6..7])?,
+      system_identifier: Identifier::deserialize_unchecked(
+        &mut IdentifierKind::ACharacters,
+        &input[8..40],
+      )?,
+      volume_partition_identifier: Identifier::deserialize_unchecked(
+        &mut IdentifierKind::DCharacters,
+        &input[40..72],
+      )?,
+      volume_partition_location: both_endian_u32(&input[72..80]),
+      volume_partition_size: both_endian_u32(&input[80..88]),
+    })
+  }
+}
+
+impl IsoDeserialize for VolumeDescriptorSetTerminator {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2048
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    expect_descriptor_type(input, VolumeDescriptorType::Terminator)?;
+
+    Ok(Self)
+  }
+}
+
+impl IsoDeserialize for DirectoryRecord {
+  type Context = DirectoryRecordContext;
+
+  fn extent(_: &DirectoryRecordContext, input: &[u8]) -> usize {
+    input
+      .first()
+      .map_or(33, |&length| (length as usize).max(33))
+  }
+
+  unsafe fn deserialize_unchecked(cx: &mut DirectoryRecordContext, input: &[u8]) -> Result<Self> {
+    let file_identifier_length = input[32];
+
+    if 33 + file_identifier_length as usize > input[0] as usize {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "file_identifier_length",
+        value: file_identifier_length,
+      });
+    }
+
+    let file_flags = FileFlags::deserialize_unchecked(&mut (), &input[25..26])?;
+    let identifier = &input[33..33 + file_identifier_length as usize];
+
+    let mut kind = match (identifier, cx.compatibility_mode) {
+      ([0], _) => IdentifierKind::CurrentDirectory,
+      ([1], _) => IdentifierKind::ParentDirectory,
+      (_, CompatibilityMode::Joliet(_)) if file_flags.contains(FileFlags::DIRECTORY) => {
+        IdentifierKind::JolietDirectoryIdentifier
+      }
+      (_, CompatibilityMode::Joliet(_)) => IdentifierKind::JolietFileIdentifier,
+      (_, CompatibilityMode::Standard(_)) if file_flags.contains(FileFlags::DIRECTORY) => {
+        IdentifierKind::StandardDirectoryIdentifier
+      }
+      (_, CompatibilityMode::Standard(_)) => IdentifierKind::StandardFileIdentifier,
+    };
+
+    let length = input[0] as usize;
+    let offset = 33
+      + file_identifier_length as usize
+      + file_identifier_length.is_multiple_of(2) as usize
+      + cx.bytes_skipped as usize;
+
+    let system_use = system_use_area(input.get(offset..length).unwrap_or_default())?;
+
+    Ok(Self {
+      extended_attribute_length: input[1],
+      extent_location: both_endian_u32(&input[2..10]),
+      data_length: both_endian_u32(&input[10..18]),
+      recording_date: NumericalDate::deserialize_unchecked(&mut (), &input[18..25])?,
+      file_flags,
+      file_unit_size: input[26],
+      interleave_gap_size: input[27],
+      volume_sequence_number: both_endian_u16(&input[28..32]),
+      file_identifier_length,
+      file_identifier: Identifier::deserialize_unchecked(&mut kind, identifier)?,
+      system_use,
+    })
+  }
+}
+
+/// Reads the entries of a System Use area, or of one of its continuation areas, up to its end
+/// or a `ST` entry. Bytes that don't form a SUSP entry, such as CD-XA system use information or
+/// padding, end the area.
+pub fn system_use_area(input: &[u8]) -> Result<Vec<SystemUseEntry>> {
+  let mut offset = 0;
+  let mut entries = Vec::new();
+
+  while offset + 4 <= input.len() && input[offset] != 0 {
+    let extent = SystemUseEntry::extent(&(), &input[offset..]);
+
+    if offset + extent > input.len() {
+      break;
+    }
+
+    let entry = SystemUseEntry::deserialize(&mut (), &input[offset..offset + extent])?;
+    let terminator = entry == SystemUseEntry::Terminator;
+
+    offset += extent;
+    entries.push(entry);
+
+    if terminator {
+      break;
+    }
+  }
+
+  Ok(entries)
+}
+
+/// Reads the timestamps of a `TF` entry flagged by `flags`, each taking up `extent` bytes.
+fn time_stamps<T>(flags: u8, input: &[u8], extent: usize) -> Result<RecordedTimeStamps<T>>
+where
+  T: IsoDeserialize<Context = ()>,
+{
+  let mut timestamps = RecordedTimeStamps::default();
+  let mut offset = 0;
+
+  for (ix, timestamp) in timestamps.iter_mut().enumerate() {
+    if flags & 1 << ix != 0 {
+      *timestamp =
+        Some(unsafe { T::deserialize_unchecked(&mut (), &input[offset..offset + extent])? });
+      offset += extent;
+    }
+  }
+
+  Ok(timestamps)
+}
+
+impl IsoDeserialize for SystemUseEntry {
+  type Context = ();
+
+  fn extent((): &(), input: &[u8]) -> usize {
+    input.get(2).map_or(4, |&length| (length as usize).max(4))
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let length = input[2];
+    let data = &input[4..(length as usize).max(4)];
+
+    let expect_length = |expected: usize| {
+      if data.len() == expected {
+        Ok(())
+      } else {
+        Err(IsoDeserializeError::InvalidValue {
+          field: "system_use_entry_length",
+          value: length,
+        })
+      }
+    };
+
+    // Every entry counts its own 4 byte header.
+    if length < 4 {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "system_use_entry_length",
+        value: length,
+      });
+    }
+
+    Ok(match &[input[0], input[1]] {
+      b"SP" => {
+        expect_length(3)?;
+
+        if data[0..2] != [0xBE, 0xEF] {
+          return Err(IsoDeserializeError::InvalidValue {
+            field: "sharing_protocol_check_bytes",
+            value: data[0],
+          });
+        }
+
+        Self::SharingProtocolIndicator {
+          bytes_skipped: data[2],
+        }
+      }
+      b"CE" => {
+        expect_length(24)?;
+
+        Self::ContinuationArea {
+          block_location: both_endian_u32(&data[0..8]),
+          offset: both_endian_u32(&data[8..16]),
+          length: both_endian_u32(&data[16..24]),
+        }
+      }
+      b"PD" => Self::Padding { length: length - 4 },
+      b"ST" => {
+        expect_length(0)?;
+        Self::Terminator
+      }
+      b"ER" => {
+        if data.len() < 4 {
+          expect_length(4)?;
+        }
+
+        let (identifier_length, descriptor_length, source_length) =
+          (data[0] as usize, data[1] as usize, data[2] as usize);
+
+        expect_length(4 + identifier_length + descriptor_length + source_length)?;
+
+        let string =
+          |range: std::ops::Range<usize>| String::from_utf8_lossy(&data[range]).into_owned();
+
+        Self::ExtensionsReference {
+          version: data[3],
+          identifier: string(4..4 + identifier_length),
+          descriptor: string(4 + identifier_length..4 + identifier_length + descriptor_length),
+          source: string(4 + identifier_length + descriptor_length..data.len()),
+        }
+      }
+      b"ES" => {
+        expect_length(1)?;
+        Self::ExtensionSelector { sequence: data[0] }
+      }
+      b"PX" => {
+        if data.len() != 32 {
+          expect_length(40)?;
+        }
+
+        Self::PosixFileAttributes {
+          mode: both_endian_u32(&data[0..8]),
+          links: both_endian_u32(&data[8..16]),
+          uid: both_endian_u32(&data[16..24]),
+          gid: both_endian_u32(&data[24..32]),
+          serial: data.get(32..40).map(both_endian_u32),
+        }
+      }
+      b"PN" => {
+        expect_length(16)?;
+
+        Self::DeviceNumber {
+          high: both_endian_u32(&data[0..8]),
+          low: both_endian_u32(&data[8..16]),
+        }
+      }
+      b"TF" => {
+        if data.is_empty() {
+          expect_length(1)?;
+        }
+
+        let flags = data[0];
+        let count = (flags & !RecordedTimeStamps::<DigitsDate>::LONG_FORM).count_ones() as usize;
+
+        if flags & RecordedTimeStamps::<DigitsDate>::LONG_FORM != 0 {
+          expect_length(1 + 17 * count)?;
+          Self::TimeStamps(TimeStamps::Long(time_stamps(flags, &data[1..], 17)?))
+        } else {
+          expect_length(1 + 7 * count)?;
+          Self::TimeStamps(TimeStamps::Short(time_stamps(flags, &data[1..], 7)?))
+        }
+      }
+      b"NM" => {
+        if data.is_empty() {
+          expect_length(1)?;
+        }
+
+        Self::AlternateName {
+          flags: AlternateNameFlags::from_bits_retain(data[0]),
+          content: data[1..].to_vec(),
+        }
+      }
+      b"SL" => {
+        if data.is_empty() {
+          expect_length(1)?;
+        }
+
+        let mut components = vec![];
+        let mut offset = 1;
+
+        while offset < data.len() {
+          if offset + 2 > data.len() || offset + 2 + data[offset + 1] as usize > data.len() {
+            return Err(IsoDeserializeError::InvalidValue {
+              field: "symbolic_link_component_length",
+              value: data.get(offset + 1).copied().unwrap_or(0),
+            });
+          }
+
+          let length = data[offset + 1] as usize;
+
+          components.push(SymbolicLinkComponent {
+            flags: SymbolicLinkComponentFlags::from_bits_retain(data[offset]),
+            content: data[offset + 2..offset + 2 + length].to_vec(),
+          });
+          offset += 2 + length;
+        }
+
+        Self::SymbolicLink {
+          continues: data[0] & 1 != 0,
+          components,
+        }
+      }
+      b"CL" => {
+        expect_length(8)?;
+        Self::ChildLink {
+          block_location: both_endian_u32(data),
+        }
+      }
+      b"PL" => {
+        expect_length(8)?;
+        Self::ParentLink {
+          block_location: both_endian_u32(data),
+        }
+      }
+      b"RE" => {
+        expect_length(0)?;
+        Self::Relocated
+      }
+      b"ZF" => {
+        expect_length(12)?;
+        Self::CompressedFile {
+          algorithm: [data[0], data[1]],
+          header_size: data[2],
+          block_size_log2: data[3],
+          uncompressed_size: both_endian_u32(&data[4..12]),
+        }
+      }
+      &signature => Self::Unknown {
+        signature,
+        version: input[3],
+        data: data.to_vec(),
+      },
+    })
+  }
+}
+
+impl IsoDeserialize for RootDirectoryRecord {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    34
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      extent_location: both_endian_u32(&input[2..10]),
+      data_length: both_endian_u32(&input[10..18]),
+      recording_date: NumericalDate::deserialize_unchecked(&mut (), &input[18..25])?,
+      file_flags: FileFlags::deserialize_unchecked(&mut (), &input[25..26])?,
+      file_unit_size: input[26],
+      interleave_gap_size: input[27],
+      volume_sequence_number: both_endian_u16(&input[28..32]),
+    })
+  }
+}
+
+impl IsoDeserialize for PathTableRecord {
+  type Context = Endianness;
+
+  fn extent(_: &Endianness, input: &[u8]) -> usize {
+    let directory_identifier_length = input.first().copied().unwrap_or(0) as usize;
+
+    8 + directory_identifier_length + directory_identifier_length % 2
+  }
+
+  unsafe fn deserialize_unchecked(endianness: &mut Endianness, input: &[u8]) -> Result<Self> {
+    let directory_identifier_length = input[0];
+    let identifier = &input[8..8 + directory_identifier_length as usize];
+
+    let (extent_location, parent_directory_number) = match endianness {
+      Endianness::Little => (
+        u32::from_le_bytes(input[2..6].try_into().unwrap()),
+        u16::from_le_bytes(input[6..8].try_into().unwrap()),
+      ),
+      Endianness::Big => (
+        u32::from_be_bytes(input[2..6].try_into().unwrap()),
+        u16::from_be_bytes(input[6..8].try_into().unwrap()),
+      ),
+    };
+
+    Ok(Self {
+      directory_identifier_length,
+      extended_attribute_record_length: input[1],
+      extent_location,
+      parent_directory_number,
+      directory_identifier: match identifier {
+        [0] => Identifier::root_directory(),
+        _ => Identifier::deserialize_unchecked(
+          &mut IdentifierKind::StandardDirectoryIdentifier,
+          identifier,
+        )?,
+      },
+    })
+  }
+}
+
+impl IsoDeserialize for ExtendedAttributeRecord {
+  type Context = ();
+
+  fn extent((): &(), input: &[u8]) -> usize {
+    match input.get(..250) {
+      Some(header) => 250 + both_endian_u16(&header[246..250]) as usize + header[181] as usize,
+      None => 250,
+    }
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let escape_sequences_offset = 250 + both_endian_u16(&input[246..250]) as usize;
+
+    Ok(Self {
+      owner_identification: OwnerIdentification(both_endian_u16(&input[0..4])),
+      group_identification: GroupIdentification(both_endian_u16(&input[4..8])),
+      permissions: Permissions::deserialize_unchecked(&mut (), &input[8..10])?,
+      file_creation_date: DigitsDate::deserialize_unchecked(&mut (), &input[10..27])?,
+      file_modification_date: DigitsDate::deserialize_unchecked(&mut (), &input[27..44])?,
+      file_expiration_date: DigitsDate::deserialize_unchecked(&mut (), &input[44..61])?,
+      file_effective_date: DigitsDate::deserialize_unchecked(&mut (), &input[61..78])?,
+      record_format: RecordFormat::deserialize_unchecked(&mut (), &input[78..79])?,
+      record_attributes: RecordAttributes::deserialize_unchecked(&mut (), &input[79..80])?,
+      record_length: both_endian_u16(&input[80..84]),
+      system_identifier: Identifier::deserialize_unchecked(
+        &mut IdentifierKind::ACharacters,
+        &input[84..116],
+      )?,
+      system_use: input[116..180].try_into().unwrap(),
+      extended_attribute_record_version: ExtendedAttributeRecordVersion::deserialize_unchecked(
+        &mut (),
+        &input[180..181],
+      )?,
+      application_use: input[250..escape_sequences_offset].to_vec(),
+      escape_sequences: VariadicEscapeSequences::deserialize_unchecked(
+        &mut (),
+        &input[escape_sequences_offset..escape_sequences_offset + input[181] as usize],
+      )?,
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoManufacturerId {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    24
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[..24].try_into().unwrap()))
+  }
+}
+
+impl IsoDeserialize for ElToritoBootMediaTypeExt {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    1
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let emulation_type = ElToritoEmulationType::try_from(input[0] & 0x0f).map_err(|value| {
+      IsoDeserializeError::InvalidValue {
+        field: "emulation_type",
+        value,
+      }
+    })?;
+
+    Ok(Self {
+      emulation_type,
+      continuation_entry_follows: input[0] & (1 << 5) != 0,
+      contains_atapi_driver: input[0] & (1 << 6) != 0,
+      contains_scsi_drivers: input[0] & (1 << 7) != 0,
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoSectionId {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    28
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[..28].try_into().unwrap()))
+  }
+}
+
+impl IsoDeserialize for ElToritoInitialSectionEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    32
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      boot_indicator: input[0].into(),
+      boot_media_type: ElToritoBootMediaType(input[1]),
+      load_segment: u16::from_le_bytes([input[2], input[3]]),
+      system_type: input[4],
+      sector_count: u16::from_le_bytes([input[6], input[7]]),
+      virtual_disk_location: u32::from_le_bytes(input[8..0x0c].try_into().unwrap()),
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoSectionHeaderEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    32
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let header_indicator = ElToritoHeaderIndicator::try_from(input[0]).map_err(|value| {
+      IsoDeserializeError::InvalidValue {
+        field: "header_indicator",
+        value,
+      }
+    })?;
+
+    Ok(Self {
+      header_indicator,
+      platform_id: input[1].into(),
+      succeeding_section_entries: u16::from_le_bytes([input[2], input[3]]),
+      section_id: ElToritoSectionId::deserialize_unchecked(&mut (), &input[4..0x20])?,
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoValidationEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    32
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if input[0x1e] != 0x55 || input[0x1f] != 0xAA {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "key_bytes",
+        value: input[0x1e],
+      });
+    }
+
+    Ok(Self {
+      header_id: input[0].into(),
+      platform_id: input[1].into(),
+      manufacturer_id: ElToritoManufacturerId::deserialize_unchecked(&mut (), &input[4..0x1c])?,
+      checksum: u16::from_le_bytes([input[0x1c], input[0x1d]]),
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoSectionEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    32
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      boot_indicator: input[0].into(),
+      boot_media_type: ElToritoBootMediaTypeExt::deserialize_unchecked(&mut (), &input[1..2])?,
+      load_segment: u16::from_le_bytes([input[2], input[3]]),
+      system_type: input[4],
+      sector_count: u16::from_le_bytes([input[6], input[7]]),
+      virtual_disk_location: u32::from_le_bytes(input[8..0x0c].try_into().unwrap()),
+      selection_criteria_type: input[0x0c].into(),
+      vendor_selection_criteria: input[0x0d..0x20].try_into().unwrap(),
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoSectionEntryExtension {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    32
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if input[0] != 0x44 {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "extension_indicator",
+        value: input[0],
+      });
+    }
+
+    Ok(Self {
+      extension_record_follows_indicator: ElToritoExtensionRecordFollowsIndicator::from_bits_retain(
+        input[1],
+      ),
+      vendor_unique_selection_criteria: input[2..0x20].try_into().unwrap(),
+    })
+  }
+}
+
+impl IsoDeserialize for ElToritoBootRecordVolumeDescriptor {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    2048
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    expect_descriptor_type(input, VolumeDescriptorType::BootRecord)?;
+
+    if &input[7..30] != b"EL TORITO SPECIFICATION" {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "boot_system_identifier",
+        value: input[7],
+      });
+    }
+
+    Ok(Self {
+      standard_identifier: StandardIdentifier::deserialize_unchecked(&mut (), &input[1..6])?,
+      version: VolumeDescriptorVersion::deserialize_unchecked(&mut (), &input[6..7])?,
+      boot_catalog_pointer: u32::from_le_bytes(input[0x47..0x4b].try_into().unwrap()),
+    })
+  }
+}
+
+impl IsoDeserialize for BootInfoTable {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    56
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let word = |offset: usize| u32::from_le_bytes(input[offset..offset + 4].try_into().unwrap());
+
+    Ok(Self {
+      primary_volume_descriptor: word(0),
+      boot_file_location: word(4),
+      boot_file_length: word(8),
+      checksum: word(12),
+    })
+  }
+}
+
+impl IsoDeserialize for MbrPartitionEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    16
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self {
+      boot_indicator: input[0],
+      start_chs: input[1..4].try_into().unwrap(),
+      partition_type: input[4],
+      end_chs: input[5..8].try_into().unwrap(),
+      start_lba: u32::from_le_bytes(input[8..12].try_into().unwrap()),
+      sector_count: u32::from_le_bytes(input[12..16].try_into().unwrap()),
+    })
+  }
+}
+
+impl IsoDeserialize for MbrPartitionTable {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    66
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if input[64..66] != [0x55, 0xAA] {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "mbr_signature",
+        value: input[64],
+      });
+    }
+
+    let mut entries = [MbrPartitionEntry::default(); 4];
+
+    for (ix, entry) in entries.iter_mut().enumerate() {
+      *entry = MbrPartitionEntry::deserialize_unchecked(&mut (), &input[ix * 16..(ix + 1) * 16])?;
+    }
+
+    Ok(Self { entries })
+  }
+}
+
+impl IsoDeserialize for Guid {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    16
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    Ok(Self(input[..16].try_into().unwrap()))
+  }
+}
+
+impl IsoDeserialize for GptHeader {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    GptHeader::SIZE as usize
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if &input[0..8] != GptHeader::SIGNATURE {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "gpt_signature",
+        value: input[0],
+      });
+    }
+
+    let u64_at = |offset: usize| u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap());
+    let u32_at = |offset: usize| u32::from_le_bytes(input[offset..offset + 4].try_into().unwrap());
+
+    Ok(Self {
+      header_crc32: u32_at(16),
+      my_lba: u64_at(24),
+      alternate_lba: u64_at(32),
+      first_usable_lba: u64_at(40),
+      last_usable_lba: u64_at(48),
+      disk_guid: Guid::deserialize_unchecked(&mut (), &input[56..72])?,
+      partition_entry_lba: u64_at(72),
+      number_of_partition_entries: u32_at(80),
+      size_of_partition_entry: u32_at(84),
+      partition_entry_array_crc32: u32_at(88),
+    })
+  }
+}
+
+impl IsoDeserialize for GptPartitionEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    GptPartitionEntry::SIZE as usize
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    let u64_at = |offset: usize| u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap());
+
+    Ok(Self {
+      partition_type_guid: Guid::deserialize_unchecked(&mut (), &input[0..16])?,
+      unique_partition_guid: Guid::deserialize_unchecked(&mut (), &input[16..32])?,
+      starting_lba: u64_at(32),
+      ending_lba: u64_at(40),
+      attributes: u64_at(48),
+      partition_name: std::array::from_fn(|ix| {
+        u16::from_le_bytes([input[56 + ix * 2], input[57 + ix * 2]])
+      }),
+    })
+  }
+}
+
+impl IsoDeserialize for ApmDriverDescriptorMap {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    8
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if &input[0..2] != ApmDriverDescriptorMap::SIGNATURE {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "apm_driver_descriptor_signature",
+        value: input[0],
+      });
+    }
+
+    Ok(Self {
+      block_size: u16::from_be_bytes([input[2], input[3]]),
+      block_count: u32::from_be_bytes(input[4..8].try_into().unwrap()),
+    })
+  }
+}
+
+impl IsoDeserialize for ApmPartitionEntry {
+  type Context = ();
+
+  fn extent((): &(), _: &[u8]) -> usize {
+    512
+  }
+
+  unsafe fn deserialize_unchecked((): &mut (), input: &[u8]) -> Result<Self> {
+    if &input[0..2] != ApmPartitionEntry::SIGNATURE {
+      return Err(IsoDeserializeError::InvalidValue {
+        field: "apm_partition_signature",
+        value: input[0],
+      });
+    }
+
+    let u32_at = |offset: usize| u32::from_be_bytes(input[offset..offset + 4].try_into().unwrap());
+
+    Ok(Self {
+      map_block_count: u32_at(4),
+      start_block: u32_at(8),
+      block_count: u32_at(12),
+      name: input[16..48].try_into().unwrap(),
+      partition_type: input[48..80].try_into().unwrap(),
+      data_start: u32_at(80),
+      data_count: u32_at(84),
+      status: u32_at(88),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::serialize::IsoSerialize;
+
+  fn round_trip<T>(
+    value: T,
+    serialize_cx: &mut <T as IsoSerialize>::Context,
+    deserialize_cx: &mut <T as IsoDeserialize>::Context,
+  ) where
+    T: IsoSerialize + IsoDeserialize + PartialEq + std::fmt::Debug,
+  {
+    let mut bytes = vec![0u8; value.extent()];
+
+    value.serialize(serialize_cx, &mut bytes).unwrap();
+
+    assert_eq!(
+      <T as IsoDeserialize>::extent(deserialize_cx, &bytes),
+      bytes.len()
+    );
+    assert_eq!(T::deserialize(deserialize_cx, &bytes).unwrap(), value);
+  }
+
+  fn digits_date() -> DigitsDate {
+    DigitsDate {
+      year: DigitsYear(2025),
+      month: DigitsMonth(10),
+      day: DigitsDay(31),
+      hour: DigitsHour(23),
+      minute: DigitsMinute(59),
+      second: DigitsSecond(58),
+      hundreths: DigitsHundreths(99),
+      gmt_offset: NumericalGmtOffset(-20),
+    }
+  }
+
+  fn numerical_date() -> NumericalDate {
+    NumericalDate {
+      years_since_1900: NumericalYear(125),
+      month: NumericalMonth(1),
+      day: NumericalDay(2),
+      hour: NumericalHour(3),
+      minute: NumericalMinute(4),
+      second: NumericalSecond(5),
+      gmt_offset: NumericalGmtOffset(36),
+    }
+  }
+
+  fn root_directory_record() -> RootDirectoryRecord {
+    RootDirectoryRecord {
+      extent_location: 20,
+      data_length: 2048,
+      recording_date: numerical_date(),
+      file_flags: FileFlags::DIRECTORY,
+      file_unit_size: 0,
+      interleave_gap_size: 0,
+      volume_sequence_number: 1,
+    }
+  }
+
+  #[test]
+  fn dates() {
+    round_trip(digits_date(), &mut (), &mut ());
+    round_trip(numerical_date(), &mut (), &mut ());
+  }
+
+  #[test]
+  fn unrecorded_digits_date() {
+    let mut bytes = [b'0'; 17];
+    bytes[16] = 0;
+
+    let date = DigitsDate::deserialize(&mut (), &bytes).unwrap();
+
+    assert_eq!(date.year, DigitsYear(0));
+    assert!(DigitsDate::deserialize(&mut (), b"20251031235958XX\0").is_err());
+  }
+
+  #[test]
+  fn scalars() {
+    round_trip(
+      FileFlags::DIRECTORY | FileFlags::MULTI_EXTENT,
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      Permissions::SYSTEM_READ | Permissions::OTHER_EXECUTE,
+      &mut (),
+      &mut (),
+    );
+    round_trip(VolumeFlags::UNREGISTERED_ESCAPE_SEQUENCES, &mut (), &mut ());
+    round_trip(OwnerIdentification(1000), &mut (), &mut ());
+    round_trip(GroupIdentification(100), &mut (), &mut ());
+    round_trip(RecordFormat::VariableLengthRecordsLsb, &mut (), &mut ());
+    round_trip(RecordAttributes::Other(7), &mut (), &mut ());
+    round_trip(ExtendedAttributeRecordVersion::Standard, &mut (), &mut ());
+    round_trip(StandardIdentifier::Cd001, &mut (), &mut ());
+    round_trip(StandardIdentifier::Other(*b"XYZ01"), &mut (), &mut ());
+    round_trip(VolumeDescriptorVersion::Other(2), &mut (), &mut ());
+    round_trip(FileStructureVersion::Standard, &mut (), &mut ());
+    round_trip(EscapeSequences::<32>::joliet_level_3(), &mut (), &mut ());
+    round_trip(
+      VariadicEscapeSequences(vec![0x25, 0x2f, 0x45]),
+      &mut (),
+      &mut (),
+    );
+  }
+
+  #[test]
+  fn identifiers() {
+    round_trip(
+      Identifier::system_identifier("LINUX").unwrap(),
+      &mut (),
+      &mut IdentifierKind::ACharacters,
+    );
+    round_trip(
+      Identifier::copyright_file_identifier("COPYING", InterchangeLevel::Level3).unwrap(),
+      &mut (),
+      &mut IdentifierKind::DCharacters,
+    );
+    round_trip(
+      Identifier::standard_file_identifier("readme.txt", InterchangeLevel::Level3).unwrap(),
+      &mut (),
+      &mut IdentifierKind::StandardFileIdentifier,
+    );
+  }
+
+  #[test]
+  fn volume_descriptors() {
+    round_trip(
+      PrimaryVolumeDescriptor {
+        standard_identifier: StandardIdentifier::Cd001,
+        version: VolumeDescriptorVersion::Standard,
+        system_identifier: Identifier::system_identifier("LINUX").unwrap(),
+        volume_identifier: Identifier::volume_identifier("VOLUME").unwrap(),
+        volume_space_size: 1234,
+        volume_set_size: 1,
+        volume_sequence_number: 1,
+        logical_block_size: 2048,
+        path_table_size: 10,
+        type_l_path_table_location: 19,
+        optional_type_l_path_table_location: 0,
+        type_m_path_table_location: 20,
+        optional_type_m_path_table_location: 0,
+        root_directory_record: root_directory_record(),
+        volume_set_identifier: Identifier::volume_set_identifier("SET").unwrap(),
+        publisher_identifier: Identifier::publisher_identifier("PUBLISHER").unwrap(),
+        data_preparer_identifier: Identifier::data_preparer_identifier("PREPARER").unwrap(),
+        application_identifier: Identifier::application_identifier("ISOFS").unwrap(),
+        copyright_file_identifier: Identifier::copyright_file_identifier(
+          "COPYING",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        abstract_file_identifier: Identifier::abstract_file_identifier(
+          "ABSTRACT",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        bibliographic_file_identifier: Identifier::bibliographic_file_identifier(
+          "BIBLIO",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        creation_date: digits_date(),
+        modification_date: digits_date(),
+        expiration_date: digits_date(),
+        effective_date: digits_date(),
+        file_structure_version: FileStructureVersion::Standard,
+        application_use: [7; 512],
+      },
+      &mut (),
+      &mut (),
+    );
+
+    round_trip(
+      SupplementaryVolumeDescriptor {
+        standard_identifier: StandardIdentifier::Cd001,
+        version: VolumeDescriptorVersion::Standard,
+        volume_flags: VolumeFlags::empty(),
+        system_identifier: Identifier::system_identifier("LINUX").unwrap(),
+        volume_identifier: Identifier::volume_identifier("VOLUME").unwrap(),
+        volume_space_size: 1234,
+        escape_sequences: EscapeSequences::joliet_level_3(),
+        volume_set_size: 1,
+        volume_sequence_number: 1,
+        logical_block_size: 2048,
+        path_table_size: 10,
+        type_l_path_table_location: 21,
+        optional_type_l_path_table_location: 0,
+        type_m_path_table_location: 22,
+        optional_type_m_path_table_location: 0,
+        root_directory_record: root_directory_record(),
+        volume_set_identifier: Identifier::volume_set_identifier("SET").unwrap(),
+        publisher_identifier: Identifier::publisher_identifier("PUBLISHER").unwrap(),
+        data_preparer_identifier: Identifier::data_preparer_identifier("PREPARER").unwrap(),
+        application_identifier: Identifier::application_identifier("ISOFS").unwrap(),
+        copyright_file_identifier: Identifier::copyright_file_identifier(
+          "",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        abstract_file_identifier: Identifier::abstract_file_identifier(
+          "",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        bibliographic_file_identifier: Identifier::bibliographic_file_identifier(
+          "",
+          InterchangeLevel::Level3,
+        )
+        .unwrap(),
+        creation_date: digits_date(),
+        modification_date: digits_date(),
+        expiration_date: digits_date(),
+        effective_date: digits_date(),
+        file_structure_version: FileStructureVersion::Standard,
+        application_use: [0; 512],
+      },
+      &mut (),
+      &mut (),
+    );
+
+    round_trip(
+      VolumePartitionDescriptor {
+        standard_identifier: StandardIdentifier::Cd001,
+        version: VolumeDescriptorVersion::Standard,
+        system_identifier: Identifier::system_identifier("LINUX").unwrap(),
+        volume_partition_identifier: Identifier::volume_identifier("PARTITION").unwrap(),
+        volume_partition_location: 100,
+        volume_partition_size: 200,
+      },
+      &mut (),
+      &mut (),
+    );
+
+    round_trip(VolumeDescriptorSetTerminator, &mut (), &mut ());
+  }
+
+  #[test]
+  fn unexpected_volume_descriptor_type() {
+    let mut bytes = [0u8; 2048];
+
+    VolumeDescriptorSetTerminator
+      .serialize(&mut (), &mut bytes)
+      .unwrap();
+
+    assert!(matches!(
+      PrimaryVolumeDescriptor::deserialize(&mut (), &bytes),
+      Err(IsoDeserializeError::InvalidValue { value: 255, .. })
+    ));
+  }
+
+  #[test]
+  fn directory_records() {
+    let record = |file_flags, file_identifier: Identifier| DirectoryRecord {
+      extended_attribute_length: 0,
+      extent_location: 42,
+      data_length: 4096,
+      recording_date: numerical_date(),
+      file_flags,
+      file_unit_size: 0,
+      interleave_gap_size: 0,
+      volume_sequence_number: 1,
+      file_identifier_length: file_identifier.extent() as u8,
+      file_identifier,
+      system_use: vec![],
+    };
+
+    round_trip(
+      record(
+        FileFlags::empty(),
+        Identifier::standard_file_identifier("hello.txt", InterchangeLevel::Level3).unwrap(),
+      ),
+      &mut (),
+      &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+    );
+    round_trip(
+      record(
+        FileFlags::DIRECTORY,
+        Identifier::standard_directory_identifier("docs", InterchangeLevel::Level3).unwrap(),
+      ),
+      &mut (),
+      &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+    );
+    round_trip(
+      record(FileFlags::DIRECTORY, Identifier::current_directory()),
+      &mut (),
+      &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+    );
+    round_trip(
+      record(FileFlags::DIRECTORY, Identifier::parent_directory()),
+      &mut (),
+      &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+    );
+    round_trip(
+      record(
+        FileFlags::empty(),
+        Identifier::joliet_file_identifier("Hello, wörld.txt", JolietLevel::Level3).unwrap(),
+      ),
+      &mut (),
+      &mut CompatibilityMode::Joliet(JolietLevel::Level3).into(),
+    );
+    round_trip(
+      DirectoryRecord {
+        system_use: vec![
+          SystemUseEntry::SharingProtocolIndicator { bytes_skipped: 0 },
+          SystemUseEntry::ContinuationArea {
+            block_location: 30,
+            offset: 0,
+            length: 237,
+          },
+          SystemUseEntry::Terminator,
+        ],
+        ..record(FileFlags::DIRECTORY, Identifier::current_directory())
+      },
+      &mut (),
+      &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+    );
+    round_trip(root_directory_record(), &mut (), &mut ());
+  }
+
+  #[test]
+  fn truncated_directory_record() {
+    let mut bytes = [0u8; 40];
+
+    bytes[0] = 34;
+    bytes[32] = 5;
+
+    assert!(matches!(
+      DirectoryRecord::deserialize(
+        &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+        &bytes
+      ),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "file_identifier_length",
+        ..
+      })
+    ));
+    assert!(matches!(
+      DirectoryRecord::deserialize(
+        &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+        &bytes[..20]
+      ),
+      Err(IsoDeserializeError::InputBufferTooSmall { .. })
+    ));
+  }
+
+  #[test]
+  fn skipped_system_use_bytes() {
+    let file_identifier =
+      Identifier::standard_file_identifier("hello.txt", InterchangeLevel::Level3).unwrap();
+    let record = DirectoryRecord {
+      extended_attribute_length: 0,
+      extent_location: 20,
+      data_length: 2048,
+      recording_date: numerical_date(),
+      file_flags: FileFlags::empty(),
+      file_unit_size: 0,
+      interleave_gap_size: 0,
+      volume_sequence_number: 1,
+      file_identifier_length: file_identifier.extent() as u8,
+      file_identifier,
+      system_use: vec![],
+    };
+    let mut bytes = vec![0u8; record.extent()];
+
+    record.serialize(&mut (), &mut bytes).unwrap();
+
+    // CD-XA system use information precedes the SUSP entries.
+    bytes.extend([
+      0x03, 0xE8, 0x00, 0x64, 0x0D, 0x55, b'X', b'A', 0, 0, 0, 0, 0, 0,
+    ]);
+    bytes.extend(b"ES\x05\x01\x02");
+    bytes[0] = bytes.len() as u8;
+
+    let mut cx = DirectoryRecordContext {
+      compatibility_mode: CompatibilityMode::Standard(InterchangeLevel::Level3),
+      bytes_skipped: 14,
+    };
+
+    assert_eq!(
+      DirectoryRecord::deserialize(&mut cx, &bytes).unwrap(),
+      DirectoryRecord {
+        system_use: vec![SystemUseEntry::ExtensionSelector { sequence: 2 }],
+        ..record
+      }
+    );
+    assert!(matches!(
+      DirectoryRecord::deserialize(
+        &mut CompatibilityMode::Standard(InterchangeLevel::Level3).into(),
+        &bytes
+      ),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "system_use_entry_length",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn path_table_records() {
+    let record = |directory_identifier: Identifier| PathTableRecord {
+      directory_identifier_length: directory_identifier.extent().max(1) as u8,
+      extended_attribute_record_length: 0,
+      extent_location: 0x01020304,
+      parent_directory_number: 0x0102,
+      directory_identifier,
+    };
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+      let mut serialize_cx = endianness;
+      let mut deserialize_cx = endianness;
+
+      round_trip(
+        record(Identifier::root_directory()),
+        &mut serialize_cx,
+        &mut deserialize_cx,
+      );
+      round_trip(
+        record(
+          Identifier::standard_directory_identifier("docs", InterchangeLevel::Level3).unwrap(),
+        ),
+        &mut serialize_cx,
+        &mut deserialize_cx,
+      );
+      round_trip(
+        record(Identifier::standard_directory_identifier("abc", InterchangeLevel::Level3).unwrap()),
+        &mut serialize_cx,
+        &mut deserialize_cx,
+      );
+    }
+  }
+
+  #[test]
+  fn extended_attribute_record() {
+    round_trip(
+      ExtendedAttributeRecord {
+        owner_identification: OwnerIdentification(1000),
+        group_identification: GroupIdentification(100),
+        permissions: Permissions::PERMISSION_1 | Permissions::USER_READ,
+        file_creation_date: digits_date(),
+        file_modification_date: digits_date(),
+        file_expiration_date: digits_date(),
+        file_effective_date: digits_date(),
+        record_format: RecordFormat::StructureNotSpecified,
+        record_attributes: RecordAttributes::PreceededByLfcFollowedByCrc,
+        record_length: 0,
+        system_identifier: Identifier::system_identifier("LINUX").unwrap(),
+        system_use: [3; 64],
+        extended_attribute_record_version: ExtendedAttributeRecordVersion::Standard,
+        application_use: vec![1, 2, 3, 4, 5],
+        escape_sequences: VariadicEscapeSequences(vec![0x25, 0x2f, 0x40]),
+      },
+      &mut (),
+      &mut (),
+    );
+  }
+
+  #[test]
+  fn el_torito() {
+    round_trip(
+      ElToritoValidationEntry {
+        header_id: ElToritoHeaderId::Standard,
+        platform_id: ElToritoPlatformId::X86,
+        manufacturer_id: ElToritoManufacturerId(*b"ISOFS\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"),
+        checksum: 0xBEEF,
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ElToritoInitialSectionEntry {
+        boot_indicator: ElToritoBootIndicator::Bootable,
+        boot_media_type: ElToritoBootMediaType(0),
+        load_segment: 0x07C0,
+        system_type: 0,
+        sector_count: 4,
+        virtual_disk_location: 1000,
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ElToritoSectionHeaderEntry {
+        header_indicator: ElToritoHeaderIndicator::FinalHeader,
+        platform_id: ElToritoPlatformId::Mac,
+        succeeding_section_entries: 2,
+        section_id: ElToritoSectionId([9; 28]),
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ElToritoSectionEntry {
+        boot_indicator: ElToritoBootIndicator::NonBootable,
+        boot_media_type: ElToritoBootMediaTypeExt {
+          emulation_type: ElToritoEmulationType::HardDisk,
+          continuation_entry_follows: true,
+          contains_atapi_driver: false,
+          contains_scsi_drivers: true,
+        },
+        load_segment: 0,
+        system_type: 0x0c,
+        sector_count: 1,
+        virtual_disk_location: 2000,
+        selection_criteria_type: ElToritoSelectionCriteriaType::LanguageAndVersionInformation,
+        vendor_selection_criteria: [4; 19],
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ElToritoSectionEntryExtension {
+        extension_record_follows_indicator:
+          ElToritoExtensionRecordFollowsIndicator::EXTENSION_RECORD_FOLLOWS,
+        vendor_unique_selection_criteria: [5; 30],
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      BootInfoTable {
+        primary_volume_descriptor: 16,
+        boot_file_location: 40,
+        boot_file_length: 4096,
+        checksum: 0xDEADBEEF,
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ElToritoBootRecordVolumeDescriptor {
+        standard_identifier: StandardIdentifier::Cd001,
+        version: VolumeDescriptorVersion::Standard,
+        boot_catalog_pointer: 0x12345678,
+      },
+      &mut (),
+      &mut (),
+    );
+  }
+
+  #[test]
+  fn mbr_partition_table() {
+    let mut table = MbrPartitionTable::default();
+
+    table.entries[1] = MbrPartitionEntry {
+      boot_indicator: 0x80,
+      start_chs: [0xFE, 0xFF, 0xFF],
+      partition_type: 0xEF,
+      end_chs: [0xFE, 0xFF, 0xFF],
+      start_lba: 64,
+      sector_count: 2880,
+    };
+
+    round_trip(table, &mut (), &mut ());
+
+    assert!(matches!(
+      MbrPartitionTable::deserialize(&mut (), &[0u8; 66]),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "mbr_signature",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn gpt() {
+    assert_eq!(
+      Guid::EFI_SYSTEM_PARTITION.0,
+      [
+        0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9,
+        0x3B
+      ]
+    );
+
+    round_trip(
+      GptHeader {
+        header_crc32: 0xDEAD_BEEF,
+        my_lba: 1,
+        alternate_lba: 4095,
+        first_usable_lba: 64,
+        last_usable_lba: 4062,
+        disk_guid: Guid([7; 16]),
+        partition_entry_lba: 2,
+        number_of_partition_entries: 128,
+        size_of_partition_entry: 128,
+        partition_entry_array_crc32: 0xCAFE_F00D,
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      GptPartitionEntry {
+        partition_type_guid: Guid::EFI_SYSTEM_PARTITION,
+        unique_partition_guid: Guid([9; 16]),
+        starting_lba: 64,
+        ending_lba: 127,
+        attributes: 1,
+        partition_name: GptPartitionEntry::encode_name("EFI System").unwrap(),
+      },
+      &mut (),
+      &mut (),
+    );
+
+    assert!(GptPartitionEntry::encode_name(&"x".repeat(37)).is_none());
+    assert!(matches!(
+      GptHeader::deserialize(&mut (), &[0u8; 92]),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "gpt_signature",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn apm() {
+    round_trip(
+      ApmDriverDescriptorMap {
+        block_size: 2048,
+        block_count: 1234,
+      },
+      &mut (),
+      &mut (),
+    );
+    round_trip(
+      ApmPartitionEntry {
+        map_block_count: 2,
+        start_block: 100,
+        block_count: 16,
+        name: ApmPartitionEntry::encode_str("EFI").unwrap(),
+        partition_type: ApmPartitionEntry::encode_str("Apple_HFS").unwrap(),
+        data_start: 0,
+        data_count: 16,
+        status: 0x33,
+      },
+      &mut (),
+      &mut (),
+    );
+
+    assert!(ApmPartitionEntry::encode_str(&"x".repeat(33)).is_none());
+    assert!(matches!(
+      ApmPartitionEntry::deserialize(&mut (), &[0u8; 512]),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "apm_partition_signature",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn system_use_entries() {
+    for entry in [
+      SystemUseEntry::SharingProtocolIndicator { bytes_skipped: 0 },
+      SystemUseEntry::ContinuationArea {
+        block_location: 1234,
+        offset: 56,
+        length: 78,
+      },
+      SystemUseEntry::Padding { length: 3 },
+      SystemUseEntry::Terminator,
+      SystemUseEntry::ExtensionsReference {
+        version: 1,
+        identifier: "RRIP_1991A".into(),
+        descriptor: "THE ROCK RIDGE INTERCHANGE PROTOCOL".into(),
+        source: "SEE PUBLISHER".into(),
+      },
+      SystemUseEntry::ExtensionSelector { sequence: 2 },
+      SystemUseEntry::PosixFileAttributes {
+        mode: file_type::REGULAR | 0o755,
+        links: 1,
+        uid: 1000,
+        gid: 100,
+        serial: None,
+      },
+      SystemUseEntry::PosixFileAttributes {
+        mode: file_type::DIRECTORY | 0o555,
+        links: 2,
+        uid: 0,
+        gid: 0,
+        serial: Some(42),
+      },
+      SystemUseEntry::DeviceNumber {
+        high: 0,
+        low: 0x0801,
+      },
+      SystemUseEntry::TimeStamps(TimeStamps::Short(RecordedTimeStamps {
+        modify: Some(numerical_date()),
+        access: Some(numerical_date()),
+        ..Default::default()
+      })),
+      SystemUseEntry::TimeStamps(TimeStamps::Long(RecordedTimeStamps {
+        creation: Some(digits_date()),
+        effective: Some(digits_date()),
+        ..Default::default()
+      })),
+      SystemUseEntry::AlternateName {
+        flags: AlternateNameFlags::CONTINUE,
+        content: b"Hello, ".to_vec(),
+      },
+      SystemUseEntry::SymbolicLink {
+        continues: false,
+        components: vec![
+          SymbolicLinkComponent {
+            flags: SymbolicLinkComponentFlags::ROOT,
+            content: vec![],
+          },
+          SymbolicLinkComponent {
+            flags: SymbolicLinkComponentFlags::empty(),
+            content: b"usr".to_vec(),
+          },
+          SymbolicLinkComponent {
+            flags: SymbolicLinkComponentFlags::PARENT,
+            content: vec![],
+          },
+        ],
+      },
+      SystemUseEntry::ChildLink {
+        block_location: 0x1234,
+      },
+      SystemUseEntry::ParentLink { block_location: 42 },
+      SystemUseEntry::Relocated,
+      SystemUseEntry::CompressedFile {
+        algorithm: *b"pz",
+        header_size: 4,
+        block_size_log2: 15,
+        uncompressed_size: 100_000,
+      },
+      SystemUseEntry::Unknown {
+        signature: *b"XX",
+        version: 1,
+        data: vec![1, 2, 3],
+      },
+    ] {
+      round_trip(entry, &mut (), &mut ());
+    }
+
+    assert!(matches!(
+      SystemUseEntry::deserialize(&mut (), b"SP\x07\x01\xBE\xEE\x00"),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "sharing_protocol_check_bytes",
+        ..
+      })
+    ));
+    assert!(matches!(
+      SystemUseEntry::deserialize(&mut (), b"SL\x08\x01\x00\x00\x05x"),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "symbolic_link_component_length",
+        ..
+      })
+    ));
+    assert!(matches!(
+      SystemUseEntry::deserialize(&mut (), b"ST\x05\x01\x00"),
+      Err(IsoDeserializeError::InvalidValue {
+        field: "system_use_entry_length",
+        ..
+      })
+    ));
+
+    for signature in [b"PD", b"ST", b"SP", b"XX"] {
+      for length in 0..4 {
+        let input = [signature[0], signature[1], length, 1];
+
+        assert!(matches!(
+          system_use_area(&input),
+          Err(IsoDeserializeError::InvalidValue {
+            field: "system_use_entry_length",
+            value,
+          }) if value == length
+        ));
+      }
+    }
+  }
+}
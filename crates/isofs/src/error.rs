@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
-use crate::serialize::IsoSerializeError;
+use crate::{
+  deserialize::IsoDeserializeError, serialize::IsoSerializeError, spec::IdentifierError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -10,6 +12,34 @@ pub enum Error {
   WalkDir(#[from] walkdir::Error),
   #[error("Not a file: {0}")]
   NotAFile(PathBuf),
+  #[error("Not in the root directory: {0}")]
+  NotInRootDirectory(PathBuf),
+  #[error("File too large for a single extent: {0}")]
+  FileTooLarge(PathBuf),
+  #[error("Directory too deep to record without Rock Ridge: {0}")]
+  HierarchyTooDeep(PathBuf),
+  #[error("Symbolic link target can't be recorded with Rock Ridge: {0}")]
+  InvalidSymbolicLinkTarget(PathBuf),
+  #[error("Invalid name {0}: {1}")]
+  InvalidName(PathBuf, IdentifierError),
+  #[error("Invalid identifier: {0}")]
+  Identifier(#[from] IdentifierError),
   #[error("ISO serialization error: {0}")]
-  IsoSerialize(#[from] IsoSerializeError)
+  IsoSerialize(#[from] IsoSerializeError),
+  #[error("ISO deserialization error: {0}")]
+  IsoDeserialize(#[from] IsoDeserializeError),
+  #[error("Invalid volume descriptor at LBA {0}")]
+  InvalidVolumeDescriptor(u64),
+  #[error("No primary volume descriptor found")]
+  MissingPrimaryVolumeDescriptor,
+  #[error("Not found: {0}")]
+  NotFound(String),
+  #[error("Not a directory: {0}")]
+  NotADirectory(String),
+  #[error("Is a directory: {0}")]
+  IsADirectory(String),
+  #[error("Invalid boot configuration: {0}")]
+  InvalidBootConfiguration(String),
+  #[error("Invalid zisofs options: {0}")]
+  InvalidZisofsOptions(String),
 }
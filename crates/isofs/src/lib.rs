@@ -1,6 +1,9 @@
-pub mod writer;
+pub mod deserialize;
 pub mod error;
+pub mod mangle;
 pub mod path;
 pub(crate) mod prelude;
+pub mod reader;
 pub mod serialize;
 pub mod spec;
+pub mod writer;
@@ -0,0 +1,325 @@
+//! Maps host names onto identifiers that are valid within a hierarchy, mangling the names that
+//! aren't and resolving the collisions this causes within a directory.
+
+use crate::spec::{
+  CompatibilityMode, Identifier, IdentifierError, IdentifierKind, InterchangeLevel,
+};
+use std::collections::HashSet;
+
+/// Identifiers of the entries of a directory, given their names along with whether they name a
+/// directory. The identifiers are returned in the same order as `names`.
+///
+/// Names valid as is keep their identifier, unless an earlier name already claimed it. The others
+/// are mangled, replacing invalid characters with `_` and truncating the name while keeping its
+/// extension (e.g. `Long File Name.text` becomes `LONG_FIL.TEX` at level 1). Colliding names
+/// are suffixed with `_1`, `_2` and so on, as d-characters lack the usual `~`. The result only
+/// depends on the order of `names`, and no identifier matches one of the `reserved` names.
+///
+/// Names that can't be mangled into any identifier, such as empty ones, keep the error of their
+/// mangled form.
+pub fn identifiers(
+  names: &[(&str, bool)],
+  reserved: &[&str],
+  compatibility_mode: CompatibilityMode,
+) -> Vec<Result<Identifier, IdentifierError>> {
+  let mut taken = reserved
+    .iter()
+    .filter_map(|name| identifier(name, true, compatibility_mode).ok())
+    .map(|x| key(&x))
+    .collect::<HashSet<_>>();
+
+  // Names valid as is claim their identifiers first, so mangled names never displace them.
+  let identifiers = names
+    .iter()
+    .map(|&(name, directory)| {
+      identifier(name, directory, compatibility_mode)
+        .ok()
+        .filter(|x| taken.insert(key(x)))
+    })
+    .collect::<Vec<_>>();
+
+  names
+    .iter()
+    .zip(identifiers)
+    .map(|(&(name, directory), claimed)| match claimed {
+      Some(x) => Ok(x),
+      // Every name claims a single identifier, so one of these suffixes is free.
+      None => (0..=names.len() + reserved.len())
+        .map(|suffix| {
+          identifier(
+            &mangle(name, directory, suffix, compatibility_mode),
+            directory,
+            compatibility_mode,
+          )
+        })
+        .find(|x| x.as_ref().map_or(true, |x| taken.insert(key(x))))
+        .expect("One of the suffixes should be free"),
+    })
+    .collect()
+}
+
+fn identifier(
+  name: &str,
+  directory: bool,
+  compatibility_mode: CompatibilityMode,
+) -> Result<Identifier, IdentifierError> {
+  match directory {
+    true => Identifier::directory_identifier(name, compatibility_mode),
+    false => Identifier::file_identifier(name, compatibility_mode),
+  }
+}
+
+/// Name readers know an identifier by, without its version number or the separator of an empty
+/// extension. Joliet names differing in case alone collide too, as Windows ignores case.
+fn key(identifier: &Identifier) -> String {
+  let name = identifier.to_string();
+  let name = name.split(';').next().unwrap_or_default();
+  let name = name.strip_suffix('.').unwrap_or(name);
+
+  match identifier.kind() {
+    IdentifierKind::JolietFileIdentifier | IdentifierKind::JolietDirectoryIdentifier => {
+      name.to_uppercase()
+    }
+    _ => name.to_string(),
+  }
+}
+
+/// `name` restricted to the characters and length of identifiers of `compatibility_mode`, its
+/// stem ending with `_suffix` unless `suffix` is zero.
+fn mangle(
+  name: &str,
+  directory: bool,
+  suffix: usize,
+  compatibility_mode: CompatibilityMode,
+) -> String {
+  // Leading dots belong to the stem, as in `.bashrc`.
+  let (stem, extension) = match name.rfind('.') {
+    Some(ix) if !directory && ix > 0 => (&name[..ix], Some(&name[ix + 1..])),
+    _ => (name, None),
+  };
+  let replace = |s: &str| {
+    s.chars()
+      .map(|c| replace(c, compatibility_mode))
+      .collect::<String>()
+  };
+  let length = |s: &str| match compatibility_mode {
+    CompatibilityMode::Joliet(_) => s.chars().count(),
+    CompatibilityMode::Standard(_) => s.len(),
+  };
+
+  // Lengths of a directory identifier, and the file identifier's stem and extension together.
+  // The separator only counts towards the latter past ISO 9660 levels 1 to 3.
+  let (max_length, separator) = match compatibility_mode {
+    CompatibilityMode::Joliet(_) => (64, 1),
+    CompatibilityMode::Standard(InterchangeLevel::Iso9660_1999) => (207, 1),
+    CompatibilityMode::Standard(InterchangeLevel::Level1) if !directory => (11, 0),
+    CompatibilityMode::Standard(InterchangeLevel::Level2 | InterchangeLevel::Level3)
+      if !directory =>
+    {
+      (30, 0)
+    }
+    CompatibilityMode::Standard(level) => (level.max_directory_identifier_length(), 0),
+  };
+
+  // Extensions leave room for at least 8 characters of the stem, which level 1 always has.
+  let max_extension = match compatibility_mode {
+    CompatibilityMode::Standard(InterchangeLevel::Level1) => 3,
+    _ => max_length - separator - 8,
+  };
+  let extension = extension.map(|x| truncate(&replace(x), max_extension, length));
+  let max_stem = match (compatibility_mode, &extension) {
+    (CompatibilityMode::Standard(InterchangeLevel::Level1), _) if !directory => 8,
+    (_, Some(extension)) => max_length - separator - length(extension),
+    (_, None) => max_length,
+  };
+  let suffix = match suffix {
+    0 => String::new(),
+    suffix => format!("_{suffix}"),
+  };
+
+  let mut mangled = truncate(&replace(stem), max_stem - suffix.len(), length);
+
+  mangled.push_str(&suffix);
+
+  if let Some(extension) = extension {
+    mangled.push('.');
+    mangled.push_str(&extension);
+  }
+
+  mangled
+}
+
+/// Replacement of `c` within identifiers of `compatibility_mode`, `_` if it's not permitted.
+fn replace(c: char, compatibility_mode: CompatibilityMode) -> char {
+  match compatibility_mode {
+    // Joliet identifiers are recorded in UCS-2, which lacks characters past the BMP.
+    CompatibilityMode::Joliet(level) if level.permits(c) && c <= '\u{FFFF}' => c,
+    CompatibilityMode::Standard(InterchangeLevel::Iso9660_1999)
+      if !c.is_control() && c != '/' && c != ';' =>
+    {
+      c
+    }
+    CompatibilityMode::Standard(
+      InterchangeLevel::Level1 | InterchangeLevel::Level2 | InterchangeLevel::Level3,
+    ) if c.is_ascii() && Identifier::D_CHARACTERS.contains(&(c.to_ascii_uppercase() as u8)) => {
+      c.to_ascii_uppercase()
+    }
+    _ => '_',
+  }
+}
+
+/// Longest prefix of `s` no longer than `max`, as measured by `length`.
+fn truncate(s: &str, max: usize, length: impl Fn(&str) -> usize) -> String {
+  let end = s
+    .char_indices()
+    .map(|(ix, c)| ix + c.len_utf8())
+    .take_while(|&end| length(&s[..end]) <= max)
+    .last()
+    .unwrap_or(0);
+
+  s[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::spec::JolietLevel;
+
+  fn names(names: &[(&str, bool)], compatibility_mode: CompatibilityMode) -> Vec<String> {
+    identifiers(names, &[], compatibility_mode)
+      .iter()
+      .map(|x| x.as_ref().unwrap().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn level_1() {
+    let level_1 = CompatibilityMode::Standard(InterchangeLevel::Level1);
+
+    assert_eq!(
+      names(
+        &[
+          ("readme.txt", false),
+          ("Long File Name.text", false),
+          ("archive.tar.gz", false),
+          (".bashrc", false),
+          ("Makefile", false),
+          ("node_modules", true),
+          ("café", true),
+        ],
+        level_1,
+      ),
+      [
+        "README.TXT;1",
+        "LONG_FIL.TEX;1",
+        "ARCHIVE_.GZ;1",
+        "_BASHRC.;1",
+        "MAKEFILE.;1",
+        "NODE_MOD",
+        "CAF_",
+      ]
+    );
+  }
+
+  #[test]
+  fn collisions() {
+    let level_1 = CompatibilityMode::Standard(InterchangeLevel::Level1);
+
+    // Valid names keep their identifiers, even when listed after the names they collide with.
+    assert_eq!(
+      names(
+        &[
+          ("longfilename1.txt", false),
+          ("longfilename2.txt", false),
+          ("LONGFILE.TXT", false),
+          ("longfi_1.txt", false),
+          ("LongFile.txt", false),
+        ],
+        level_1,
+      ),
+      [
+        "LONGFI_2.TXT;1",
+        "LONGFI_3.TXT;1",
+        "LONGFILE.TXT;1",
+        "LONGFI_1.TXT;1",
+        "LONGFI_4.TXT;1",
+      ]
+    );
+
+    // Files without an extension collide with directories of the same name.
+    assert_eq!(
+      names(&[("docs", true), ("docs", false)], level_1),
+      ["DOCS", "DOCS_1.;1"]
+    );
+
+    assert_eq!(
+      identifiers(&[("rr_moved", true)], &["rr_moved"], level_1)[0]
+        .as_ref()
+        .unwrap()
+        .to_string(),
+      "RR_MOV_1"
+    );
+  }
+
+  #[test]
+  fn unmangleable() {
+    let level_1 = CompatibilityMode::Standard(InterchangeLevel::Level1);
+
+    // Nothing is left of an empty name to mangle.
+    assert_eq!(
+      identifiers(&[("", false), ("readme", false)], &[], level_1)
+        .into_iter()
+        .map(|x| x.map(|x| x.to_string()))
+        .collect::<Vec<_>>(),
+      [
+        Err(IdentifierError::Empty {
+          kind: IdentifierKind::StandardFileIdentifier
+        }),
+        Ok("README.;1".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn level_3() {
+    let level_3 = CompatibilityMode::Standard(InterchangeLevel::Level3);
+    let long = format!("{}.tar.gz", "x".repeat(40));
+
+    assert_eq!(
+      names(&[(&long, false), (&"y".repeat(40), true)], level_3),
+      [format!("{}.GZ;1", "X".repeat(28)), "Y".repeat(31),]
+    );
+  }
+
+  #[test]
+  fn joliet_and_iso9660_1999() {
+    let joliet = CompatibilityMode::Joliet(JolietLevel::Level3);
+    let long = format!("{}.txt", "ü".repeat(70));
+
+    assert_eq!(
+      names(
+        &[("a:b*c.txt", false), (&long, false), ("🦀.rs", false)],
+        joliet
+      ),
+      [
+        "a_b_c.txt".to_string(),
+        format!("{}.txt", "ü".repeat(60)),
+        "_.rs".to_string(),
+      ]
+    );
+
+    // Windows takes names differing in case alone for the same one.
+    assert_eq!(
+      names(&[("Readme.txt", false), ("README.TXT", false)], joliet),
+      ["Readme.txt", "README_1.TXT"]
+    );
+
+    let iso9660_1999 = CompatibilityMode::Standard(InterchangeLevel::Iso9660_1999);
+    let long = format!("{}.txt", "ü".repeat(110));
+
+    assert_eq!(
+      names(&[("a;b.txt", false), (&long, false)], iso9660_1999),
+      ["a_b.txt".to_string(), format!("{}.txt", "ü".repeat(101)),]
+    );
+  }
+}
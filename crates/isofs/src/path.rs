@@ -7,6 +7,10 @@ impl IsoPath {
     unsafe { &*(s.as_ref() as *const str as *const IsoPath) }
   }
 
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
   /// Returns the components of this path as an iterator.
   pub fn components<'a>(&'a self) -> Components<'a> {
     Components { path: &self.0 }
@@ -19,6 +23,24 @@ impl AsRef<IsoPath> for str {
   }
 }
 
+impl AsRef<IsoPath> for String {
+  fn as_ref(&self) -> &IsoPath {
+    IsoPath::new(self)
+  }
+}
+
+impl AsRef<IsoPath> for IsoPath {
+  fn as_ref(&self) -> &IsoPath {
+    self
+  }
+}
+
+impl std::fmt::Display for IsoPath {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
 pub struct Components<'a> {
   path: &'a str,
 }
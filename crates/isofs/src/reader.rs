@@ -0,0 +1,784 @@
+//! High-level interface for reading existing ISO 9660 filesystems.
+
+use crate::{
+  deserialize::{self, IsoDeserialize},
+  path::IsoPath,
+  spec,
+};
+
+use super::prelude::*;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Volume descriptors always occupy a full 2048 byte logical sector.
+const VOLUME_DESCRIPTOR_SIZE: u64 = 2048;
+
+/// Descriptors start at LBA 16. System area is LBA 0..=15.
+const VOLUME_DESCRIPTOR_SET_LBA: u64 = 16;
+
+/// Identifiers of the `ER` entry announcing the Rock Ridge Interchange Protocol, by RRIP version.
+const ROCK_RIDGE_IDENTIFIERS: [&str; 3] = ["RRIP_1991A", "IEEE_P1282", "IEEE_1282"];
+
+/// Contiguous run of an entry's data within the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+  /// Logical block the data starts at, past any extended attribute record.
+  pub lba: u32,
+  /// Length of the data in bytes.
+  pub length: u32,
+}
+
+impl Extent {
+  fn from_record(record: &spec::DirectoryRecord) -> Self {
+    Self {
+      lba: record.extent_location + record.extended_attribute_length as u32,
+      length: record.data_length,
+    }
+  }
+}
+
+/// POSIX metadata of an entry, as recorded by the Rock Ridge `PX`, `PN` and `TF` entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixMetadata {
+  /// File type and permission bits, see [spec::file_type].
+  pub mode: u32,
+  pub links: u32,
+  pub uid: u32,
+  pub gid: u32,
+  /// File serial (inode) number, only recorded since RRIP 1.12.
+  pub serial: Option<u32>,
+  /// Device number (`st_rdev`) of block and character devices.
+  pub device: Option<u64>,
+  pub timestamps: Option<spec::TimeStamps>,
+}
+
+/// Represents a file or directory found within an ISO image.
+#[derive(Debug, Clone)]
+pub struct Entry {
+  name: String,
+  record: spec::DirectoryRecord,
+  system_use: Vec<spec::SystemUseEntry>,
+  extents: Vec<Extent>,
+  directory: bool,
+}
+
+impl Entry {
+  /// Creates the entry of `record`, whose System Use area, continuation areas included, holds
+  /// `system_use`. Rock Ridge names replace the ISO 9660 ones if `rock_ridge` is set.
+  fn new(
+    record: spec::DirectoryRecord,
+    system_use: Vec<spec::SystemUseEntry>,
+    rock_ridge: bool,
+  ) -> Self {
+    let mut alternate_name = None::<Vec<u8>>;
+
+    if rock_ridge {
+      for entry in &system_use {
+        if let spec::SystemUseEntry::AlternateName { flags, content } = entry {
+          if !flags.intersects(spec::AlternateNameFlags::CURRENT | spec::AlternateNameFlags::PARENT)
+          {
+            alternate_name.get_or_insert_default().extend(content);
+          }
+        }
+      }
+    }
+
+    let name = match alternate_name {
+      Some(name) => String::from_utf8_lossy(&name).into_owned(),
+      None => {
+        let mut name = record.file_identifier.to_string();
+
+        // Strip the version suffix (e.g. `;1`) and the separator of an empty extension.
+        if !record.file_flags.contains(spec::FileFlags::DIRECTORY) {
+          if let Some(ix) = name.rfind(';') {
+            name.truncate(ix);
+          }
+
+          if name.ends_with('.') {
+            name.pop();
+          }
+        }
+
+        name
+      }
+    };
+
+    Self {
+      name,
+      extents: vec![Extent::from_record(&record)],
+      directory: record.file_flags.contains(spec::FileFlags::DIRECTORY),
+      record,
+      system_use,
+    }
+  }
+
+  /// Name of the entry without the version suffix, or its Rock Ridge name if recorded.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Whether the entry is a directory, including relocated directories recorded as files.
+  pub fn is_directory(&self) -> bool {
+    self.directory
+  }
+
+  /// System Use entries of the entry, following its continuation areas. `CE` and `ST`
+  /// entries are left out.
+  pub fn system_use(&self) -> &[spec::SystemUseEntry] {
+    &self.system_use
+  }
+
+  /// POSIX metadata of the entry, if recorded through Rock Ridge.
+  pub fn posix_metadata(&self) -> Option<PosixMetadata> {
+    let mut metadata = self.system_use.iter().find_map(|x| match *x {
+      spec::SystemUseEntry::PosixFileAttributes {
+        mode,
+        links,
+        uid,
+        gid,
+        serial,
+      } => Some(PosixMetadata {
+        mode,
+        links,
+        uid,
+        gid,
+        serial,
+        device: None,
+        timestamps: None,
+      }),
+      _ => None,
+    })?;
+
+    for entry in &self.system_use {
+      match entry {
+        spec::SystemUseEntry::DeviceNumber { high, low } => {
+          metadata.device = Some((*high as u64) << 32 | *low as u64);
+        }
+        spec::SystemUseEntry::TimeStamps(x) => metadata.timestamps = Some(x.clone()),
+        _ => {}
+      }
+    }
+
+    Some(metadata)
+  }
+
+  /// Target of the entry if it's a symbolic link recorded through Rock Ridge `SL` entries.
+  pub fn symbolic_link(&self) -> Option<String> {
+    let mut target = None::<String>;
+    let mut continued = false;
+
+    for entry in &self.system_use {
+      let spec::SystemUseEntry::SymbolicLink { components, .. } = entry else {
+        continue;
+      };
+
+      let target = target.get_or_insert_default();
+
+      for component in components {
+        // Components are separated unless continued, or following the root.
+        if !continued && !target.is_empty() && !target.ends_with('/') {
+          target.push('/');
+        }
+
+        match component.flags {
+          x if x.contains(spec::SymbolicLinkComponentFlags::ROOT) => target.push('/'),
+          x if x.contains(spec::SymbolicLinkComponentFlags::PARENT) => target.push_str(".."),
+          x if x.contains(spec::SymbolicLinkComponentFlags::CURRENT) => target.push('.'),
+          _ => target.push_str(&String::from_utf8_lossy(&component.content)),
+        }
+
+        continued = component
+          .flags
+          .contains(spec::SymbolicLinkComponentFlags::CONTINUE);
+      }
+    }
+
+    target
+  }
+
+  /// Size of the entry's data in bytes, summed over all of its extents.
+  /// For files compressed with zisofs, this is their uncompressed size.
+  pub fn size(&self) -> u64 {
+    match self.zisofs() {
+      Some((_, uncompressed_size)) => uncompressed_size as u64,
+      None => self.extents.iter().map(|x| x.length as u64).sum(),
+    }
+  }
+
+  /// Block size (as a base 2 logarithm) and uncompressed size of the entry's
+  /// data, if compressed with zisofs.
+  fn zisofs(&self) -> Option<(u8, u32)> {
+    self.system_use.iter().find_map(|x| match *x {
+      spec::SystemUseEntry::CompressedFile {
+        algorithm: spec::zisofs::ALGORITHM,
+        block_size_log2,
+        uncompressed_size,
+        ..
+      } => Some((block_size_log2, uncompressed_size)),
+      _ => None,
+    })
+  }
+
+  /// The directory record this entry was read from. For files spanning
+  /// multiple extents, this is the first record. For relocated directories,
+  /// this is the record left in their place, carrying the `CL` entry.
+  pub fn record(&self) -> &spec::DirectoryRecord {
+    &self.record
+  }
+
+  /// Extents holding the entry's data, in file order.
+  pub fn extents(&self) -> &[Extent] {
+    &self.extents
+  }
+}
+
+/// Reads an ISO 9660 image from any seekable storage.
+#[derive(Debug)]
+pub struct IsoReader<R> {
+  storage: R,
+  primary_volume_descriptor: spec::PrimaryVolumeDescriptor,
+  /// Whether the root directory's `.` record announces SUSP with an `SP` entry.
+  susp: bool,
+  /// Bytes preceding the SUSP entries of each System Use area, as announced by the `SP` entry.
+  bytes_skipped: u8,
+  rock_ridge: bool,
+}
+
+impl<R> IsoReader<R>
+where
+  R: Read + Seek,
+{
+  /// Scans the volume descriptor set and opens the volume described by
+  /// the primary volume descriptor.
+  pub fn new(mut storage: R) -> Result<Self> {
+    let mut primary_volume_descriptor = None;
+    let mut descriptor_bytes = [0u8; VOLUME_DESCRIPTOR_SIZE as usize];
+
+    for lba in VOLUME_DESCRIPTOR_SET_LBA.. {
+      storage.seek(SeekFrom::Start(lba * VOLUME_DESCRIPTOR_SIZE))?;
+      storage.read_exact(&mut descriptor_bytes)?;
+
+      if &descriptor_bytes[1..6] != spec::StandardIdentifier::Cd001.as_bytes() {
+        return Err(Error::InvalidVolumeDescriptor(lba));
+      }
+
+      match descriptor_bytes[0] {
+        1 if primary_volume_descriptor.is_none() => {
+          primary_volume_descriptor = Some(spec::PrimaryVolumeDescriptor::deserialize(
+            &mut (),
+            &descriptor_bytes,
+          )?);
+        }
+        255 => break,
+        _ => log::debug!("Skipping volume descriptor at LBA {lba}"),
+      }
+    }
+
+    let mut reader = Self {
+      storage,
+      primary_volume_descriptor: primary_volume_descriptor
+        .ok_or(Error::MissingPrimaryVolumeDescriptor)?,
+      susp: false,
+      bytes_skipped: 0,
+      rock_ridge: false,
+    };
+
+    let root = reader.root();
+
+    if let Some(dot) = reader.records(root.extents[0])?.into_iter().next() {
+      if let Some(&spec::SystemUseEntry::SharingProtocolIndicator { bytes_skipped }) =
+        dot.system_use.first()
+      {
+        reader.susp = true;
+        reader.bytes_skipped = bytes_skipped;
+        reader.rock_ridge = reader.system_use(&dot)?.iter().any(|x| {
+          matches!(x, spec::SystemUseEntry::ExtensionsReference { identifier, .. }
+            if ROCK_RIDGE_IDENTIFIERS.contains(&identifier.as_str()))
+        });
+      }
+    }
+
+    Ok(reader)
+  }
+
+  pub fn primary_volume_descriptor(&self) -> &spec::PrimaryVolumeDescriptor {
+    &self.primary_volume_descriptor
+  }
+
+  /// Whether the volume records Rock Ridge names and attributes, as announced in the root
+  /// directory.
+  pub fn is_rock_ridge(&self) -> bool {
+    self.rock_ridge
+  }
+
+  /// Consumes the reader, returning the underlying storage.
+  pub fn into_inner(self) -> R {
+    self.storage
+  }
+
+  /// Entry representing the root directory of the volume.
+  pub fn root(&self) -> Entry {
+    let root = &self.primary_volume_descriptor.root_directory_record;
+
+    Entry::new(
+      spec::DirectoryRecord {
+        extended_attribute_length: 0,
+        extent_location: root.extent_location,
+        data_length: root.data_length,
+        recording_date: root.recording_date.clone(),
+        file_flags: root.file_flags,
+        file_unit_size: root.file_unit_size,
+        interleave_gap_size: root.interleave_gap_size,
+        volume_sequence_number: root.volume_sequence_number,
+        file_identifier_length: 1,
+        file_identifier: spec::Identifier::root_directory(),
+        system_use: vec![],
+      },
+      vec![],
+      false,
+    )
+  }
+
+  /// Looks up the entry at `path`. Names are matched without regard to version
+  /// suffixes, exactly if possible and case-insensitively otherwise.
+  pub fn lookup<P>(&mut self, path: &P) -> Result<Entry>
+  where
+    P: AsRef<IsoPath> + ?Sized,
+  {
+    let path = path.as_ref();
+    let mut entry = self.root();
+
+    for component in path.components().filter(|x| !x.is_empty()) {
+      if !entry.is_directory() {
+        return Err(Error::NotADirectory(path.to_string()));
+      }
+
+      let mut entries = self.entries(&entry)?;
+      let ix = entries
+        .iter()
+        .position(|x| x.name == component)
+        .or_else(|| {
+          entries
+            .iter()
+            .position(|x| x.name.eq_ignore_ascii_case(component))
+        })
+        .ok_or_else(|| Error::NotFound(path.to_string()))?;
+
+      entry = entries.swap_remove(ix);
+    }
+
+    Ok(entry)
+  }
+
+  /// Lists the entries of the directory at `path`, excluding `.` and `..`.
+  pub fn read_dir<P>(&mut self, path: &P) -> Result<Vec<Entry>>
+  where
+    P: AsRef<IsoPath> + ?Sized,
+  {
+    let entry = self.lookup(path)?;
+
+    if !entry.is_directory() {
+      return Err(Error::NotADirectory(path.as_ref().to_string()));
+    }
+
+    self.entries(&entry)
+  }
+
+  /// Lists the entries of `dir`, excluding `.` and `..`. With Rock Ridge,
+  /// relocated directories are listed where they belong rather than in
+  /// `RR_MOVED`.
+  pub fn entries(&mut self, dir: &Entry) -> Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = vec![];
+    let mut continued = false;
+
+    for record in self.records(dir.extents[0])? {
+      match record.file_identifier.kind() {
+        spec::IdentifierKind::CurrentDirectory | spec::IdentifierKind::ParentDirectory => {}
+        _ => {
+          let next = record.file_flags.contains(spec::FileFlags::MULTI_EXTENT);
+          let system_use = self.system_use(&record)?;
+          let mut entry = Entry::new(record, system_use, self.rock_ridge);
+
+          if self.rock_ridge {
+            if entry.system_use.contains(&spec::SystemUseEntry::Relocated) {
+              continue;
+            }
+
+            if let Some(block_location) = entry.system_use.iter().find_map(|x| match x {
+              spec::SystemUseEntry::ChildLink { block_location } => Some(*block_location),
+              _ => None,
+            }) {
+              entry.extents = vec![self.relocated_extent(block_location)?];
+              entry.directory = true;
+            }
+          }
+
+          // Every record but the last of a multi-extent file has the flag set,
+          // and all of them share the same identifier.
+          match entries.last_mut() {
+            Some(last) if continued && last.name == entry.name => {
+              last.extents.extend(entry.extents);
+            }
+            _ => entries.push(entry),
+          }
+
+          continued = next;
+        }
+      }
+    }
+
+    Ok(entries)
+  }
+
+  /// Reads the directory records of the directory stored in `extent`.
+  fn records(&mut self, extent: Extent) -> Result<Vec<spec::DirectoryRecord>> {
+    let block_size = self.primary_volume_descriptor.logical_block_size as u64;
+    let root_lba = self
+      .primary_volume_descriptor
+      .root_directory_record
+      .extent_location;
+    let mut data = vec![0u8; extent.length as usize];
+
+    self
+      .storage
+      .seek(SeekFrom::Start(extent.lba as u64 * block_size))?;
+    self.storage.read_exact(&mut data)?;
+
+    let mut records = vec![];
+    let mut offset = 0;
+
+    while offset < data.len() {
+      let length = data[offset] as usize;
+
+      if length == 0 {
+        // Records never span sectors, the rest of this one is padding.
+        offset = (offset as u64 / block_size + 1) as usize * block_size as usize;
+        continue;
+      }
+
+      // The `SP` entry itself starts the System Use area of the root directory's `.` record.
+      records.push(spec::DirectoryRecord::deserialize(
+        &mut deserialize::DirectoryRecordContext {
+          compatibility_mode: spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3),
+          bytes_skipped: match offset == 0 && extent.lba == root_lba {
+            true => 0,
+            false => self.bytes_skipped,
+          },
+        },
+        &data[offset..],
+      )?);
+
+      offset += length;
+    }
+
+    Ok(records)
+  }
+
+  /// Extent of the relocated directory at `block_location`, as recorded by its `.` record.
+  fn relocated_extent(&mut self, block_location: u32) -> Result<Extent> {
+    let block_size = self.primary_volume_descriptor.logical_block_size as u32;
+    let dot = self
+      .records(Extent {
+        lba: block_location,
+        length: block_size,
+      })?
+      .into_iter()
+      .next()
+      .ok_or_else(|| Error::NotFound(format!("relocated directory at LBA {block_location}")))?;
+
+    Ok(Extent::from_record(&dot))
+  }
+
+  /// Entries of the System Use area of `record`, followed by those of its
+  /// continuation areas if SUSP is in use.
+  fn system_use(&mut self, record: &spec::DirectoryRecord) -> Result<Vec<spec::SystemUseEntry>> {
+    if !self.susp {
+      return Ok(record.system_use.clone());
+    }
+
+    let block_size = self.primary_volume_descriptor.logical_block_size as u64;
+    let mut entries = vec![];
+    let mut area = record.system_use.clone();
+    let mut visited = vec![];
+
+    loop {
+      let mut continuation = None;
+
+      for entry in area {
+        match entry {
+          spec::SystemUseEntry::ContinuationArea {
+            block_location,
+            offset,
+            length,
+          } => continuation = Some((block_location, offset, length)),
+          spec::SystemUseEntry::Terminator => break,
+          x => entries.push(x),
+        }
+      }
+
+      let Some((block_location, offset, length)) = continuation else {
+        break;
+      };
+
+      // Guard against continuation areas continuing into each other.
+      if visited.contains(&(block_location, offset)) {
+        break;
+      }
+
+      visited.push((block_location, offset));
+
+      let mut data = vec![0u8; length as usize];
+
+      self.storage.seek(SeekFrom::Start(
+        block_location as u64 * block_size + offset as u64,
+      ))?;
+      self.storage.read_exact(&mut data)?;
+
+      area = deserialize::system_use_area(&data)?;
+    }
+
+    Ok(entries)
+  }
+
+  /// Opens the file at `path` for reading. Data is streamed from the image
+  /// on demand, so large files are never loaded into memory.
+  pub fn open_file<P>(&mut self, path: &P) -> Result<FileReader<'_, R>>
+  where
+    P: AsRef<IsoPath> + ?Sized,
+  {
+    let entry = self.lookup(path)?;
+
+    if entry.is_directory() {
+      return Err(Error::IsADirectory(path.as_ref().to_string()));
+    }
+
+    self.open_entry(&entry)
+  }
+
+  /// Opens the file described by `entry` for reading.
+  pub fn open_entry(&mut self, entry: &Entry) -> Result<FileReader<'_, R>> {
+    if entry.is_directory() {
+      return Err(Error::IsADirectory(entry.name.clone()));
+    }
+
+    let mut data = ExtentReader {
+      storage: &mut self.storage,
+      block_size: self.primary_volume_descriptor.logical_block_size as u64,
+      extents: entry.extents.clone(),
+    };
+
+    let zisofs = match entry.zisofs() {
+      Some((_, uncompressed_size)) => Some(ZisofsBlocks::read(&mut data, uncompressed_size)?),
+      None => None,
+    };
+
+    Ok(FileReader {
+      data,
+      size: entry.size(),
+      position: 0,
+      zisofs,
+    })
+  }
+}
+
+/// Streams the contents of a file out of an [`IsoReader`]'s storage,
+/// decompressing files compressed with zisofs.
+#[derive(Debug)]
+pub struct FileReader<'a, R> {
+  data: ExtentReader<'a, R>,
+  size: u64,
+  position: u64,
+  zisofs: Option<ZisofsBlocks>,
+}
+
+impl<R> FileReader<'_, R> {
+  /// Total size of the file in bytes.
+  pub fn size(&self) -> u64 {
+    self.size
+  }
+}
+
+impl<R> Read for FileReader<'_, R>
+where
+  R: Read + Seek,
+{
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let Some(zisofs) = &mut self.zisofs else {
+      let read = self.data.read_at(self.position, buf)?;
+      self.position += read as u64;
+
+      return Ok(read);
+    };
+
+    if self.position >= self.size {
+      return Ok(0);
+    }
+
+    let ix = (self.position >> zisofs.block_size_log2) as usize;
+    let offset = (self.position - ((ix as u64) << zisofs.block_size_log2)) as usize;
+    let block = zisofs.block(&mut self.data, ix, self.size)?;
+    let len = buf.len().min(block.len() - offset);
+
+    buf[..len].copy_from_slice(&block[offset..offset + len]);
+    self.position += len as u64;
+
+    Ok(len)
+  }
+}
+
+/// Reads the data of a file as stored in its extents.
+#[derive(Debug)]
+struct ExtentReader<'a, R> {
+  storage: &'a mut R,
+  block_size: u64,
+  extents: Vec<Extent>,
+}
+
+impl<R> ExtentReader<'_, R>
+where
+  R: Read + Seek,
+{
+  /// Reads the data at `position`, returning zero bytes past its end.
+  fn read_at(&mut self, position: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut start = 0;
+
+    for extent in &self.extents {
+      let end = start + extent.length as u64;
+
+      if position < end {
+        let offset = position - start;
+        let len = buf.len().min((end - position) as usize);
+
+        self.storage.seek(SeekFrom::Start(
+          extent.lba as u64 * self.block_size + offset,
+        ))?;
+
+        return self.storage.read(&mut buf[..len]);
+      }
+
+      start = end;
+    }
+
+    Ok(0)
+  }
+
+  fn read_exact_at(&mut self, mut position: u64, mut buf: &mut [u8]) -> std::io::Result<()> {
+    while !buf.is_empty() {
+      match self.read_at(position, buf)? {
+        0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+        read => {
+          position += read as u64;
+          buf = &mut buf[read..];
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Blocks of a file compressed with zisofs, each being a zlib stream of its
+/// own. Empty blocks consist of zeros.
+#[derive(Debug)]
+struct ZisofsBlocks {
+  block_size_log2: u8,
+  /// Offsets of the blocks within the file, followed by the end of the last one.
+  block_pointers: Vec<u32>,
+  /// Index and content of the block decompressed last.
+  cached: Option<(usize, Vec<u8>)>,
+}
+
+impl ZisofsBlocks {
+  /// Reads the header and the block pointers of the compressed data.
+  fn read<R: Read + Seek>(data: &mut ExtentReader<'_, R>, uncompressed_size: u32) -> Result<Self> {
+    let invalid = |reason: &str| {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid zisofs header: {reason}"),
+      )
+    };
+
+    let mut header = [0u8; 16];
+    data.read_exact_at(0, &mut header)?;
+
+    if header[0..8] != spec::zisofs::MAGIC {
+      return Err(invalid("bad magic").into());
+    }
+
+    let (header_size, block_size_log2) = (header[12] as u64 * 4, header[13]);
+
+    if !(15..=17).contains(&block_size_log2) {
+      return Err(invalid("unsupported block size").into());
+    }
+
+    let block_count = (uncompressed_size as u64).div_ceil(1 << block_size_log2) as usize;
+    let mut block_pointers = vec![0u8; 4 * (block_count + 1)];
+    data.read_exact_at(header_size, &mut block_pointers)?;
+
+    Ok(Self {
+      block_size_log2,
+      block_pointers: block_pointers
+        .chunks_exact(4)
+        .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+        .collect(),
+      cached: None,
+    })
+  }
+
+  /// Decompresses the block at `ix` of a file `size` bytes long.
+  fn block<R: Read + Seek>(
+    &mut self,
+    data: &mut ExtentReader<'_, R>,
+    ix: usize,
+    size: u64,
+  ) -> std::io::Result<&[u8]> {
+    if self.cached.as_ref().is_none_or(|(x, _)| *x != ix) {
+      let start = (ix as u64) << self.block_size_log2;
+      let length = (size - start).min(1 << self.block_size_log2);
+
+      let (from, to) = match self.block_pointers.get(ix..ix + 2) {
+        Some(&[from, to]) if from <= to => (from, to),
+        _ => {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid zisofs block pointers of block {ix}"),
+          ))
+        }
+      };
+
+      let mut compressed = vec![0u8; (to - from) as usize];
+      let mut block = Vec::with_capacity(length as usize);
+
+      data.read_exact_at(from as u64, &mut compressed)?;
+
+      if !compressed.is_empty() {
+        flate2::read::ZlibDecoder::new(compressed.as_slice())
+          .take(length)
+          .read_to_end(&mut block)?;
+      }
+
+      block.resize(length as usize, 0);
+      self.cached = Some((ix, block));
+    }
+
+    Ok(self.cached.as_ref().map_or(&[], |(_, x)| x.as_slice()))
+  }
+}
+
+impl<R> Seek for FileReader<'_, R> {
+  fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+    let position = match pos {
+      SeekFrom::Start(x) => Some(x),
+      SeekFrom::End(x) => self.size.checked_add_signed(x),
+      SeekFrom::Current(x) => self.position.checked_add_signed(x),
+    };
+
+    self.position = position.ok_or_else(|| {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "invalid seek to a negative or overflowing position",
+      )
+    })?;
+
+    Ok(self.position)
+  }
+}
@@ -2,7 +2,7 @@ use crate::spec::*;
 
 type Result<T> = std::result::Result<T, IsoSerializeError>;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endianness {
   Little,
   Big,
@@ -12,6 +12,8 @@ pub enum Endianness {
 pub enum IsoSerializeError {
   #[error("Output buffer too small")]
   OutputBufferTooSmall { expected: usize, actual: usize },
+  #[error("Directory record of {0} bytes exceeds 255 bytes")]
+  DirectoryRecordTooLarge(usize),
 }
 
 pub trait IsoSerialize {
@@ -19,6 +21,9 @@ pub trait IsoSerialize {
 
   fn extent(&self) -> usize;
 
+  /// # Safety
+  ///
+  /// `out` must be at least [IsoSerialize::extent] bytes long.
   unsafe fn serialize_unchecked(&self, cx: &mut Self::Context, out: &mut [u8]) -> Result<()>;
 
   fn serialize(&self, cx: &mut Self::Context, out: &mut [u8]) -> Result<usize> {
@@ -146,6 +151,19 @@ impl IsoSerialize for GroupIdentification {
   }
 }
 
+impl IsoSerialize for RecordFormat {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    1
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0] = (*self).into();
+    Ok(())
+  }
+}
+
 impl IsoSerialize for RecordAttributes {
   type Context = ();
 
@@ -511,6 +529,11 @@ impl IsoSerialize for NumericalDate {
     self
       .second
       .serialize_unchecked(&mut (), &mut out[offset..offset + self.second.extent()])?;
+    offset += self.second.extent();
+
+    self
+      .gmt_offset
+      .serialize_unchecked(&mut (), &mut out[offset..offset + self.gmt_offset.extent()])?;
 
     Ok(())
   }
@@ -655,7 +678,9 @@ impl IsoSerialize for SupplementaryVolumeDescriptor {
     out[72..80].fill(0);
     out[80..84].copy_from_slice(&self.volume_space_size.to_le_bytes());
     out[84..88].copy_from_slice(&self.volume_space_size.to_be_bytes());
-    out[88..120].fill(0); // TODO(meowesque): Add escape sequences
+    self
+      .escape_sequences
+      .serialize_unchecked(&mut (), &mut out[88..120])?;
     out[120..122].copy_from_slice(&self.volume_set_size.to_le_bytes());
     out[122..124].copy_from_slice(&self.volume_set_size.to_be_bytes());
     out[124..126].copy_from_slice(&self.volume_sequence_number.to_le_bytes());
@@ -666,8 +691,8 @@ impl IsoSerialize for SupplementaryVolumeDescriptor {
     out[136..140].copy_from_slice(&self.path_table_size.to_be_bytes());
     out[140..144].copy_from_slice(&self.type_l_path_table_location.to_le_bytes());
     out[144..148].copy_from_slice(&self.optional_type_l_path_table_location.to_le_bytes());
-    out[148..152].copy_from_slice(&self.type_m_path_table_location.to_le_bytes()); // TODO(meowesque): Check if the endianness is correct
-    out[152..156].copy_from_slice(&self.optional_type_m_path_table_location.to_le_bytes());
+    out[148..152].copy_from_slice(&self.type_m_path_table_location.to_be_bytes());
+    out[152..156].copy_from_slice(&self.optional_type_m_path_table_location.to_be_bytes());
     self
       .root_directory_record
       .serialize_unchecked(&mut (), &mut out[156..190])?;
@@ -762,11 +787,19 @@ impl IsoSerialize for DirectoryRecord {
   type Context = ();
 
   fn extent(&self) -> usize {
-    33 + self.file_identifier_length as usize + (self.file_identifier_length % 2 == 0) as usize
+    33 + self.file_identifier_length as usize
+      + self.file_identifier_length.is_multiple_of(2) as usize
+      + self.system_use.iter().map(|x| x.extent()).sum::<usize>()
   }
 
   unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
-    out[0] = self.extent() as u8;
+    let extent = self.extent();
+
+    if extent > 255 {
+      return Err(IsoSerializeError::DirectoryRecordTooLarge(extent));
+    }
+
+    out[0] = extent as u8;
     out[1] = self.extended_attribute_length;
     out[2..6].copy_from_slice(&self.extent_location.to_le_bytes());
     out[6..10].copy_from_slice(&self.extent_location.to_be_bytes());
@@ -790,8 +823,158 @@ impl IsoSerialize for DirectoryRecord {
       &mut out[33..33 + self.file_identifier_length as usize],
     )?;
 
-    if self.file_identifier_length % 2 == 0 {
-      out[33 + self.file_identifier_length as usize] = 0;
+    let mut offset = 33 + self.file_identifier_length as usize;
+
+    if self.file_identifier_length.is_multiple_of(2) {
+      out[offset] = 0;
+      offset += 1;
+    }
+
+    for entry in &self.system_use {
+      entry.serialize_unchecked(&mut (), &mut out[offset..])?;
+      offset += entry.extent();
+    }
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for SystemUseEntry {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    4 + match self {
+      Self::SharingProtocolIndicator { .. } => 3,
+      Self::ContinuationArea { .. } => 24,
+      Self::Padding { length } => *length as usize,
+      Self::Terminator | Self::Relocated => 0,
+      Self::ExtensionsReference {
+        identifier,
+        descriptor,
+        source,
+        ..
+      } => 4 + identifier.len() + descriptor.len() + source.len(),
+      Self::ExtensionSelector { .. } => 1,
+      Self::PosixFileAttributes { serial, .. } => 32 + 8 * serial.is_some() as usize,
+      Self::DeviceNumber { .. } => 16,
+      Self::TimeStamps(TimeStamps::Short(timestamps)) => {
+        1 + 7 * timestamps.iter().flatten().count()
+      }
+      Self::TimeStamps(TimeStamps::Long(timestamps)) => {
+        1 + 17 * timestamps.iter().flatten().count()
+      }
+      Self::AlternateName { content, .. } => 1 + content.len(),
+      Self::SymbolicLink { components, .. } => {
+        1 + components
+          .iter()
+          .map(|x| 2 + x.content.len())
+          .sum::<usize>()
+      }
+      Self::ChildLink { .. } | Self::ParentLink { .. } => 8,
+      Self::CompressedFile { .. } => 12,
+      Self::Unknown { data, .. } => data.len(),
+    }
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..2].copy_from_slice(&self.signature());
+    out[2] = self.extent() as u8;
+    out[3] = match self {
+      Self::Unknown { version, .. } => *version,
+      _ => 1,
+    };
+
+    let data = &mut out[4..self.extent()];
+
+    match self {
+      Self::SharingProtocolIndicator { bytes_skipped } => {
+        data[0] = 0xBE;
+        data[1] = 0xEF;
+        data[2] = *bytes_skipped;
+      }
+      Self::ContinuationArea {
+        block_location,
+        offset,
+        length,
+      } => both_endian_u32s(data, [*block_location, *offset, *length]),
+      Self::Padding { .. } | Self::Terminator => data.fill(0),
+      Self::ExtensionsReference {
+        version,
+        identifier,
+        descriptor,
+        source,
+      } => {
+        data[0] = identifier.len() as u8;
+        data[1] = descriptor.len() as u8;
+        data[2] = source.len() as u8;
+        data[3] = *version;
+
+        let mut offset = 4;
+
+        for s in [identifier, descriptor, source] {
+          data[offset..offset + s.len()].copy_from_slice(s.as_bytes());
+          offset += s.len();
+        }
+      }
+      Self::ExtensionSelector { sequence } => data[0] = *sequence,
+      Self::PosixFileAttributes {
+        mode,
+        links,
+        uid,
+        gid,
+        serial,
+      } => both_endian_u32s(data, [*mode, *links, *uid, *gid].into_iter().chain(*serial)),
+      Self::DeviceNumber { high, low } => both_endian_u32s(data, [*high, *low]),
+      Self::TimeStamps(TimeStamps::Short(timestamps)) => {
+        data[0] = timestamps.flags();
+
+        for (ix, timestamp) in timestamps.iter().flatten().enumerate() {
+          timestamp.serialize_unchecked(&mut (), &mut data[1 + ix * 7..])?;
+        }
+      }
+      Self::TimeStamps(TimeStamps::Long(timestamps)) => {
+        data[0] = timestamps.flags() | RecordedTimeStamps::<DigitsDate>::LONG_FORM;
+
+        for (ix, timestamp) in timestamps.iter().flatten().enumerate() {
+          timestamp.serialize_unchecked(&mut (), &mut data[1 + ix * 17..])?;
+        }
+      }
+      Self::AlternateName { flags, content } => {
+        data[0] = flags.bits();
+        data[1..].copy_from_slice(content);
+      }
+      Self::SymbolicLink {
+        continues,
+        components,
+      } => {
+        data[0] = *continues as u8;
+
+        let mut offset = 1;
+
+        for component in components {
+          data[offset] = component.flags.bits();
+          data[offset + 1] = component.content.len() as u8;
+          data[offset + 2..offset + 2 + component.content.len()]
+            .copy_from_slice(&component.content);
+          offset += 2 + component.content.len();
+        }
+      }
+      Self::ChildLink { block_location } | Self::ParentLink { block_location } => {
+        both_endian_u32s(data, [*block_location])
+      }
+      Self::Relocated => {}
+      Self::CompressedFile {
+        algorithm,
+        header_size,
+        block_size_log2,
+        uncompressed_size,
+      } => {
+        data[0..2].copy_from_slice(algorithm);
+        data[2] = *header_size;
+        data[3] = *block_size_log2;
+        both_endian_u32s(&mut data[4..], [*uncompressed_size]);
+      }
+      Self::Unknown { data: bytes, .. } => data.copy_from_slice(bytes),
     }
 
     Ok(())
@@ -825,7 +1008,8 @@ impl IsoSerialize for RootDirectoryRecord {
     out[27] = self.interleave_gap_size;
     out[28..30].copy_from_slice(&self.volume_sequence_number.to_le_bytes());
     out[30..32].copy_from_slice(&self.volume_sequence_number.to_be_bytes());
-    out[32] = 0; // NOTE(meowesque): This might be one
+    // The root's identifier is a single 0x00 byte.
+    out[32] = 1;
     out[33] = 0;
 
     Ok(())
@@ -871,6 +1055,58 @@ impl IsoSerialize for PathTableRecord {
   }
 }
 
+impl IsoSerialize for ExtendedAttributeRecord {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    250 + self.application_use.len() + self.escape_sequences.extent()
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..2].copy_from_slice(&self.owner_identification.0.to_le_bytes());
+    out[2..4].copy_from_slice(&self.owner_identification.0.to_be_bytes());
+    out[4..6].copy_from_slice(&self.group_identification.0.to_le_bytes());
+    out[6..8].copy_from_slice(&self.group_identification.0.to_be_bytes());
+    self
+      .permissions
+      .serialize_unchecked(&mut (), &mut out[8..10])?;
+    self
+      .file_creation_date
+      .serialize_unchecked(&mut (), &mut out[10..27])?;
+    self
+      .file_modification_date
+      .serialize_unchecked(&mut (), &mut out[27..44])?;
+    self
+      .file_expiration_date
+      .serialize_unchecked(&mut (), &mut out[44..61])?;
+    self
+      .file_effective_date
+      .serialize_unchecked(&mut (), &mut out[61..78])?;
+    out[78] = self.record_format.into();
+    out[79] = self.record_attributes.into();
+    out[80..82].copy_from_slice(&self.record_length.to_le_bytes());
+    out[82..84].copy_from_slice(&self.record_length.to_be_bytes());
+    self
+      .system_identifier
+      .serialize_unchecked(&mut (), &mut out[84..116])?;
+    out[116..180].copy_from_slice(&self.system_use);
+    out[180] = self.extended_attribute_record_version.into();
+    out[181] = self.escape_sequences.extent() as u8;
+    out[182..246].fill(0);
+    out[246..248].copy_from_slice(&(self.application_use.len() as u16).to_le_bytes());
+    out[248..250].copy_from_slice(&(self.application_use.len() as u16).to_be_bytes());
+
+    let escape_sequences_offset = 250 + self.application_use.len();
+
+    out[250..escape_sequences_offset].copy_from_slice(&self.application_use);
+    self
+      .escape_sequences
+      .serialize_unchecked(&mut (), &mut out[escape_sequences_offset..])?;
+
+    Ok(())
+  }
+}
+
 impl IsoSerialize for ElToritoManufacturerId {
   type Context = ();
 
@@ -1010,7 +1246,7 @@ impl IsoSerialize for ElToritoSectionEntryExtension {
   }
 
   unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
-    out[0] = 44;
+    out[0] = 0x44;
     out[1] = self.extension_record_follows_indicator.bits();
     out[2..=0x1F].copy_from_slice(&self.vendor_unique_selection_criteria);
 
@@ -1029,11 +1265,185 @@ impl IsoSerialize for ElToritoBootRecordVolumeDescriptor {
     out[0] = 0;
     out[1..=5].copy_from_slice(self.standard_identifier.as_bytes());
     out[6] = self.version.into();
-    out[7..=26].copy_from_slice(b"EL TORITO SPECIFICATION");
-    out[27..=46].fill(0);
-    out[47..=0x4a].copy_from_slice(&self.boot_catalog_pointer.to_le_bytes());
-    out[0x4a..=0x7ff].fill(0);
+    // Boot system identifier, padded with zeroes, followed by an unused boot identifier.
+    out[7..0x47].fill(0);
+    out[7..30].copy_from_slice(b"EL TORITO SPECIFICATION");
+    out[0x47..0x4b].copy_from_slice(&self.boot_catalog_pointer.to_le_bytes());
+    out[0x4b..0x800].fill(0);
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for BootInfoTable {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    56
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..4].copy_from_slice(&self.primary_volume_descriptor.to_le_bytes());
+    out[4..8].copy_from_slice(&self.boot_file_location.to_le_bytes());
+    out[8..12].copy_from_slice(&self.boot_file_length.to_le_bytes());
+    out[12..16].copy_from_slice(&self.checksum.to_le_bytes());
+    out[16..56].fill(0);
 
     Ok(())
   }
 }
+
+impl IsoSerialize for MbrPartitionEntry {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    16
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0] = self.boot_indicator;
+    out[1..4].copy_from_slice(&self.start_chs);
+    out[4] = self.partition_type;
+    out[5..8].copy_from_slice(&self.end_chs);
+    out[8..12].copy_from_slice(&self.start_lba.to_le_bytes());
+    out[12..16].copy_from_slice(&self.sector_count.to_le_bytes());
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for MbrPartitionTable {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    66
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    for (ix, entry) in self.entries.iter().enumerate() {
+      entry.serialize_unchecked(&mut (), &mut out[ix * 16..(ix + 1) * 16])?;
+    }
+
+    out[64] = 0x55;
+    out[65] = 0xAA;
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for Guid {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    16
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[..16].copy_from_slice(&self.0);
+    Ok(())
+  }
+}
+
+impl IsoSerialize for GptHeader {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    GptHeader::SIZE as usize
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..8].copy_from_slice(GptHeader::SIGNATURE);
+    out[8..12].copy_from_slice(&GptHeader::REVISION.to_le_bytes());
+    out[12..16].copy_from_slice(&GptHeader::SIZE.to_le_bytes());
+    out[16..20].copy_from_slice(&self.header_crc32.to_le_bytes());
+    out[20..24].fill(0);
+    out[24..32].copy_from_slice(&self.my_lba.to_le_bytes());
+    out[32..40].copy_from_slice(&self.alternate_lba.to_le_bytes());
+    out[40..48].copy_from_slice(&self.first_usable_lba.to_le_bytes());
+    out[48..56].copy_from_slice(&self.last_usable_lba.to_le_bytes());
+    self
+      .disk_guid
+      .serialize_unchecked(&mut (), &mut out[56..72])?;
+    out[72..80].copy_from_slice(&self.partition_entry_lba.to_le_bytes());
+    out[80..84].copy_from_slice(&self.number_of_partition_entries.to_le_bytes());
+    out[84..88].copy_from_slice(&self.size_of_partition_entry.to_le_bytes());
+    out[88..92].copy_from_slice(&self.partition_entry_array_crc32.to_le_bytes());
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for GptPartitionEntry {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    GptPartitionEntry::SIZE as usize
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    self
+      .partition_type_guid
+      .serialize_unchecked(&mut (), &mut out[0..16])?;
+    self
+      .unique_partition_guid
+      .serialize_unchecked(&mut (), &mut out[16..32])?;
+    out[32..40].copy_from_slice(&self.starting_lba.to_le_bytes());
+    out[40..48].copy_from_slice(&self.ending_lba.to_le_bytes());
+    out[48..56].copy_from_slice(&self.attributes.to_le_bytes());
+
+    for (ix, unit) in self.partition_name.iter().enumerate() {
+      out[56 + ix * 2..58 + ix * 2].copy_from_slice(&unit.to_le_bytes());
+    }
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for ApmDriverDescriptorMap {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    8
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..2].copy_from_slice(ApmDriverDescriptorMap::SIGNATURE);
+    out[2..4].copy_from_slice(&self.block_size.to_be_bytes());
+    out[4..8].copy_from_slice(&self.block_count.to_be_bytes());
+
+    Ok(())
+  }
+}
+
+impl IsoSerialize for ApmPartitionEntry {
+  type Context = ();
+
+  fn extent(&self) -> usize {
+    512
+  }
+
+  unsafe fn serialize_unchecked(&self, (): &mut (), out: &mut [u8]) -> Result<()> {
+    out[0..2].copy_from_slice(ApmPartitionEntry::SIGNATURE);
+    out[2..4].fill(0);
+    out[4..8].copy_from_slice(&self.map_block_count.to_be_bytes());
+    out[8..12].copy_from_slice(&self.start_block.to_be_bytes());
+    out[12..16].copy_from_slice(&self.block_count.to_be_bytes());
+    out[16..48].copy_from_slice(&self.name);
+    out[48..80].copy_from_slice(&self.partition_type);
+    out[80..84].copy_from_slice(&self.data_start.to_be_bytes());
+    out[84..88].copy_from_slice(&self.data_count.to_be_bytes());
+    out[88..92].copy_from_slice(&self.status.to_be_bytes());
+    // Boot code fields are unused, booting goes through EFI.
+    out[92..512].fill(0);
+
+    Ok(())
+  }
+}
+
+/// Writes consecutive both-endian (ISO 9660 7.3.3) 32-bit values.
+fn both_endian_u32s(out: &mut [u8], values: impl IntoIterator<Item = u32>) {
+  for (ix, value) in values.into_iter().enumerate() {
+    out[ix * 8..ix * 8 + 4].copy_from_slice(&value.to_le_bytes());
+    out[ix * 8 + 4..ix * 8 + 8].copy_from_slice(&value.to_be_bytes());
+  }
+}
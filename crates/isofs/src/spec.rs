@@ -38,63 +38,151 @@ pub struct Identifier {
 }
 
 impl Identifier {
-  const A_CHARACTERS: &[u8] = b" !\"%&'()*+,-./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ:;<=>?";
-  const D_CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+  const A_CHARACTERS: &[u8] = b" !\"%&'()*+,-./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ:;<=>?_";
+  pub(crate) const D_CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+  /// d-characters along with the separators of file identifiers, `.` and `;`.
+  const FILE_CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_.;";
 
   pub fn kind(&self) -> IdentifierKind {
     self.kind
   }
 
-  pub fn file_identifier(
-    name: impl AsRef<str>,
-    compatibility_mode: CompatibilityMode,
-  ) -> Option<Self> {
-    match compatibility_mode {
-      CompatibilityMode::Joliet(level) => Self::joliet_file_identifier(name, level),
-      CompatibilityMode::Standard => Self::standard_file_identifier(name),
+  /// Bytes of the identifier, excluding any padding.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.data[..self.length as usize]
+  }
+
+  /// Creates an identifier from bytes as they were recorded on disk.
+  pub(crate) fn from_bytes(kind: IdentifierKind, bytes: &[u8]) -> Self {
+    let mut data = [0u8; 256];
+    let length = bytes.len().min(255);
+
+    data[..length].copy_from_slice(&bytes[..length]);
+
+    Self {
+      kind,
+      data,
+      length: length as u8,
+      padding: 0,
     }
   }
 
-  pub fn joliet_file_identifier(name: impl AsRef<str>, _level: JolietLevel) -> Option<Self> {
-    // TODO(meowesque): Implement level.
-    let count = name.as_ref().chars().count();
+  /// Creates an identifier from a fixed-length field as it was recorded on disk,
+  /// treating trailing filler (spaces or zeroes) as padding.
+  pub(crate) fn from_padded_bytes(kind: IdentifierKind, bytes: &[u8]) -> Self {
+    let mut identifier = Self::from_bytes(kind, bytes);
+    let length = bytes
+      .iter()
+      .rposition(|&b| b != b' ' && b != 0)
+      .map_or(0, |ix| ix + 1);
+
+    identifier.padding = identifier.length - length as u8;
+    identifier.length = length as u8;
+    identifier
+  }
 
-    if count > 64 {
-      return None;
+  /// Orders identifiers the way the records of a directory are ordered (ECMA-119 9.3), `.` and
+  /// `..` first. File names are compared first and extensions second, the shorter of either
+  /// padded with spaces, then version numbers in descending order. Directory identifiers are
+  /// compared as file names.
+  pub fn cmp_record_order(&self, other: &Self) -> std::cmp::Ordering {
+    fn parts(identifier: &Identifier) -> (String, String, u32) {
+      let full = match identifier.kind {
+        IdentifierKind::CurrentDirectory => return ("\0".to_string(), String::new(), 0),
+        IdentifierKind::ParentDirectory => return ("\u{1}".to_string(), String::new(), 0),
+        _ => identifier.to_string(),
+      };
+
+      match identifier.kind {
+        IdentifierKind::StandardDirectoryIdentifier
+        | IdentifierKind::JolietDirectoryIdentifier
+        | IdentifierKind::RootDirectory => (full, String::new(), 0),
+        _ => {
+          let (rest, version) = full.rsplit_once(';').unwrap_or((&full, ""));
+          let (name, extension) = rest.rsplit_once('.').unwrap_or((rest, ""));
+
+          (
+            name.to_string(),
+            extension.to_string(),
+            version.parse().unwrap_or(0),
+          )
+        }
+      }
     }
 
-    let mut ucs2: [u16; 64] = [0; 64];
+    fn cmp_padded(a: &str, b: &str) -> std::cmp::Ordering {
+      let length = a.chars().count().max(b.chars().count());
+      let padded = |s: &str| {
+        s.chars()
+          .chain(std::iter::repeat(' '))
+          .take(length)
+          .collect::<Vec<_>>()
+      };
 
-    ucs2::encode(name.as_ref(), &mut ucs2).ok()?;
+      padded(a).cmp(&padded(b))
+    }
 
-    let mut data: [u8; 256] = [0; 256];
+    let (name, extension, version) = parts(self);
+    let (other_name, other_extension, other_version) = parts(other);
 
-    for (ix, &c) in ucs2.iter().enumerate().take(count) {
-      let bytes = c.to_be_bytes();
+    cmp_padded(&name, &other_name)
+      .then_with(|| cmp_padded(&extension, &other_extension))
+      .then_with(|| other_version.cmp(&version))
+  }
 
-      data[ix * 2] = bytes[0];
-      data[ix * 2 + 1] = bytes[1];
+  pub fn file_identifier(
+    name: impl AsRef<str>,
+    compatibility_mode: CompatibilityMode,
+  ) -> Result<Self, IdentifierError> {
+    match compatibility_mode {
+      CompatibilityMode::Joliet(level) => Self::joliet_file_identifier(name, level),
+      CompatibilityMode::Standard(level) => Self::standard_file_identifier(name, level),
     }
+  }
 
-    Some(Self {
-      kind: IdentifierKind::JolietFileIdentifier,
-      data,
-      length: (count * 2) as u8,
-      padding: 0,
-    })
+  pub fn directory_identifier(
+    name: impl AsRef<str>,
+    compatibility_mode: CompatibilityMode,
+  ) -> Result<Self, IdentifierError> {
+    match compatibility_mode {
+      CompatibilityMode::Joliet(level) => Self::joliet_directory_identifier(name, level),
+      CompatibilityMode::Standard(level) => Self::standard_directory_identifier(name, level),
+    }
+  }
+
+  pub fn joliet_file_identifier(
+    name: impl AsRef<str>,
+    level: JolietLevel,
+  ) -> Result<Self, IdentifierError> {
+    Self::from_parts_joliet(IdentifierKind::JolietFileIdentifier, name, level)
   }
 
-  pub fn joliet_directory_identifier(name: impl AsRef<str>, _level: JolietLevel) -> Option<Self> {
-    // TODO(meowesque): Implement level.
-    let count = name.as_ref().chars().count();
+  pub fn joliet_directory_identifier(
+    name: impl AsRef<str>,
+    level: JolietLevel,
+  ) -> Result<Self, IdentifierError> {
+    Self::from_parts_joliet(IdentifierKind::JolietDirectoryIdentifier, name, level)
+  }
 
-    if count > 64 {
-      return None;
+  fn from_parts_joliet(
+    kind: IdentifierKind,
+    name: impl AsRef<str>,
+    level: JolietLevel,
+  ) -> Result<Self, IdentifierError> {
+    let name = name.as_ref();
+    let count = name.chars().count();
+
+    if name.is_empty() {
+      return Err(IdentifierError::Empty { kind });
     }
 
+    // UCS-2 lacks the characters past the BMP.
+    IdentifierError::check_characters(kind, name, 0, |c| level.permits(c) && c <= '\u{FFFF}')?;
+    IdentifierError::check_length(kind, count, 64)?;
+
     let mut ucs2: [u16; 64] = [0; 64];
 
-    ucs2::encode(name.as_ref(), &mut ucs2).ok()?;
+    ucs2::encode(name, &mut ucs2).expect("Characters should be within the BMP");
 
     let mut data: [u8; 256] = [0; 256];
 
@@ -105,8 +193,8 @@ impl Identifier {
       data[ix * 2 + 1] = bytes[1];
     }
 
-    Some(Self {
-      kind: IdentifierKind::JolietFileIdentifier,
+    Ok(Self {
+      kind,
       data,
       length: (count * 2) as u8,
       padding: 0,
@@ -143,8 +231,20 @@ impl Identifier {
     }
   }
 
-  pub fn standard_directory_identifier(name: impl AsRef<str>) -> Option<Self> {
-    let mut data: [u8; 256] = [0; 256];
+  pub fn standard_directory_identifier(
+    name: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    let kind = IdentifierKind::StandardDirectoryIdentifier;
+    let name = name.as_ref();
+
+    if level == InterchangeLevel::Iso9660_1999 {
+      return Self::from_parts_iso9660_1999(kind, name);
+    }
+
+    if name.is_empty() {
+      return Err(IdentifierError::Empty { kind });
+    }
 
     let convert = |b: u8| {
       let b = b.to_ascii_uppercase();
@@ -156,60 +256,71 @@ impl Identifier {
       }
     };
 
-    for (ix, &b) in name.as_ref().as_bytes().iter().enumerate() {
-      let b = convert(b);
+    IdentifierError::check_characters(kind, name, 0, |c| {
+      c.is_ascii() && Self::D_CHARACTERS.contains(&convert(c as u8))
+    })?;
+    IdentifierError::check_length(kind, name.len(), level.max_directory_identifier_length())?;
 
-      if ix >= 31 || !Self::D_CHARACTERS.contains(&b) {
-        // TODO(meowesque): Provide better error reporting.
-        return None;
-      }
+    let mut data: [u8; 256] = [0; 256];
 
-      data[ix] = b;
+    for (ix, &b) in name.as_bytes().iter().enumerate() {
+      data[ix] = convert(b);
     }
 
-    Some(Self {
-      kind: IdentifierKind::StandardDirectoryIdentifier,
+    Ok(Self {
+      kind,
       data,
-      length: name.as_ref().len() as u8,
+      length: name.len() as u8,
       padding: 0,
     })
   }
 
-  pub fn standard_file_identifier(full: impl AsRef<str>) -> Option<Self> {
+  pub fn standard_file_identifier(
+    full: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    let kind = IdentifierKind::StandardFileIdentifier;
     let full = full.as_ref();
 
-    if full.len() > 35 || (!full.contains('.') && full.len() > 34) {
-      // TODO(meowesque): Provide better error reporting.
-      return None;
+    if level == InterchangeLevel::Iso9660_1999 {
+      return Self::from_parts_iso9660_1999(kind, full);
     }
 
-    let stem = full.split('.').next()?; // TODO(meowesque): Provide better error reporting.
-    let ext = full.rsplit('.').next().unwrap_or("");
+    if full.is_empty() {
+      return Err(IdentifierError::Empty { kind });
+    }
 
-    // TODO(meowesque): Maximum compatibility mode only allows 3 character extensions.
-    /*
-    if ext.len() > 3 {
-      // TODO(meowesque): Provide better error reporting.
-      return None;
-    } */
+    let (stem, ext) = full.rsplit_once('.').unwrap_or((full, ""));
+    let permitted =
+      |c: char| c.is_ascii() && Self::D_CHARACTERS.contains(&(c as u8).to_ascii_uppercase());
 
-    let mut new_name = [0u8; 37];
+    IdentifierError::check_characters(kind, stem, 0, permitted)?;
+    IdentifierError::check_characters(kind, ext, stem.chars().count() + 1, permitted)?;
 
-    for (ix, &b) in stem.as_bytes().iter().enumerate() {
-      if !Self::D_CHARACTERS.contains(&b.to_ascii_uppercase()) {
-        return None;
+    match level {
+      InterchangeLevel::Level1 => {
+        IdentifierError::check_length(kind, stem.len(), 8)?;
+
+        if ext.len() > 3 {
+          return Err(IdentifierError::ExtensionTooLong {
+            kind,
+            length: ext.len(),
+            limit: 3,
+          });
+        }
       }
+      _ => IdentifierError::check_length(kind, stem.len() + ext.len(), 30)?,
+    }
 
+    let mut new_name = [0u8; 37];
+
+    for (ix, &b) in stem.as_bytes().iter().enumerate() {
       new_name[ix] = b.to_ascii_uppercase();
     }
 
     new_name[stem.len()] = b'.';
 
     for (ix, &b) in ext.as_bytes().iter().enumerate() {
-      if !Self::D_CHARACTERS.contains(&b.to_ascii_uppercase()) {
-        return None;
-      }
-
       new_name[stem.len() + 1 + ix] = b.to_ascii_uppercase();
     }
 
@@ -220,131 +331,240 @@ impl Identifier {
 
     data[..new_name.len()].copy_from_slice(&new_name);
 
-    Some(Self {
-      kind: IdentifierKind::StandardFileIdentifier,
+    Ok(Self {
+      kind,
       data,
       length: (stem.len() + 1 + ext.len() + 2) as u8,
       padding: 0,
     })
   }
 
-  pub fn system_identifier(name: impl AsRef<str>) -> Option<Self> {
+  /// ISO 9660:1999 identifiers are recorded as is, without separators or version numbers,
+  /// excluding only control characters and those readers split paths or versions on.
+  fn from_parts_iso9660_1999(kind: IdentifierKind, name: &str) -> Result<Self, IdentifierError> {
+    if name.is_empty() {
+      return Err(IdentifierError::Empty { kind });
+    }
+
+    IdentifierError::check_characters(kind, name, 0, |c| !c.is_control() && c != '/' && c != ';')?;
+    IdentifierError::check_length(
+      kind,
+      name.len(),
+      InterchangeLevel::Iso9660_1999.max_directory_identifier_length(),
+    )?;
+
+    Ok(Self::from_bytes(kind, name.as_bytes()))
+  }
+
+  pub fn system_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::ACharacters,
       name,
       32,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn volume_identifier(name: impl AsRef<str>) -> Option<Self> {
+  pub fn volume_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::DCharacters,
       name,
       32,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn volume_set_identifier(name: impl AsRef<str>) -> Option<Self> {
+  pub fn volume_set_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::DCharacters,
       name,
       128,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn publisher_identifier(name: impl AsRef<str>) -> Option<Self> {
+  pub fn publisher_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::DCharacters,
       name,
       128,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn data_preparer_identifier(name: impl AsRef<str>) -> Option<Self> {
+  pub fn data_preparer_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::DCharacters,
       name,
       128,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn application_identifier(name: impl AsRef<str>) -> Option<Self> {
+  pub fn application_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
     Self::from_parts_ascii(
       IdentifierKind::ACharacters,
       name,
       128,
-      |x| Self::A_CHARACTERS.contains(&x),
+      Self::A_CHARACTERS,
       b' ',
     )
   }
 
-  pub fn copyright_file_identifier(name: impl AsRef<str>) -> Option<Self> {
-    Self::from_parts_ascii(
-      IdentifierKind::DCharacters,
-      name,
-      37,
-      |x| Self::D_CHARACTERS.contains(&x),
-      0,
-    )
+  pub fn copyright_file_identifier(
+    name: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    Self::volume_file_identifier(name, level)
   }
 
-  pub fn abstract_file_identifier(name: impl AsRef<str>) -> Option<Self> {
-    Self::from_parts_ascii(
-      IdentifierKind::DCharacters,
-      name,
-      37,
-      |x| Self::D_CHARACTERS.contains(&x),
-      0,
-    )
+  pub fn abstract_file_identifier(
+    name: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    Self::volume_file_identifier(name, level)
   }
 
-  pub fn bibliographic_file_identifier(name: impl AsRef<str>) -> Option<Self> {
-    Self::from_parts_ascii(
-      IdentifierKind::DCharacters,
-      name,
-      37,
-      |x| Self::D_CHARACTERS.contains(&x),
-      0,
-    )
+  pub fn bibliographic_file_identifier(
+    name: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    Self::volume_file_identifier(name, level)
   }
 
-  fn from_parts_ascii(
+  /// Identifier of a file within the root directory, as volume descriptors refer to it. File
+  /// identifiers of ISO 9660:1999 aren't limited to d-characters.
+  fn volume_file_identifier(
+    name: impl AsRef<str>,
+    level: InterchangeLevel,
+  ) -> Result<Self, IdentifierError> {
+    match level {
+      InterchangeLevel::Iso9660_1999 => Self::from_parts(
+        IdentifierKind::D1Characters,
+        name,
+        37,
+        |c| !c.is_control() && c != '/' && c != ';',
+        0,
+      ),
+      _ => Self::from_parts_ascii(
+        IdentifierKind::DCharacters,
+        name,
+        37,
+        Self::FILE_CHARACTERS,
+        0,
+      ),
+    }
+  }
+
+  pub fn joliet_system_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::A1Characters, name, 32, [0x00, 0x20])
+  }
+
+  pub fn joliet_volume_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::D1Characters, name, 32, [0x00, 0x20])
+  }
+
+  pub fn joliet_volume_set_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::D1Characters, name, 128, [0x00, 0x20])
+  }
+
+  pub fn joliet_publisher_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::A1Characters, name, 128, [0x00, 0x20])
+  }
+
+  pub fn joliet_data_preparer_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::A1Characters, name, 128, [0x00, 0x20])
+  }
+
+  pub fn joliet_application_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::A1Characters, name, 128, [0x00, 0x20])
+  }
+
+  pub fn joliet_copyright_file_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::D1Characters, name, 37, [0x00, 0x00])
+  }
+
+  pub fn joliet_abstract_file_identifier(name: impl AsRef<str>) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::D1Characters, name, 37, [0x00, 0x00])
+  }
+
+  pub fn joliet_bibliographic_file_identifier(
+    name: impl AsRef<str>,
+  ) -> Result<Self, IdentifierError> {
+    Self::from_parts_ucs2(IdentifierKind::D1Characters, name, 37, [0x00, 0x00])
+  }
+
+  /// Encodes `s` as UCS-2 big endian, padded with `padding` up to `max` bytes.
+  fn from_parts_ucs2(
     kind: IdentifierKind,
     s: impl AsRef<str>,
     max: u8,
-    charset: fn(u8) -> bool,
-    padding: u8,
-  ) -> Option<Self> {
+    padding: [u8; 2],
+  ) -> Result<Self, IdentifierError> {
     let s = s.as_ref();
-    let mut data = [0u8; 256];
 
-    if s.len() > max as usize {
-      return None;
-    }
+    IdentifierError::check_characters(kind, s, 0, |c| c <= '\u{FFFF}')?;
+    IdentifierError::check_length(kind, s.chars().count(), max as usize / 2)?;
 
-    for (i, &b) in s.as_bytes().iter().enumerate() {
-      if !charset(b) {
-        return None;
-      }
+    let mut data = [0u8; 256];
+    let mut length = 0;
 
-      data[i] = b;
+    for c in s.encode_utf16() {
+      data[length..length + 2].copy_from_slice(&c.to_be_bytes());
+      length += 2;
     }
 
-    for i in s.len()..(max as usize) {
-      data[i] = padding;
+    for pair in data[length..max as usize].chunks_exact_mut(2) {
+      pair.copy_from_slice(&padding);
     }
 
-    Some(Self {
+    Ok(Self {
+      kind,
+      data,
+      length: length as u8,
+      padding: max - length as u8,
+    })
+  }
+
+  fn from_parts_ascii(
+    kind: IdentifierKind,
+    s: impl AsRef<str>,
+    max: u8,
+    charset: &[u8],
+    padding: u8,
+  ) -> Result<Self, IdentifierError> {
+    Self::from_parts(
+      kind,
+      s,
+      max,
+      |c| c.is_ascii() && charset.contains(&(c as u8)),
+      padding,
+    )
+  }
+
+  /// Encodes `s` as UTF-8 of `permitted` characters, padded with `padding` up to `max` bytes.
+  fn from_parts(
+    kind: IdentifierKind,
+    s: impl AsRef<str>,
+    max: u8,
+    permitted: impl Fn(char) -> bool,
+    padding: u8,
+  ) -> Result<Self, IdentifierError> {
+    let s = s.as_ref();
+    let mut data = [0u8; 256];
+
+    IdentifierError::check_characters(kind, s, 0, permitted)?;
+    IdentifierError::check_length(kind, s.len(), max as usize)?;
+
+    data[..s.len()].copy_from_slice(s.as_bytes());
+    data[s.len()..max as usize].fill(padding);
+
+    Ok(Self {
       kind,
       data,
       length: s.len() as u8,
@@ -353,50 +573,235 @@ impl Identifier {
   }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Reason a name can't be recorded as an identifier of some kind.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IdentifierError {
+  #[error("{kind:?} is empty")]
+  Empty { kind: IdentifierKind },
+  #[error("{kind:?} can't hold {character:?} at position {position}")]
+  InvalidCharacter {
+    kind: IdentifierKind,
+    character: char,
+    /// Position of the character within the name, in characters.
+    position: usize,
+  },
+  #[error("{kind:?} of length {length} exceeds the limit of {limit}")]
+  TooLong {
+    kind: IdentifierKind,
+    length: usize,
+    limit: usize,
+  },
+  #[error("{kind:?} extension of length {length} exceeds the limit of {limit}")]
+  ExtensionTooLong {
+    kind: IdentifierKind,
+    length: usize,
+    limit: usize,
+  },
+}
+
+impl IdentifierError {
+  /// Fails on the first character of `s` that isn't `permitted`, `s` starting at `offset`
+  /// characters into the name.
+  fn check_characters(
+    kind: IdentifierKind,
+    s: &str,
+    offset: usize,
+    permitted: impl Fn(char) -> bool,
+  ) -> Result<(), Self> {
+    match s.chars().enumerate().find(|&(_, c)| !permitted(c)) {
+      Some((position, character)) => Err(Self::InvalidCharacter {
+        kind,
+        character,
+        position: offset + position,
+      }),
+      None => Ok(()),
+    }
+  }
+
+  fn check_length(kind: IdentifierKind, length: usize, limit: usize) -> Result<(), Self> {
+    match length > limit {
+      true => Err(Self::TooLong {
+        kind,
+        length,
+        limit,
+      }),
+      false => Ok(()),
+    }
+  }
+}
+
+impl PartialEq for Identifier {
+  fn eq(&self, other: &Self) -> bool {
+    let extent = self.length as usize + self.padding as usize;
+
+    self.kind == other.kind
+      && self.length == other.length
+      && self.padding == other.padding
+      && self.data[..extent] == other.data[..extent]
+  }
+}
+
+impl Eq for Identifier {}
+
+impl std::fmt::Display for Identifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.kind {
+      IdentifierKind::JolietFileIdentifier | IdentifierKind::JolietDirectoryIdentifier => {
+        let units = self
+          .as_bytes()
+          .chunks_exact(2)
+          .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+
+        char::decode_utf16(units)
+          .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+          .try_for_each(|c| write!(f, "{c}"))
+      }
+      IdentifierKind::CurrentDirectory => write!(f, "."),
+      IdentifierKind::ParentDirectory => write!(f, ".."),
+      _ => write!(f, "{}", String::from_utf8_lossy(self.as_bytes())),
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum JolietLevel {
-  /// UCS-2 Level 1
+  /// UCS-2 Level 1, no combining characters.
   Level1,
-  /// UCS-2 Level 2
+  /// UCS-2 Level 2, no combining characters from the generic diacritical blocks.
   Level2,
-  /// UCS-2 Level 3
+  /// UCS-2 Level 3, all characters.
   Level3,
 }
 
-#[derive(Debug)]
+impl JolietLevel {
+  /// Characters Joliet never permits within file and directory identifiers.
+  const RESERVED: &[char] = &['*', '/', ':', ';', '?', '\\'];
+
+  /// Combining characters excluded from UCS-2 Level 2 (ISO/IEC 10646-1 Annex B.2).
+  const LEVEL_2_EXCLUDED: &[(char, char)] = &[
+    ('\u{0300}', '\u{0345}'),
+    ('\u{0360}', '\u{0361}'),
+    ('\u{0483}', '\u{0486}'),
+    ('\u{20D0}', '\u{20E1}'),
+    ('\u{302A}', '\u{302F}'),
+    ('\u{3099}', '\u{309A}'),
+    ('\u{FE20}', '\u{FE23}'),
+  ];
+
+  /// Whether `c` belongs to the repertoire of this level.
+  pub fn permits(&self, c: char) -> bool {
+    if c < ' ' || Self::RESERVED.contains(&c) {
+      return false;
+    }
+
+    match self {
+      Self::Level1 => !unicode_normalization::char::is_combining_mark(c),
+      Self::Level2 => !Self::LEVEL_2_EXCLUDED
+        .iter()
+        .any(|&(start, end)| (start..=end).contains(&c)),
+      Self::Level3 => true,
+    }
+  }
+}
+
+/// Interchange level of the primary hierarchy, restricting its identifiers and how file data is
+/// recorded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InterchangeLevel {
+  /// 8.3 file identifiers, 8 character directory identifiers and files recorded within a single
+  /// extent.
+  Level1,
+  /// 31 character identifiers and files recorded within a single extent.
+  Level2,
+  /// 31 character identifiers and files recorded across multiple extents.
+  #[default]
+  Level3,
+  /// ISO 9660:1999, announced by an enhanced volume descriptor (version 2). Identifiers of up to
+  /// 207 characters without version numbers, files recorded across multiple extents and
+  /// hierarchies deeper than 8 levels.
+  Iso9660_1999,
+}
+
+impl InterchangeLevel {
+  /// Maximum length of a directory identifier. ISO 9660:1999 file identifiers share it.
+  pub fn max_directory_identifier_length(&self) -> usize {
+    match self {
+      Self::Level1 => 8,
+      Self::Level2 | Self::Level3 => 31,
+      Self::Iso9660_1999 => 207,
+    }
+  }
+
+  /// Whether files may be recorded across multiple extents, such as those larger than 4 GiB.
+  pub fn permits_multi_extent(&self) -> bool {
+    matches!(self, Self::Level3 | Self::Iso9660_1999)
+  }
+
+  /// Whether the hierarchy may be deeper than 8 levels.
+  pub fn permits_deep_hierarchies(&self) -> bool {
+    matches!(self, Self::Iso9660_1999)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompatibilityMode {
   Joliet(JolietLevel),
-  // TODO(meowesque): Add 8.3?
-  Standard,
+  Standard(InterchangeLevel),
 }
 
 /// Escape sequences conforming to ISO/IEC 2022, including the escape characters.
 ///
 /// If all the bytes of the escape sequences are zero, it shall mean that the set
 /// of a1-characters is identical to the set of a-characters.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct EscapeSequences<const LENGTH: usize>(pub(crate) [u8; LENGTH]);
 
 impl<const LENGTH: usize> EscapeSequences<LENGTH> {
-  pub const fn joliet_level_3() -> Self {
+  pub const fn joliet(level: JolietLevel) -> Self {
     let mut data = [b' '; LENGTH];
 
     if LENGTH >= 4 {
       data[0] = 0x25; // '%'
       data[1] = 0x2F; // '/'
-      data[2] = 0x45; // 'E'
+      data[2] = match level {
+        JolietLevel::Level1 => 0x40, // '@'
+        JolietLevel::Level2 => 0x43, // 'C'
+        JolietLevel::Level3 => 0x45, // 'E'
+      };
     }
 
     Self(data)
   }
+
+  pub const fn joliet_level_1() -> Self {
+    Self::joliet(JolietLevel::Level1)
+  }
+
+  pub const fn joliet_level_2() -> Self {
+    Self::joliet(JolietLevel::Level2)
+  }
+
+  pub const fn joliet_level_3() -> Self {
+    Self::joliet(JolietLevel::Level3)
+  }
+
+  /// Joliet level announced by these escape sequences, if any.
+  pub fn joliet_level(&self) -> Option<JolietLevel> {
+    match self.0.get(..3)? {
+      [0x25, 0x2F, 0x40] => Some(JolietLevel::Level1),
+      [0x25, 0x2F, 0x43] => Some(JolietLevel::Level2),
+      [0x25, 0x2F, 0x45] => Some(JolietLevel::Level3),
+      _ => None,
+    }
+  }
 }
 
 /// Escape sequences conforming to ISO/IEC 2022, excluding the escape characters.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct VariadicEscapeSequences(pub(crate) Vec<u8>);
 
 bitflags::bitflags! {
-  #[derive(Debug)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
   pub struct FileFlags: u8 {
     const EXISTENCE = 1 << 0;
     const DIRECTORY = 1 << 1;
@@ -408,7 +813,7 @@ bitflags::bitflags! {
     const MULTI_EXTENT = 1 << 7;
   }
 
-  #[derive(Debug)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
   pub struct Permissions: u16 {
     const SYSTEM_READ = 1 << 0;
     /// "Shall be set to 1."
@@ -436,7 +841,7 @@ bitflags::bitflags! {
     const PERMISSION_15 = 1 << 15;
   }
 
-  #[derive(Debug)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
   pub struct VolumeFlags: u8 {
     /// If zero, shall mean that the escape sequences field specifies only
     /// escape sequences registered by ISO/IEC 2375.
@@ -455,15 +860,15 @@ bitflags::bitflags! {
 }
 
 /// TODO(meowesque): Define this better?
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct OwnerIdentification(pub(crate) u16);
 
 /// TODO(meowesque): Define this better?
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct GroupIdentification(pub(crate) u16);
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordFormat {
   StructureNotSpecified = 0,
   FixedLengthRecords = 1,
@@ -472,9 +877,9 @@ pub enum RecordFormat {
   Other(u8),
 }
 
-impl Into<u8> for RecordFormat {
-  fn into(self) -> u8 {
-    match self {
+impl From<RecordFormat> for u8 {
+  fn from(value: RecordFormat) -> Self {
+    match value {
       RecordFormat::StructureNotSpecified => 0,
       RecordFormat::FixedLengthRecords => 1,
       RecordFormat::VariableLengthRecordsMsb => 2,
@@ -484,8 +889,20 @@ impl Into<u8> for RecordFormat {
   }
 }
 
+impl From<u8> for RecordFormat {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => RecordFormat::StructureNotSpecified,
+      1 => RecordFormat::FixedLengthRecords,
+      2 => RecordFormat::VariableLengthRecordsMsb,
+      3 => RecordFormat::VariableLengthRecordsLsb,
+      v => RecordFormat::Other(v),
+    }
+  }
+}
+
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordAttributes {
   PreceededByLfcFollowedByCrc = 0,
   /// First byte of the record shall be interpreted as specified in ISO/IEC 1539-1 for vertical spacing.
@@ -494,9 +911,9 @@ pub enum RecordAttributes {
   Other(u8),
 }
 
-impl Into<u8> for RecordAttributes {
-  fn into(self) -> u8 {
-    match self {
+impl From<RecordAttributes> for u8 {
+  fn from(value: RecordAttributes) -> Self {
+    match value {
       RecordAttributes::PreceededByLfcFollowedByCrc => 0,
       RecordAttributes::FirstByteInterpretedByIso15391 => 1,
       RecordAttributes::ContainsNecessaryControlInformation => 2,
@@ -505,23 +922,43 @@ impl Into<u8> for RecordAttributes {
   }
 }
 
+impl From<u8> for RecordAttributes {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => RecordAttributes::PreceededByLfcFollowedByCrc,
+      1 => RecordAttributes::FirstByteInterpretedByIso15391,
+      2 => RecordAttributes::ContainsNecessaryControlInformation,
+      v => RecordAttributes::Other(v),
+    }
+  }
+}
+
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExtendedAttributeRecordVersion {
   Standard = 1,
   Other(u8),
 }
 
-impl Into<u8> for ExtendedAttributeRecordVersion {
-  fn into(self) -> u8 {
-    match self {
+impl From<ExtendedAttributeRecordVersion> for u8 {
+  fn from(value: ExtendedAttributeRecordVersion) -> Self {
+    match value {
       ExtendedAttributeRecordVersion::Standard => 1,
       ExtendedAttributeRecordVersion::Other(v) => v,
     }
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<u8> for ExtendedAttributeRecordVersion {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => ExtendedAttributeRecordVersion::Standard,
+      v => ExtendedAttributeRecordVersion::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StandardIdentifier {
   /// Standard ISO 9660 identifier; "CD001"
   Cd001,
@@ -553,8 +990,22 @@ impl StandardIdentifier {
   }
 }
 
+impl From<[u8; 5]> for StandardIdentifier {
+  fn from(value: [u8; 5]) -> Self {
+    match &value {
+      b"CD001" => StandardIdentifier::Cd001,
+      b"BEA01" => StandardIdentifier::Bea01,
+      b"NSR02" => StandardIdentifier::Nsr02,
+      b"NSR03" => StandardIdentifier::Nsr03,
+      b"BOOT2" => StandardIdentifier::Boot2,
+      b"TEA01" => StandardIdentifier::Tea01,
+      _ => StandardIdentifier::Other(value),
+    }
+  }
+}
+
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VolumeDescriptorType {
   BootRecord = 0,
   Primary = 1,
@@ -564,9 +1015,9 @@ pub enum VolumeDescriptorType {
   Terminator = 255,
 }
 
-impl Into<u8> for VolumeDescriptorType {
-  fn into(self) -> u8 {
-    match self {
+impl From<VolumeDescriptorType> for u8 {
+  fn from(value: VolumeDescriptorType) -> Self {
+    match value {
       VolumeDescriptorType::BootRecord => 0,
       VolumeDescriptorType::Primary => 1,
       VolumeDescriptorType::Supplementary => 2,
@@ -577,81 +1028,118 @@ impl Into<u8> for VolumeDescriptorType {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<u8> for VolumeDescriptorType {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => VolumeDescriptorType::BootRecord,
+      1 => VolumeDescriptorType::Primary,
+      2 => VolumeDescriptorType::Supplementary,
+      3 => VolumeDescriptorType::Partition,
+      255 => VolumeDescriptorType::Terminator,
+      v => VolumeDescriptorType::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum VolumeDescriptorVersion {
   Standard = 1,
   Other(u8),
 }
 
-impl Into<u8> for VolumeDescriptorVersion {
-  fn into(self) -> u8 {
-    match self {
+impl From<VolumeDescriptorVersion> for u8 {
+  fn from(value: VolumeDescriptorVersion) -> Self {
+    match value {
       VolumeDescriptorVersion::Standard => 1,
       VolumeDescriptorVersion::Other(v) => v,
     }
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<u8> for VolumeDescriptorVersion {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => VolumeDescriptorVersion::Standard,
+      v => VolumeDescriptorVersion::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FileStructureVersion {
   Standard = 1,
   Other(u8),
 }
 
-impl Into<u8> for FileStructureVersion {
-  fn into(self) -> u8 {
-    match self {
+impl From<FileStructureVersion> for u8 {
+  fn from(value: FileStructureVersion) -> Self {
+    match value {
       FileStructureVersion::Standard => 1,
       FileStructureVersion::Other(v) => v,
     }
   }
 }
 
-#[derive(Debug, Clone)]
+impl From<u8> for FileStructureVersion {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => FileStructureVersion::Standard,
+      v => FileStructureVersion::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsYear(pub(crate) u16);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsMonth(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsDay(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsHour(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsMinute(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsHundreths(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsSecond(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalYear(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalMonth(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalDay(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalHour(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalMinute(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalSecond(pub(crate) u8);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalGmtOffset(pub(crate) i8);
 
-#[derive(Debug, Clone)]
+/// Date that cannot be represented as a calendar date, e.g. an unspecified
+/// (all zero) date.
+#[derive(Debug, thiserror::Error)]
+#[error("Date is not representable")]
+pub struct InvalidDate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigitsDate {
   pub year: DigitsYear,
   pub month: DigitsMonth,
@@ -683,13 +1171,31 @@ impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for DigitsDate {
 }
 
 #[cfg(feature = "chrono")]
-impl<Tz: chrono::TimeZone> Into<chrono::DateTime<Tz>> for DigitsDate {
-  fn into(self) -> chrono::DateTime<Tz> {
-    todo!()
+impl TryFrom<DigitsDate> for chrono::DateTime<chrono::FixedOffset> {
+  type Error = InvalidDate;
+
+  fn try_from(date: DigitsDate) -> Result<Self, Self::Error> {
+    use chrono::TimeZone;
+
+    chrono::FixedOffset::east_opt(date.gmt_offset.0 as i32 * 15 * 60)
+      .and_then(|offset| {
+        offset
+          .with_ymd_and_hms(
+            date.year.0 as i32,
+            date.month.0 as u32,
+            date.day.0 as u32,
+            date.hour.0 as u32,
+            date.minute.0 as u32,
+            date.second.0 as u32,
+          )
+          .single()
+      })
+      .map(|dt| dt + chrono::Duration::milliseconds(date.hundreths.0 as i64 * 10))
+      .ok_or(InvalidDate)
   }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NumericalDate {
   pub years_since_1900: NumericalYear,
   pub month: NumericalMonth,
@@ -719,13 +1225,30 @@ impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for NumericalDate {
 }
 
 #[cfg(feature = "chrono")]
-impl<Tz: chrono::TimeZone> Into<chrono::DateTime<Tz>> for NumericalDate {
-  fn into(self) -> chrono::DateTime<Tz> {
-    todo!()
+impl TryFrom<NumericalDate> for chrono::DateTime<chrono::FixedOffset> {
+  type Error = InvalidDate;
+
+  fn try_from(date: NumericalDate) -> Result<Self, Self::Error> {
+    use chrono::TimeZone;
+
+    chrono::FixedOffset::east_opt(date.gmt_offset.0 as i32 * 15 * 60)
+      .and_then(|offset| {
+        offset
+          .with_ymd_and_hms(
+            1900 + date.years_since_1900.0 as i32,
+            date.month.0 as u32,
+            date.day.0 as u32,
+            date.hour.0 as u32,
+            date.minute.0 as u32,
+            date.second.0 as u32,
+          )
+          .single()
+      })
+      .ok_or(InvalidDate)
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct PrimaryVolumeDescriptor {
   pub standard_identifier: StandardIdentifier,
   pub version: VolumeDescriptorVersion,
@@ -756,7 +1279,7 @@ pub struct PrimaryVolumeDescriptor {
   pub application_use: [u8; 512],
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct SupplementaryVolumeDescriptor {
   pub standard_identifier: StandardIdentifier,
   pub version: VolumeDescriptorVersion,
@@ -789,7 +1312,7 @@ pub struct SupplementaryVolumeDescriptor {
   pub application_use: [u8; 512],
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct VolumePartitionDescriptor {
   pub standard_identifier: StandardIdentifier,
   pub version: VolumeDescriptorVersion,
@@ -799,10 +1322,10 @@ pub struct VolumePartitionDescriptor {
   pub volume_partition_size: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct VolumeDescriptorSetTerminator;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DirectoryRecord {
   pub extended_attribute_length: u8,
   pub extent_location: u32,
@@ -814,12 +1337,232 @@ pub struct DirectoryRecord {
   pub volume_sequence_number: u16,
   pub file_identifier_length: u8,
   pub file_identifier: Identifier,
+  /// System Use area following the file identifier.
+  pub system_use: Vec<SystemUseEntry>,
+}
+
+/// Entry of a System Use area, as defined by the System Use Sharing Protocol (SUSP).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemUseEntry {
+  /// `SP`: indicates SUSP is in use, found first in the `.` record of the root directory.
+  SharingProtocolIndicator {
+    /// Bytes skipped at the start of every other System Use area.
+    bytes_skipped: u8,
+  },
+  /// `CE`: continues the System Use area in a continuation area.
+  ContinuationArea {
+    block_location: u32,
+    offset: u32,
+    length: u32,
+  },
+  /// `PD`: padding of `length` zero bytes.
+  Padding { length: u8 },
+  /// `ST`: terminates the System Use area.
+  Terminator,
+  /// `ER`: identifies an extension in use, as recorded in the root directory.
+  ExtensionsReference {
+    version: u8,
+    identifier: String,
+    descriptor: String,
+    source: String,
+  },
+  /// `ES`: assigns the following entries to the extension of the `ER` entry at `sequence`.
+  ExtensionSelector { sequence: u8 },
+  /// `PX`: POSIX file attributes (RRIP). The serial number is only recorded since RRIP 1.12.
+  PosixFileAttributes {
+    mode: u32,
+    links: u32,
+    uid: u32,
+    gid: u32,
+    serial: Option<u32>,
+  },
+  /// `PN`: device number of a block or character device (RRIP).
+  DeviceNumber { high: u32, low: u32 },
+  /// `TF`: timestamps of the file (RRIP).
+  TimeStamps(TimeStamps),
+  /// `NM`: alternate name of the file (RRIP), continued by the next `NM` entry if flagged so.
+  AlternateName {
+    flags: AlternateNameFlags,
+    content: Vec<u8>,
+  },
+  /// `SL`: target of a symbolic link (RRIP), continued by the next `SL` entry if `continues`.
+  SymbolicLink {
+    continues: bool,
+    components: Vec<SymbolicLinkComponent>,
+  },
+  /// `CL`: location of a relocated directory, recorded in place of its record (RRIP).
+  ChildLink { block_location: u32 },
+  /// `PL`: location of the actual parent of a relocated directory, in its `..` record (RRIP).
+  ParentLink { block_location: u32 },
+  /// `RE`: marks the record of a relocated directory (RRIP).
+  Relocated,
+  /// `ZF`: file data compressed with `algorithm`, `pz` standing for zisofs.
+  CompressedFile {
+    algorithm: [u8; 2],
+    /// Size of the header of the compressed data, in 4 byte units.
+    header_size: u8,
+    block_size_log2: u8,
+    uncompressed_size: u32,
+  },
+  /// Entry not otherwise understood, kept as is.
+  Unknown {
+    signature: [u8; 2],
+    version: u8,
+    data: Vec<u8>,
+  },
+}
+
+impl SystemUseEntry {
+  /// Length of a `CE` entry, which must fit in front of any entries moved to a
+  /// continuation area.
+  pub const CONTINUATION_AREA_LENGTH: usize = 28;
+
+  pub fn signature(&self) -> [u8; 2] {
+    match self {
+      Self::SharingProtocolIndicator { .. } => *b"SP",
+      Self::ContinuationArea { .. } => *b"CE",
+      Self::Padding { .. } => *b"PD",
+      Self::Terminator => *b"ST",
+      Self::ExtensionsReference { .. } => *b"ER",
+      Self::ExtensionSelector { .. } => *b"ES",
+      Self::PosixFileAttributes { .. } => *b"PX",
+      Self::DeviceNumber { .. } => *b"PN",
+      Self::TimeStamps(_) => *b"TF",
+      Self::AlternateName { .. } => *b"NM",
+      Self::SymbolicLink { .. } => *b"SL",
+      Self::ChildLink { .. } => *b"CL",
+      Self::ParentLink { .. } => *b"PL",
+      Self::Relocated => *b"RE",
+      Self::CompressedFile { .. } => *b"ZF",
+      Self::Unknown { signature, .. } => *signature,
+    }
+  }
+}
+
+bitflags::bitflags! {
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  pub struct AlternateNameFlags: u8 {
+    const CONTINUE = 1 << 0;
+    const CURRENT = 1 << 1;
+    const PARENT = 1 << 2;
+  }
+
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  pub struct SymbolicLinkComponentFlags: u8 {
+    const CONTINUE = 1 << 0;
+    const CURRENT = 1 << 1;
+    const PARENT = 1 << 2;
+    const ROOT = 1 << 3;
+  }
+}
+
+/// Component of the target of a symbolic link, continued by the next component if flagged so.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolicLinkComponent {
+  pub flags: SymbolicLinkComponentFlags,
+  pub content: Vec<u8>,
+}
+
+/// File type bits of POSIX file modes, as recorded by `PX` entries.
+pub mod file_type {
+  pub const MASK: u32 = 0o170000;
+  pub const SOCKET: u32 = 0o140000;
+  pub const SYMBOLIC_LINK: u32 = 0o120000;
+  pub const REGULAR: u32 = 0o100000;
+  pub const BLOCK_DEVICE: u32 = 0o060000;
+  pub const DIRECTORY: u32 = 0o040000;
+  pub const CHARACTER_DEVICE: u32 = 0o020000;
+  pub const FIFO: u32 = 0o010000;
+}
+
+/// Header of file data compressed in the zisofs format, as announced by `ZF` entries. The
+/// header is followed by a table of block pointers and the zlib streams of the blocks.
+pub mod zisofs {
+  pub const ALGORITHM: [u8; 2] = *b"pz";
+  pub const MAGIC: [u8; 8] = [0x37, 0xE4, 0x53, 0x96, 0xC9, 0xDB, 0xD6, 0x07];
+  /// Size of the header in 4 byte units: the magic, the uncompressed size, this size, the block
+  /// size and two reserved bytes.
+  pub const HEADER_SIZE: u8 = 4;
+}
+
+/// Timestamps recorded by a `TF` entry, either all in the 7 byte form of directory records
+/// or all in the 17 byte form of volume descriptors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeStamps {
+  Short(RecordedTimeStamps<NumericalDate>),
+  Long(RecordedTimeStamps<DigitsDate>),
+}
+
+/// Timestamps of a `TF` entry, recorded in this order when present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedTimeStamps<T> {
+  pub creation: Option<T>,
+  pub modify: Option<T>,
+  pub access: Option<T>,
+  pub attributes: Option<T>,
+  pub backup: Option<T>,
+  pub expiration: Option<T>,
+  pub effective: Option<T>,
+}
+
+impl<T> Default for RecordedTimeStamps<T> {
+  fn default() -> Self {
+    Self {
+      creation: None,
+      modify: None,
+      access: None,
+      attributes: None,
+      backup: None,
+      expiration: None,
+      effective: None,
+    }
+  }
+}
+
+impl<T> RecordedTimeStamps<T> {
+  /// Long form flag of a `TF` entry.
+  pub const LONG_FORM: u8 = 0x80;
+
+  pub fn iter(&self) -> impl Iterator<Item = &Option<T>> {
+    [
+      &self.creation,
+      &self.modify,
+      &self.access,
+      &self.attributes,
+      &self.backup,
+      &self.expiration,
+      &self.effective,
+    ]
+    .into_iter()
+  }
+
+  pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Option<T>> {
+    [
+      &mut self.creation,
+      &mut self.modify,
+      &mut self.access,
+      &mut self.attributes,
+      &mut self.backup,
+      &mut self.expiration,
+      &mut self.effective,
+    ]
+    .into_iter()
+  }
+
+  /// Flags of a `TF` entry, one bit per timestamp present.
+  pub fn flags(&self) -> u8 {
+    self
+      .iter()
+      .enumerate()
+      .filter(|(_, x)| x.is_some())
+      .fold(0, |flags, (ix, _)| flags | 1 << ix)
+  }
 }
 
 /// Root directory record as found in `SupplementaryVolumeDescriptor` and
 /// `PrimaryVolumeDescriptor`. Like `DirectoryRecord` but without the `length`
 /// and `extended_attribute_length` fields.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RootDirectoryRecord {
   pub extent_location: u32,
   pub data_length: u32,
@@ -830,7 +1573,7 @@ pub struct RootDirectoryRecord {
   pub volume_sequence_number: u16,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct PathTableRecord {
   // TODO(meowesque): We can get rid of this field by using the length of `directory_identifier`.
   pub directory_identifier_length: u8,
@@ -840,7 +1583,7 @@ pub struct PathTableRecord {
   pub directory_identifier: Identifier,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ExtendedAttributeRecord {
   pub owner_identification: OwnerIdentification,
   pub group_identification: GroupIdentification,
@@ -851,48 +1594,74 @@ pub struct ExtendedAttributeRecord {
   pub file_effective_date: DigitsDate,
   pub record_format: RecordFormat,
   pub record_attributes: RecordAttributes,
+  pub record_length: u16,
+  pub system_identifier: Identifier,
+  pub system_use: [u8; 64],
   pub extended_attribute_record_version: ExtendedAttributeRecordVersion,
   pub application_use: Vec<u8>,
   pub escape_sequences: VariadicEscapeSequences,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ElToritoHeaderId {
   Standard = 1,
   Other(u8),
 }
 
-impl Into<u8> for ElToritoHeaderId {
-  fn into(self) -> u8 {
-    match self {
+impl From<ElToritoHeaderId> for u8 {
+  fn from(value: ElToritoHeaderId) -> Self {
+    match value {
       ElToritoHeaderId::Standard => 1,
       ElToritoHeaderId::Other(v) => v,
     }
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<u8> for ElToritoHeaderId {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => ElToritoHeaderId::Standard,
+      v => ElToritoHeaderId::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ElToritoPlatformId {
   X86 = 0,
   PowerPc = 1,
   Mac = 2,
+  Efi = 0xEF,
   Other(u8),
 }
 
-impl Into<u8> for ElToritoPlatformId {
-  fn into(self) -> u8 {
-    match self {
+impl From<ElToritoPlatformId> for u8 {
+  fn from(value: ElToritoPlatformId) -> Self {
+    match value {
       ElToritoPlatformId::X86 => 0,
       ElToritoPlatformId::PowerPc => 1,
       ElToritoPlatformId::Mac => 2,
+      ElToritoPlatformId::Efi => 0xEF,
       ElToritoPlatformId::Other(v) => v,
     }
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<u8> for ElToritoPlatformId {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => ElToritoPlatformId::X86,
+      1 => ElToritoPlatformId::PowerPc,
+      2 => ElToritoPlatformId::Mac,
+      0xEF => ElToritoPlatformId::Efi,
+      v => ElToritoPlatformId::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ElToritoBootIndicator {
   Bootable = 0x88,
@@ -900,9 +1669,9 @@ pub enum ElToritoBootIndicator {
   Other(u8),
 }
 
-impl Into<u8> for ElToritoBootIndicator {
-  fn into(self) -> u8 {
-    match self {
+impl From<ElToritoBootIndicator> for u8 {
+  fn from(value: ElToritoBootIndicator) -> Self {
+    match value {
       ElToritoBootIndicator::Bootable => 0x88,
       ElToritoBootIndicator::NonBootable => 0x00,
       ElToritoBootIndicator::Other(v) => v,
@@ -910,28 +1679,60 @@ impl Into<u8> for ElToritoBootIndicator {
   }
 }
 
-#[derive(Debug)]
-pub struct ElToritoManufacturerId(pub(crate) [u8; 16]);
+impl From<u8> for ElToritoBootIndicator {
+  fn from(value: u8) -> Self {
+    match value {
+      0x88 => ElToritoBootIndicator::Bootable,
+      0x00 => ElToritoBootIndicator::NonBootable,
+      v => ElToritoBootIndicator::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ElToritoManufacturerId(pub(crate) [u8; 24]);
+
+impl ElToritoManufacturerId {
+  /// Zero padded ASCII identifier of at most 24 bytes.
+  pub fn new(id: impl AsRef<str>) -> Option<Self> {
+    let id = id.as_ref();
+    let mut data = [0u8; 24];
+
+    if id.len() > data.len() || !id.is_ascii() {
+      return None;
+    }
+
+    data[..id.len()].copy_from_slice(id.as_bytes());
+
+    Some(Self(data))
+  }
+}
 
 bitflags::bitflags! {
-  #[derive(Debug)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
   pub struct ElToritoExtensionRecordFollowsIndicator: u8 {
     const EXTENSION_RECORD_FOLLOWS = 1 << 5;
   }
 }
 
 // TODO(meowesque): Implement
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ElToritoBootMediaType(pub(crate) u8);
 
-impl Into<u8> for ElToritoBootMediaType {
-  fn into(self) -> u8 {
-    self.0
+impl From<ElToritoBootMediaType> for u8 {
+  fn from(value: ElToritoBootMediaType) -> Self {
+    value.0
+  }
+}
+
+impl From<ElToritoEmulationType> for ElToritoBootMediaType {
+  fn from(value: ElToritoEmulationType) -> Self {
+    Self(value.into())
   }
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElToritoEmulationType {
   NoEmulation = 0,
   Floppy12M = 1,
@@ -940,9 +1741,9 @@ pub enum ElToritoEmulationType {
   HardDisk = 4,
 }
 
-impl Into<u8> for ElToritoEmulationType {
-  fn into(self) -> u8 {
-    match self {
+impl From<ElToritoEmulationType> for u8 {
+  fn from(value: ElToritoEmulationType) -> Self {
+    match value {
       ElToritoEmulationType::NoEmulation => 0,
       ElToritoEmulationType::Floppy12M => 1,
       ElToritoEmulationType::Floppy144M => 2,
@@ -952,7 +1753,34 @@ impl Into<u8> for ElToritoEmulationType {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl ElToritoEmulationType {
+  /// Size in bytes of the emulated floppy disk, if this is a floppy emulation.
+  pub fn floppy_size(&self) -> Option<u64> {
+    match self {
+      Self::Floppy12M => Some(1_228_800),
+      Self::Floppy144M => Some(1_474_560),
+      Self::Floppy288M => Some(2_949_120),
+      Self::NoEmulation | Self::HardDisk => None,
+    }
+  }
+}
+
+impl TryFrom<u8> for ElToritoEmulationType {
+  type Error = u8;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0 => Ok(ElToritoEmulationType::NoEmulation),
+      1 => Ok(ElToritoEmulationType::Floppy12M),
+      2 => Ok(ElToritoEmulationType::Floppy144M),
+      3 => Ok(ElToritoEmulationType::Floppy288M),
+      4 => Ok(ElToritoEmulationType::HardDisk),
+      v => Err(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ElToritoBootMediaTypeExt {
   pub emulation_type: ElToritoEmulationType,
   pub continuation_entry_follows: bool,
@@ -961,16 +1789,44 @@ pub struct ElToritoBootMediaTypeExt {
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElToritoHeaderIndicator {
-  MoreHeadersFollow = 90,
-  FinalHeader = 91,
+  MoreHeadersFollow = 0x90,
+  FinalHeader = 0x91,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct ElToritoSectionId(pub(crate) [u8; 16]);
+impl TryFrom<u8> for ElToritoHeaderIndicator {
+  type Error = u8;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0x90 => Ok(ElToritoHeaderIndicator::MoreHeadersFollow),
+      0x91 => Ok(ElToritoHeaderIndicator::FinalHeader),
+      v => Err(v),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElToritoSectionId(pub(crate) [u8; 28]);
+
+impl ElToritoSectionId {
+  /// Zero padded ASCII identifier of at most 28 bytes.
+  pub fn new(id: impl AsRef<str>) -> Option<Self> {
+    let id = id.as_ref();
+    let mut data = [0u8; 28];
 
-#[derive(Debug, Clone, Copy)]
+    if id.len() > data.len() || !id.is_ascii() {
+      return None;
+    }
+
+    data[..id.len()].copy_from_slice(id.as_bytes());
+
+    Some(Self(data))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ElToritoSelectionCriteriaType {
   NoSelectionCriteria = 0,
@@ -978,9 +1834,9 @@ pub enum ElToritoSelectionCriteriaType {
   Other(u8),
 }
 
-impl Into<u8> for ElToritoSelectionCriteriaType {
-  fn into(self) -> u8 {
-    match self {
+impl From<ElToritoSelectionCriteriaType> for u8 {
+  fn from(value: ElToritoSelectionCriteriaType) -> Self {
+    match value {
       ElToritoSelectionCriteriaType::NoSelectionCriteria => 0,
       ElToritoSelectionCriteriaType::LanguageAndVersionInformation => 1,
       ElToritoSelectionCriteriaType::Other(v) => v,
@@ -988,7 +1844,17 @@ impl Into<u8> for ElToritoSelectionCriteriaType {
   }
 }
 
-#[derive(Debug)]
+impl From<u8> for ElToritoSelectionCriteriaType {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => ElToritoSelectionCriteriaType::NoSelectionCriteria,
+      1 => ElToritoSelectionCriteriaType::LanguageAndVersionInformation,
+      v => ElToritoSelectionCriteriaType::Other(v),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoInitialSectionEntry {
   pub boot_indicator: ElToritoBootIndicator,
   pub boot_media_type: ElToritoBootMediaType,
@@ -998,7 +1864,7 @@ pub struct ElToritoInitialSectionEntry {
   pub virtual_disk_location: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoSectionHeaderEntry {
   pub header_indicator: ElToritoHeaderIndicator,
   pub platform_id: ElToritoPlatformId,
@@ -1006,7 +1872,7 @@ pub struct ElToritoSectionHeaderEntry {
   pub section_id: ElToritoSectionId,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoValidationEntry {
   pub header_id: ElToritoHeaderId,
   pub platform_id: ElToritoPlatformId,
@@ -1014,7 +1880,38 @@ pub struct ElToritoValidationEntry {
   pub checksum: u16,
 }
 
-#[derive(Debug)]
+impl ElToritoValidationEntry {
+  /// Creates a validation entry with a correct checksum.
+  pub fn new(platform_id: ElToritoPlatformId, manufacturer_id: ElToritoManufacturerId) -> Self {
+    let mut entry = Self {
+      header_id: ElToritoHeaderId::Standard,
+      platform_id,
+      manufacturer_id,
+      checksum: 0,
+    };
+
+    entry.checksum = entry.expected_checksum();
+    entry
+  }
+
+  /// Checksum for which all 16 bit little endian words of the entry,
+  /// including the key bytes, sum to zero.
+  pub fn expected_checksum(&self) -> u16 {
+    let header = u16::from_le_bytes([self.header_id.into(), self.platform_id.into()]);
+    let key = u16::from_le_bytes([0x55, 0xAA]);
+
+    let sum = self
+      .manufacturer_id
+      .0
+      .chunks_exact(2)
+      .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+      .fold(header.wrapping_add(key), u16::wrapping_add);
+
+    sum.wrapping_neg()
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoSectionEntry {
   pub boot_indicator: ElToritoBootIndicator,
   pub boot_media_type: ElToritoBootMediaTypeExt,
@@ -1023,18 +1920,459 @@ pub struct ElToritoSectionEntry {
   pub sector_count: u16,
   pub virtual_disk_location: u32,
   pub selection_criteria_type: ElToritoSelectionCriteriaType,
-  pub vendor_selection_criteria: [u8; 18],
+  pub vendor_selection_criteria: [u8; 19],
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoSectionEntryExtension {
   pub extension_record_follows_indicator: ElToritoExtensionRecordFollowsIndicator,
-  pub vendor_unique_selection_criteria: [u8; 29],
+  pub vendor_unique_selection_criteria: [u8; 30],
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ElToritoBootRecordVolumeDescriptor {
   pub standard_identifier: StandardIdentifier,
   pub version: VolumeDescriptorVersion,
   pub boot_catalog_pointer: u32,
 }
+
+/// Table patched into a no emulation boot image at offset 8, as expected by isolinux and GRUB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootInfoTable {
+  /// LBA of the primary volume descriptor.
+  pub primary_volume_descriptor: u32,
+  /// LBA of the boot image.
+  pub boot_file_location: u32,
+  /// Length of the boot image in bytes.
+  pub boot_file_length: u32,
+  /// Sum of all 32 bit little endian words of the boot image from offset 64.
+  pub checksum: u32,
+}
+
+impl BootInfoTable {
+  /// Offset of the table within the boot image.
+  pub const OFFSET: usize = 8;
+
+  /// Computes the checksum over `boot_file`, skipping the first 64 bytes which hold the table.
+  pub fn checksum(boot_file: &[u8]) -> u32 {
+    boot_file
+      .get(64..)
+      .unwrap_or_default()
+      .chunks(4)
+      .map(|chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        u32::from_le_bytes(word)
+      })
+      .fold(0, u32::wrapping_add)
+  }
+}
+
+/// Entry of the partition table within a master boot record.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MbrPartitionEntry {
+  /// `0x80` for the active partition, `0x00` otherwise.
+  pub boot_indicator: u8,
+  pub start_chs: [u8; 3],
+  /// System indicator, e.g. `0x0C` for FAT32 or `0xEF` for an EFI system partition.
+  pub partition_type: u8,
+  pub end_chs: [u8; 3],
+  pub start_lba: u32,
+  pub sector_count: u32,
+}
+
+impl MbrPartitionEntry {
+  pub fn is_empty(&self) -> bool {
+    self.partition_type == 0
+  }
+}
+
+/// Partition table of a master boot record, followed by the `0x55AA` signature.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MbrPartitionTable {
+  pub entries: [MbrPartitionEntry; 4],
+}
+
+impl MbrPartitionTable {
+  /// Offset of the partition table within the first sector.
+  pub const OFFSET: usize = 446;
+}
+
+/// GUID in its on-disk mixed endian byte order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+  /// Partition type of an EFI system partition.
+  pub const EFI_SYSTEM_PARTITION: Self = Self::new(
+    0xC12A7328,
+    0xF81F,
+    0x11D2,
+    [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B],
+  );
+
+  /// Partition type of a basic data partition.
+  pub const BASIC_DATA: Self = Self::new(
+    0xEBD0A0A2,
+    0xB9E5,
+    0x4433,
+    [0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7],
+  );
+
+  /// GUID from its textual fields, e.g. `C12A7328-F81F-11D2-BA4B-00A0C93EC93B`.
+  pub const fn new(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+    let data1 = data1.to_le_bytes();
+    let data2 = data2.to_le_bytes();
+    let data3 = data3.to_le_bytes();
+
+    Self([
+      data1[0], data1[1], data1[2], data1[3], data2[0], data2[1], data3[0], data3[1], data4[0],
+      data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7],
+    ])
+  }
+}
+
+/// Header of a GUID partition table, found in the second 512 byte sector and,
+/// as a backup, in the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptHeader {
+  pub header_crc32: u32,
+  pub my_lba: u64,
+  pub alternate_lba: u64,
+  pub first_usable_lba: u64,
+  pub last_usable_lba: u64,
+  pub disk_guid: Guid,
+  pub partition_entry_lba: u64,
+  pub number_of_partition_entries: u32,
+  pub size_of_partition_entry: u32,
+  pub partition_entry_array_crc32: u32,
+}
+
+impl GptHeader {
+  pub const SIGNATURE: &'static [u8; 8] = b"EFI PART";
+  pub const REVISION: u32 = 0x0001_0000;
+  pub const SIZE: u32 = 92;
+}
+
+/// Entry of the partition array of a GUID partition table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptPartitionEntry {
+  pub partition_type_guid: Guid,
+  pub unique_partition_guid: Guid,
+  pub starting_lba: u64,
+  /// Last sector of the partition, inclusive.
+  pub ending_lba: u64,
+  pub attributes: u64,
+  /// UTF-16 name of the partition, zero padded.
+  pub partition_name: [u16; 36],
+}
+
+impl GptPartitionEntry {
+  pub const SIZE: u32 = 128;
+
+  /// Encodes `name` as a zero padded UTF-16 partition name of at most 36 code units.
+  pub fn encode_name(name: &str) -> Option<[u16; 36]> {
+    let mut data = [0u16; 36];
+
+    for (ix, unit) in name.encode_utf16().enumerate() {
+      *data.get_mut(ix)? = unit;
+    }
+
+    Some(data)
+  }
+}
+
+/// Driver descriptor map heading block 0 of an Apple partition map. Only the
+/// fields preceding the driver descriptors are modelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApmDriverDescriptorMap {
+  pub block_size: u16,
+  pub block_count: u32,
+}
+
+impl ApmDriverDescriptorMap {
+  pub const SIGNATURE: &'static [u8; 2] = b"ER";
+}
+
+/// Entry of an Apple partition map, one per block following the driver descriptor map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApmPartitionEntry {
+  /// Number of entries in the partition map.
+  pub map_block_count: u32,
+  pub start_block: u32,
+  pub block_count: u32,
+  /// Zero padded ASCII name, e.g. `EFI`.
+  pub name: [u8; 32],
+  /// Zero padded ASCII type, e.g. `Apple_HFS`.
+  pub partition_type: [u8; 32],
+  pub data_start: u32,
+  pub data_count: u32,
+  pub status: u32,
+}
+
+impl ApmPartitionEntry {
+  pub const SIGNATURE: &'static [u8; 2] = b"PM";
+
+  /// Encodes `s` as a zero padded ASCII string of at most 32 bytes.
+  pub fn encode_str(s: &str) -> Option<[u8; 32]> {
+    let mut data = [0u8; 32];
+
+    if s.len() > data.len() || !s.is_ascii() {
+      return None;
+    }
+
+    data[..s.len()].copy_from_slice(s.as_bytes());
+
+    Some(data)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn joliet_escape_sequences() {
+    for level in [
+      JolietLevel::Level1,
+      JolietLevel::Level2,
+      JolietLevel::Level3,
+    ] {
+      assert_eq!(
+        EscapeSequences::<32>::joliet(level).joliet_level(),
+        Some(level)
+      );
+    }
+
+    assert_eq!(&EscapeSequences::<32>::joliet_level_1().0[..3], b"%/@");
+    assert_eq!(&EscapeSequences::<32>::joliet_level_2().0[..3], b"%/C");
+    assert_eq!(&EscapeSequences::<32>::joliet_level_3().0[..3], b"%/E");
+    assert_eq!(EscapeSequences::<32>([0; 32]).joliet_level(), None);
+  }
+
+  #[test]
+  fn joliet_repertoire() {
+    let accepts = |name: &str, level| Identifier::joliet_file_identifier(name, level).is_ok();
+
+    // Precomposed characters are fine at every level.
+    assert!(accepts("café.txt", JolietLevel::Level1));
+
+    // U+0301 COMBINING ACUTE ACCENT is excluded below level 3.
+    assert!(!accepts("cafe\u{301}.txt", JolietLevel::Level1));
+    assert!(!accepts("cafe\u{301}.txt", JolietLevel::Level2));
+    assert!(accepts("cafe\u{301}.txt", JolietLevel::Level3));
+
+    // Thai vowel signs combine, but are permitted from level 2.
+    assert!(!accepts("\u{E01}\u{E34}.txt", JolietLevel::Level1));
+    assert!(accepts("\u{E01}\u{E34}.txt", JolietLevel::Level2));
+
+    for name in ["a*b", "a/b", "a:b", "a;b", "a?b", "a\\b", "a\u{7}b"] {
+      assert!(!accepts(name, JolietLevel::Level3), "{name:?}");
+    }
+
+    assert!(accepts(&"x".repeat(64), JolietLevel::Level3));
+    assert!(!accepts(&"x".repeat(65), JolietLevel::Level3));
+  }
+
+  #[test]
+  fn interchange_levels() {
+    let file =
+      |name: &str, level| Identifier::standard_file_identifier(name, level).map(|x| x.to_string());
+    let directory = |name: &str, level| {
+      Identifier::standard_directory_identifier(name, level).map(|x| x.to_string())
+    };
+    let too_long = |kind, length, limit| {
+      Err(IdentifierError::TooLong {
+        kind,
+        length,
+        limit,
+      })
+    };
+
+    assert_eq!(
+      file("readme.txt", InterchangeLevel::Level1).as_deref(),
+      Ok("README.TXT;1")
+    );
+    assert_eq!(
+      file("README", InterchangeLevel::Level1).as_deref(),
+      Ok("README.;1")
+    );
+    assert_eq!(
+      file("manifest.json", InterchangeLevel::Level1),
+      Err(IdentifierError::ExtensionTooLong {
+        kind: IdentifierKind::StandardFileIdentifier,
+        length: 4,
+        limit: 3,
+      })
+    );
+    assert_eq!(
+      file("changelog.md", InterchangeLevel::Level1),
+      too_long(IdentifierKind::StandardFileIdentifier, 9, 8)
+    );
+    assert_eq!(
+      file("changelog.md", InterchangeLevel::Level2).as_deref(),
+      Ok("CHANGELOG.MD;1")
+    );
+    assert_eq!(
+      file("archive.tar.gz", InterchangeLevel::Level3),
+      Err(IdentifierError::InvalidCharacter {
+        kind: IdentifierKind::StandardFileIdentifier,
+        character: '.',
+        position: 7,
+      })
+    );
+
+    // The name and extension take up to 30 characters together.
+    let long = format!("{}.txt", "x".repeat(27));
+
+    assert!(file(&long, InterchangeLevel::Level2).is_ok());
+    assert_eq!(
+      file(&format!("x{long}"), InterchangeLevel::Level3),
+      too_long(IdentifierKind::StandardFileIdentifier, 31, 30)
+    );
+
+    assert_eq!(
+      directory("boot", InterchangeLevel::Level1).as_deref(),
+      Ok("BOOT")
+    );
+    assert_eq!(
+      directory("packages", InterchangeLevel::Level1).as_deref(),
+      Ok("PACKAGES")
+    );
+    assert_eq!(
+      directory("packages2", InterchangeLevel::Level1),
+      too_long(IdentifierKind::StandardDirectoryIdentifier, 9, 8)
+    );
+    assert!(directory(&"x".repeat(31), InterchangeLevel::Level2).is_ok());
+    assert_eq!(
+      directory(&"x".repeat(32), InterchangeLevel::Level3),
+      too_long(IdentifierKind::StandardDirectoryIdentifier, 32, 31)
+    );
+
+    // ISO 9660:1999 records identifiers as is.
+    assert_eq!(
+      file("Archive.tar.gz", InterchangeLevel::Iso9660_1999).as_deref(),
+      Ok("Archive.tar.gz")
+    );
+    assert_eq!(
+      directory("Release Notes", InterchangeLevel::Iso9660_1999).as_deref(),
+      Ok("Release Notes")
+    );
+    assert!(file(&"x".repeat(207), InterchangeLevel::Iso9660_1999).is_ok());
+    assert_eq!(
+      file(&"x".repeat(208), InterchangeLevel::Iso9660_1999),
+      too_long(IdentifierKind::StandardFileIdentifier, 208, 207)
+    );
+    assert_eq!(
+      file("", InterchangeLevel::Iso9660_1999),
+      Err(IdentifierError::Empty {
+        kind: IdentifierKind::StandardFileIdentifier
+      })
+    );
+
+    for (name, character) in [("a/b", '/'), ("a;1", ';'), ("a\nb", '\n')] {
+      assert_eq!(
+        file(name, InterchangeLevel::Iso9660_1999),
+        Err(IdentifierError::InvalidCharacter {
+          kind: IdentifierKind::StandardFileIdentifier,
+          character,
+          position: 1,
+        }),
+        "{name:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn record_order() {
+    let file = |name| Identifier::standard_file_identifier(name, InterchangeLevel::Level3).unwrap();
+    let directory =
+      |name| Identifier::standard_directory_identifier(name, InterchangeLevel::Level3).unwrap();
+    let mut identifiers = [
+      file("A_B.TXT"),
+      file("A.TXT_"),
+      file("A.TXT"),
+      directory("AB"),
+      Identifier::parent_directory(),
+      file("A"),
+      Identifier::current_directory(),
+      file("A_"),
+    ];
+
+    identifiers.sort_by(Identifier::cmp_record_order);
+
+    // Names come before extensions, and directories sort among files.
+    assert_eq!(
+      identifiers
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>(),
+      [
+        ".",
+        "..",
+        "A.;1",
+        "A.TXT;1",
+        "A.TXT_;1",
+        "AB",
+        "A_.;1",
+        "A_B.TXT;1"
+      ]
+    );
+
+    // Versions sort in descending order.
+    let version = |version| {
+      Identifier::from_bytes(
+        IdentifierKind::StandardFileIdentifier,
+        format!("README.TXT;{version}").as_bytes(),
+      )
+    };
+
+    assert_eq!(
+      version(1).cmp_record_order(&version(2)),
+      std::cmp::Ordering::Greater
+    );
+  }
+
+  #[test]
+  fn identifier_errors() {
+    // Positions count characters rather than bytes.
+    assert_eq!(
+      Identifier::joliet_file_identifier("café:menu.txt", JolietLevel::Level3),
+      Err(IdentifierError::InvalidCharacter {
+        kind: IdentifierKind::JolietFileIdentifier,
+        character: ':',
+        position: 4,
+      })
+    );
+    assert_eq!(
+      Identifier::joliet_directory_identifier("🦀", JolietLevel::Level3),
+      Err(IdentifierError::InvalidCharacter {
+        kind: IdentifierKind::JolietDirectoryIdentifier,
+        character: '🦀',
+        position: 0,
+      })
+    );
+    assert_eq!(
+      Identifier::volume_identifier("isofs"),
+      Err(IdentifierError::InvalidCharacter {
+        kind: IdentifierKind::DCharacters,
+        character: 'i',
+        position: 0,
+      })
+    );
+    assert_eq!(
+      Identifier::joliet_volume_identifier("x".repeat(17)),
+      Err(IdentifierError::TooLong {
+        kind: IdentifierKind::D1Characters,
+        length: 17,
+        limit: 16,
+      })
+    );
+    assert_eq!(
+      Identifier::copyright_file_identifier("X".repeat(38), InterchangeLevel::Level3)
+        .unwrap_err()
+        .to_string(),
+      "DCharacters of length 38 exceeds the limit of 37"
+    );
+  }
+}
@@ -1,21 +1,35 @@
 //! High-level interface for building ISO 9660 filesystems with optional Joliet extensions.
 
 use crate::{
+  deserialize::IsoDeserialize,
+  mangle,
   serialize::{self, IsoSerialize},
-  spec::{self, EscapeSequences, Identifier},
+  spec,
 };
 
 use super::prelude::*;
 use std::{
-  collections::{hash_map, HashMap},
-  path::{self, Path},
+  collections::{btree_map, BTreeMap},
+  path::{Path, PathBuf},
   rc::Rc,
 };
 
 type ArrayStringU255 = arraystring::ArrayString<arraystring::typenum::U255>;
 
-struct Context {
+pub(crate) struct Context {
   compatibility_mode: spec::CompatibilityMode,
+  /// Rock Ridge extensions recorded within the hierarchy, if any.
+  rock_ridge: Option<RockRidgeOptions>,
+  /// Time the image is recorded at, stamped on every record and on the attributes that don't
+  /// come from the host filesystem.
+  recording_date: chrono::DateTime<chrono::Utc>,
+  sector_size: u32,
+}
+
+impl Context {
+  fn is_joliet(&self) -> bool {
+    matches!(self.compatibility_mode, spec::CompatibilityMode::Joliet(_))
+  }
 }
 
 struct SectorWriter<Storage> {
@@ -74,7 +88,7 @@ where
   }
 }
 
-struct LbaAllocator {
+pub(crate) struct LbaAllocator {
   sector_size: u32,
   next_lba: u32,
 }
@@ -89,65 +103,400 @@ impl LbaAllocator {
 
   fn allocate(&mut self, size: u32) -> u32 {
     let lba = self.next_lba;
-    let sectors = (size + self.sector_size - 1) / self.sector_size;
+    let sectors = size.div_ceil(self.sector_size);
     self.next_lba += sectors;
     lba
   }
 }
 
-struct PathTable {
-  size: u32,
-  records: Vec<spec::PathTableRecord>,
+/// Continuation areas holding the System Use entries that don't fit within their directory
+/// records, packed into consecutive blocks.
+#[derive(Debug)]
+struct ContinuationAreas {
+  /// LBA of the first block.
+  lba: u32,
+  sector_size: u32,
+  blocks: Vec<Vec<u8>>,
 }
 
-impl PathTable {
-  fn build_from_filesystem(fs: &Filesystem, context: &Context) -> Option<Self> {
-    fn aggregate(
-      dir: &DirectoryEntry,
-      parent_ix: u16,
-      records: &mut Vec<spec::PathTableRecord>,
-    ) -> Option<()> {
-      let ix = records.len() as u16 + 1; // 1-based index
-
-      let record = spec::PathTableRecord {
-        directory_identifier_length: dir.name.len() as u8,
-        extended_attribute_record_length: 0,
-        extent_location: dir.data_lba?,
-        parent_directory_number: parent_ix,
-        // TODO(meowesque): Handle different identifier types (e.g., Joliet).
-        directory_identifier: spec::Identifier::standard_directory_identifier(&dir.name)?,
-      };
+impl ContinuationAreas {
+  fn new(lba: u32, sector_size: u32) -> Self {
+    Self {
+      lba,
+      sector_size,
+      blocks: vec![],
+    }
+  }
 
-      records.push(record);
+  /// Bytes left for System Use entries within `record`, disregarding its current ones.
+  fn available(record: &spec::DirectoryRecord) -> usize {
+    255 - (record.extent() - record.system_use.iter().map(|x| x.extent()).sum::<usize>())
+  }
+
+  /// Splits `entries` into those that fit within `available` bytes, and those that must move to a
+  /// continuation area. If any must move, the former leave room for the `CE` entry referencing it.
+  fn split(
+    mut entries: Vec<spec::SystemUseEntry>,
+    available: usize,
+  ) -> (Vec<spec::SystemUseEntry>, Vec<spec::SystemUseEntry>) {
+    if entries.iter().map(|x| x.extent()).sum::<usize>() <= available {
+      return (entries, vec![]);
+    }
+
+    let mut used = spec::SystemUseEntry::CONTINUATION_AREA_LENGTH;
+    let kept = entries
+      .iter()
+      .take_while(|x| {
+        used += x.extent();
+        used <= available
+      })
+      .count();
+    let overflow = entries.split_off(kept);
+
+    (entries, overflow)
+  }
+
+  /// Attaches `entries` to `record`. The `CE` entry referencing those that don't fit is left
+  /// unresolved, which is enough to determine the record's extent.
+  fn attach(record: &mut spec::DirectoryRecord, entries: Vec<spec::SystemUseEntry>) {
+    let (mut kept, overflow) = Self::split(entries, Self::available(record));
 
-      dir.dirs.values().for_each(|subdir| {
-        aggregate(subdir, ix, records);
+    if !overflow.is_empty() {
+      kept.push(spec::SystemUseEntry::ContinuationArea {
+        block_location: 0,
+        offset: 0,
+        length: 0,
       });
+    }
+
+    record.system_use = kept;
+  }
+
+  /// Returns the entries to record within `available` bytes, moving the others to a continuation
+  /// area referenced by a trailing `CE` entry.
+  fn place(
+    &mut self,
+    entries: Vec<spec::SystemUseEntry>,
+    available: usize,
+  ) -> Result<Vec<spec::SystemUseEntry>> {
+    let (mut kept, overflow) = Self::split(entries, available);
 
-      Some(())
+    if !overflow.is_empty() {
+      kept.push(self.continue_with(overflow)?);
     }
 
-    let records = {
-      let mut records = vec![];
+    Ok(kept)
+  }
+
+  /// Moves `entries` to a continuation area within a single block, chaining further continuation
+  /// areas for those that don't fit.
+  fn continue_with(&mut self, entries: Vec<spec::SystemUseEntry>) -> Result<spec::SystemUseEntry> {
+    let (mut kept, overflow) = Self::split(entries, self.sector_size as usize);
 
-      records.push(spec::PathTableRecord {
-        directory_identifier_length: 1,
-        extended_attribute_record_length: 0,
-        extent_location: fs.root.data_lba?,
-        parent_directory_number: 1,
-        directory_identifier: spec::Identifier::root_directory(),
-      });
+    if !overflow.is_empty() {
+      kept.push(self.continue_with(overflow)?);
+    }
+
+    let mut bytes = vec![0; kept.iter().map(|x| x.extent()).sum()];
+    let mut offset = 0;
+
+    for entry in &kept {
+      entry.serialize(&mut (), &mut bytes[offset..])?;
+      offset += entry.extent();
+    }
 
-      for dir in fs.root.dirs.values() {
-        aggregate(dir, 1, &mut records);
+    let block_ix = match self.blocks.last() {
+      Some(block) if block.len() + bytes.len() <= self.sector_size as usize => {
+        self.blocks.len() - 1
       }
+      _ => {
+        self.blocks.push(vec![]);
+        self.blocks.len() - 1
+      }
+    };
+    let block = &mut self.blocks[block_ix];
+    let continuation_area = spec::SystemUseEntry::ContinuationArea {
+      block_location: self.lba + block_ix as u32,
+      offset: block.len() as u32,
+      length: bytes.len() as u32,
+    };
+
+    block.extend_from_slice(&bytes);
+
+    Ok(continuation_area)
+  }
+
+  fn size(&self) -> u32 {
+    self.blocks.len() as u32 * self.sector_size
+  }
+
+  fn write<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> Result<()> {
+    for (ix, block) in self.blocks.iter().enumerate() {
+      writer.seek(std::io::SeekFrom::Start(
+        (self.lba as u64 + ix as u64) * self.sector_size as u64,
+      ))?;
+      writer.write_all(block)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Record of the `.` or `..` entry of a directory.
+fn dot_record(
+  file_identifier: spec::Identifier,
+  extent_location: u32,
+  data_length: u32,
+  system_use: Vec<spec::SystemUseEntry>,
+  context: &Context,
+) -> spec::DirectoryRecord {
+  let mut record = spec::DirectoryRecord {
+    extended_attribute_length: 0,
+    extent_location,
+    data_length,
+    recording_date: context.recording_date.into(),
+    file_flags: spec::FileFlags::DIRECTORY,
+    file_unit_size: 0,
+    interleave_gap_size: 0,
+    volume_sequence_number: 1,
+    file_identifier_length: 1,
+    file_identifier,
+    system_use: vec![],
+  };
+
+  ContinuationAreas::attach(&mut record, system_use);
+
+  record
+}
+
+/// Records of a directory in the order they are written, along with their System Use entries,
+/// given its `.` and `..` records and the record of `RR_MOVED` within the root. The others are
+/// ordered by identifier, the records of a file spanning multiple extents kept together.
+fn directory_records(
+  dot: (spec::DirectoryRecord, Vec<spec::SystemUseEntry>),
+  dotdot: (spec::DirectoryRecord, Vec<spec::SystemUseEntry>),
+  dirs: &BTreeMap<ArrayStringU255, DirectoryEntry>,
+  relocation: Option<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)>,
+  files: &BTreeMap<ArrayStringU255, FileEntry>,
+  context: &Context,
+) -> Vec<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)> {
+  let mut entries = dirs
+    .values()
+    .map(|x| vec![(x.directory_record(context), x.system_use(context))])
+    .chain(relocation.map(|x| vec![x]))
+    .chain(files.values().map(|x| {
+      let system_use = x.system_use(context);
+
+      x.directory_records(context)
+        .into_iter()
+        .map(|record| (record, system_use.clone()))
+        .collect()
+    }))
+    .collect::<Vec<_>>();
+
+  entries.sort_by(|a, b| {
+    a[0]
+      .0
+      .file_identifier
+      .cmp_record_order(&b[0].0.file_identifier)
+  });
+
+  [dot, dotdot]
+    .into_iter()
+    .chain(entries.into_iter().flatten())
+    .collect()
+}
+
+/// Length of the extent of a directory holding records of the given extents, in the order they
+/// are written. Records never cross a sector boundary, those that would starting the next sector
+/// instead, and the extent takes up whole sectors.
+fn directory_data_length(extents: impl IntoIterator<Item = usize>, sector_size: u32) -> u32 {
+  let sector_size = sector_size as usize;
+  let end = extents.into_iter().fold(0, |offset, extent| {
+    match offset % sector_size + extent > sector_size {
+      true => offset.next_multiple_of(sector_size) + extent,
+      false => offset + extent,
+    }
+  });
+
+  end.next_multiple_of(sector_size) as u32
+}
+
+/// Extents of `records` once their System Use entries are attached.
+fn record_extents(
+  records: &[(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)],
+) -> impl Iterator<Item = usize> + '_ {
+  records.iter().map(|(record, entries)| {
+    let mut record = record.clone();
+
+    ContinuationAreas::attach(&mut record, entries.clone());
+    record.extent()
+  })
+}
+
+/// Lays out the System Use entries of a directory's records, given in the order they are
+/// written. Entries that don't fit move to continuation areas right after the directory's
+/// extent at `lba`, where readers going through the image sequentially expect them.
+fn layout_system_use(
+  lba: u32,
+  records: &[(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)],
+  sector_size: u32,
+) -> Result<(ContinuationAreas, Vec<Vec<spec::SystemUseEntry>>)> {
+  let data_length = directory_data_length(record_extents(records), sector_size);
+  let mut areas = ContinuationAreas::new(lba + data_length / sector_size, sector_size);
+  let system_use = records
+    .iter()
+    .map(|(record, entries)| areas.place(entries.clone(), ContinuationAreas::available(record)))
+    .collect::<Result<Vec<_>>>()?;
+
+  Ok((areas, system_use))
+}
+
+/// Maps the names of the entries of the directory at `path` onto identifiers within the
+/// hierarchy of `context`, none matching the `reserved` names. Entries are taken in name order,
+/// as they are kept in, so that the mapping doesn't depend on the order they were inserted in.
+fn map_identifiers(
+  path: &Path,
+  dirs: &mut BTreeMap<ArrayStringU255, DirectoryEntry>,
+  files: &mut BTreeMap<ArrayStringU255, FileEntry>,
+  reserved: &[&str],
+  context: &Context,
+) -> Result<()> {
+  let dirs = dirs.values_mut().collect::<Vec<_>>();
+  let files = files.values_mut().collect::<Vec<_>>();
+
+  let names = dirs
+    .iter()
+    .map(|x| (x.name.as_str(), true))
+    .chain(files.iter().map(|x| (x.name.as_str(), false)))
+    .collect::<Vec<_>>();
+  let mut identifiers = mangle::identifiers(&names, reserved, context.compatibility_mode)
+    .into_iter()
+    .zip(&names)
+    .map(|(identifier, (name, _))| identifier.map_err(|e| Error::InvalidName(path.join(name), e)))
+    .collect::<Vec<_>>()
+    .into_iter();
+
+  for (dir, identifier) in dirs.into_iter().zip(identifiers.by_ref()) {
+    let identifier = identifier?;
+
+    match context.is_joliet() {
+      true => dir.joliet_identifier = Some(identifier),
+      false => dir.identifier = Some(identifier),
+    }
+  }
+
+  for (file, identifier) in files.into_iter().zip(identifiers) {
+    let identifier = identifier?;
+
+    match context.is_joliet() {
+      true => file.joliet_identifier = Some(identifier),
+      false => file.identifier = Some(identifier),
+    }
+  }
+
+  Ok(())
+}
+
+struct PathTable {
+  size: u32,
+  records: Vec<spec::PathTableRecord>,
+}
 
-      records
+impl PathTable {
+  /// Path table of the hierarchy of `context`. Directories are listed level by level, ordered by
+  /// the number of their parent's record and then by identifier (ECMA-119 9.4), which readers
+  /// such as Windows rely on to resolve parent numbers.
+  fn build_from_filesystem(fs: &Filesystem, context: &Context) -> Result<Self> {
+    /// Subdirectories of `dir` listed within the path table, relocated ones being listed within
+    /// `RR_MOVED` instead.
+    fn subdirectories<'a>(
+      dir: &'a DirectoryEntry,
+      context: &Context,
+    ) -> Vec<(spec::Identifier, Option<&'a DirectoryEntry>)> {
+      dir
+        .dirs
+        .values()
+        .filter(|subdir| !subdir.is_relocated(context))
+        .map(|subdir| (subdir.identifier(context).clone(), Some(subdir)))
+        .collect()
+    }
+
+    let Some(extent_location) = fs.root.data_lba(context) else {
+      unreachable!("Directory LBA should have been allocated by now");
     };
+    let mut records = vec![spec::PathTableRecord {
+      directory_identifier_length: 1,
+      extended_attribute_record_length: 0,
+      extent_location,
+      parent_directory_number: 1,
+      directory_identifier: spec::Identifier::root_directory(),
+    }];
+
+    // `RR_MOVED` takes its place among the root's subdirectories, standing in as `None`.
+    let relocation = fs.root.relocation_directory(context);
+    let relocation_identifier = relocation
+      .as_ref()
+      .map(|_| {
+        spec::Identifier::directory_identifier(
+          RelocationDirectory::NAME,
+          context.compatibility_mode,
+        )
+      })
+      .transpose()?;
+    let root_subdirectories = fs
+      .root
+      .dirs
+      .values()
+      .map(|dir| (dir.identifier(context).clone(), Some(dir)))
+      .chain(relocation_identifier.map(|x| (x, None)))
+      .collect::<Vec<_>>();
+
+    // Subdirectories of the directories on the current level, along with the numbers of the
+    // latter's records.
+    let mut level = vec![(1, root_subdirectories)];
+
+    while !level.is_empty() {
+      let mut next_level = vec![];
+
+      for (parent_directory_number, mut dirs) in level {
+        dirs.sort_by(|(a, _), (b, _)| a.cmp_record_order(b));
+
+        for (directory_identifier, dir) in dirs {
+          let (extent_location, subdirs) = match dir {
+            Some(dir) => (dir.data_lba(context), subdirectories(dir, context)),
+            None => (
+              fs.root.relocation_lba,
+              relocation
+                .iter()
+                .flat_map(|x| &x.dirs)
+                .map(|(identifier, dir, _)| (identifier.clone(), Some(*dir)))
+                .collect(),
+            ),
+          };
+          let Some(extent_location) = extent_location else {
+            unreachable!("Directory LBA should have been allocated by now");
+          };
+
+          records.push(spec::PathTableRecord {
+            directory_identifier_length: directory_identifier.extent() as u8,
+            extended_attribute_record_length: 0,
+            extent_location,
+            parent_directory_number,
+            directory_identifier,
+          });
+
+          // 1-based index of the record just pushed.
+          next_level.push((records.len() as u16, subdirs));
+        }
+      }
+
+      level = next_level;
+    }
 
     let size = records.iter().map(|x| x.extent() as u32).sum();
 
-    Some(Self { size, records })
+    Ok(Self { size, records })
   }
 
   /// Allocates LBA for Type L path table.
@@ -171,6 +520,14 @@ enum FileEntryContentInner {
   },
   /// File with content stored directly in memory.
   InMemory(Vec<u8>),
+  /// Device or FIFO on the host filesystem, which has no content.
+  Special(std::fs::Metadata),
+  /// Symbolic link, recorded through Rock Ridge alone.
+  SymbolicLink {
+    target: PathBuf,
+    /// Metadata of the link itself, if captured from the host filesystem.
+    metadata: Option<std::fs::Metadata>,
+  },
 }
 
 /// Represents the content of a file, either from the filesystem or in-memory.
@@ -182,8 +539,67 @@ impl FileEntryContent {
     match &*self.0 {
       FileEntryContentInner::File { metadata, .. } => metadata.len(),
       FileEntryContentInner::InMemory(vec) => vec.len() as u64,
+      FileEntryContentInner::Special(_) | FileEntryContentInner::SymbolicLink { .. } => 0,
+    }
+  }
+
+  /// Symbolic link pointing to `target`. Without Rock Ridge, it appears as an empty file.
+  pub fn symbolic_link(target: impl Into<PathBuf>) -> Self {
+    FileEntryContent(Rc::new(FileEntryContentInner::SymbolicLink {
+      target: target.into(),
+      metadata: None,
+    }))
+  }
+
+  fn symbolic_link_target(&self) -> Option<&Path> {
+    match &*self.0 {
+      FileEntryContentInner::SymbolicLink { target, .. } => Some(target),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn special(metadata: std::fs::Metadata) -> Self {
+    FileEntryContent(Rc::new(FileEntryContentInner::Special(metadata)))
+  }
+
+  /// POSIX attributes of the file, taken from the host filesystem if the content comes from it,
+  /// and stamped with `time` otherwise.
+  pub fn attributes(&self, time: chrono::DateTime<chrono::Utc>) -> PosixAttributes {
+    match &*self.0 {
+      FileEntryContentInner::File { metadata, .. }
+      | FileEntryContentInner::Special(metadata)
+      | FileEntryContentInner::SymbolicLink {
+        metadata: Some(metadata),
+        ..
+      } => metadata.into(),
+      FileEntryContentInner::InMemory(_) => PosixAttributes::file(time),
+      FileEntryContentInner::SymbolicLink { metadata: None, .. } => {
+        PosixAttributes::symbolic_link(time)
+      }
+    }
+  }
+
+  /// Reader over the content, starting from its beginning.
+  pub(crate) fn reader(&self) -> std::io::Result<Box<dyn std::io::Read + '_>> {
+    match &*self.0 {
+      FileEntryContentInner::File { handle, .. } => {
+        let mut handle = handle;
+
+        std::io::Seek::seek(&mut handle, std::io::SeekFrom::Start(0))?;
+
+        Ok(Box::new(std::io::BufReader::new(handle)))
+      }
+      FileEntryContentInner::InMemory(vec) => Ok(Box::new(vec.as_slice())),
+      FileEntryContentInner::Special(_) | FileEntryContentInner::SymbolicLink { .. } => {
+        Ok(Box::new(std::io::empty()))
+      }
     }
   }
+
+  /// Whether both refer to the same content.
+  fn ptr_eq(&self, other: &Self) -> bool {
+    Rc::ptr_eq(&self.0, &other.0)
+  }
 }
 
 impl TryFrom<std::fs::File> for FileEntryContent {
@@ -203,13 +619,267 @@ impl From<Vec<u8>> for FileEntryContent {
   }
 }
 
+/// POSIX attributes of a file or directory, recorded through Rock Ridge.
+#[derive(Debug, Clone)]
+pub struct PosixAttributes {
+  /// File type and permission bits, see [spec::file_type].
+  pub mode: u32,
+  pub links: u32,
+  pub uid: u32,
+  pub gid: u32,
+  /// File serial (inode) number.
+  pub serial: u32,
+  /// Device number (`st_rdev`) of block and character devices.
+  pub device: Option<u64>,
+  pub modified: chrono::DateTime<chrono::Utc>,
+  pub accessed: chrono::DateTime<chrono::Utc>,
+  /// Time of the last change of the attributes themselves.
+  pub changed: chrono::DateTime<chrono::Utc>,
+}
+
+impl PosixAttributes {
+  /// Attributes of a read-only regular file owned by root, last modified, accessed and changed at
+  /// `time`.
+  pub fn file(time: chrono::DateTime<chrono::Utc>) -> Self {
+    Self {
+      mode: spec::file_type::REGULAR | 0o444,
+      links: 1,
+      uid: 0,
+      gid: 0,
+      serial: 0,
+      device: None,
+      modified: time,
+      accessed: time,
+      changed: time,
+    }
+  }
+
+  /// The same attributes as recorded within an image made at `time`. Times past it are clamped
+  /// to it, and the host's serial number is left out, as neither is the same between checkouts
+  /// of a tree.
+  fn recorded_at(self, time: chrono::DateTime<chrono::Utc>) -> Self {
+    Self {
+      serial: 0,
+      modified: self.modified.min(time),
+      accessed: self.accessed.min(time),
+      changed: self.changed.min(time),
+      ..self
+    }
+  }
+
+  /// Attributes of a symbolic link owned by root, stamped with `time`.
+  pub fn symbolic_link(time: chrono::DateTime<chrono::Utc>) -> Self {
+    Self {
+      mode: spec::file_type::SYMBOLIC_LINK | 0o777,
+      ..Self::file(time)
+    }
+  }
+
+  /// Attributes of a read-only directory owned by root, stamped with `time`.
+  pub fn directory(time: chrono::DateTime<chrono::Utc>) -> Self {
+    Self {
+      mode: spec::file_type::DIRECTORY | 0o555,
+      links: 2,
+      ..Self::file(time)
+    }
+  }
+
+  /// Rock Ridge entries recording the attributes.
+  fn system_use(&self, options: &RockRidgeOptions) -> Vec<spec::SystemUseEntry> {
+    let mut entries = vec![spec::SystemUseEntry::PosixFileAttributes {
+      mode: self.mode,
+      links: self.links,
+      uid: self.uid,
+      gid: self.gid,
+      serial: Some(self.serial),
+    }];
+
+    if let Some(device) = self.device {
+      entries.push(spec::SystemUseEntry::DeviceNumber {
+        high: (device >> 32) as u32,
+        low: device as u32,
+      });
+    }
+
+    entries.push(spec::SystemUseEntry::TimeStamps(
+      match options.long_form_timestamps {
+        true => spec::TimeStamps::Long(spec::RecordedTimeStamps {
+          modify: Some(self.modified.into()),
+          access: Some(self.accessed.into()),
+          attributes: Some(self.changed.into()),
+          ..Default::default()
+        }),
+        false => spec::TimeStamps::Short(spec::RecordedTimeStamps {
+          modify: Some(self.modified.into()),
+          access: Some(self.accessed.into()),
+          attributes: Some(self.changed.into()),
+          ..Default::default()
+        }),
+      },
+    ));
+
+    entries
+  }
+}
+
+#[cfg(unix)]
+impl From<&std::fs::Metadata> for PosixAttributes {
+  fn from(metadata: &std::fs::Metadata) -> Self {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    let file_type = metadata.file_type();
+    let time = |secs: i64, nsecs: i64| {
+      chrono::DateTime::from_timestamp(secs, nsecs as u32).unwrap_or_default()
+    };
+
+    Self {
+      mode: metadata.mode(),
+      links: metadata.nlink() as u32,
+      uid: metadata.uid(),
+      gid: metadata.gid(),
+      serial: metadata.ino() as u32,
+      device: (file_type.is_block_device() || file_type.is_char_device())
+        .then_some(metadata.rdev()),
+      modified: time(metadata.mtime(), metadata.mtime_nsec()),
+      accessed: time(metadata.atime(), metadata.atime_nsec()),
+      changed: time(metadata.ctime(), metadata.ctime_nsec()),
+    }
+  }
+}
+
+#[cfg(not(unix))]
+impl From<&std::fs::Metadata> for PosixAttributes {
+  fn from(metadata: &std::fs::Metadata) -> Self {
+    let time = chrono::DateTime::default();
+    let defaults = match (metadata.is_dir(), metadata.is_symlink()) {
+      (true, _) => Self::directory(time),
+      (_, true) => Self::symbolic_link(time),
+      _ => Self::file(time),
+    };
+    let writable = (!metadata.permissions().readonly()) as u32 * 0o200;
+    let modified = metadata.modified().map_or(defaults.modified, Into::into);
+
+    Self {
+      mode: defaults.mode | writable,
+      modified,
+      accessed: metadata.accessed().map_or(modified, Into::into),
+      changed: modified,
+      ..defaults
+    }
+  }
+}
+
+/// Layout of file content compressed in the zisofs format.
+///
+/// Blocks are only measured when compressing ahead of the LBA allocation, and compressed again as
+/// they are written, so that the compressed content is never held in memory.
+#[derive(Debug, Clone)]
+struct Zisofs {
+  options: ZisofsOptions,
+  uncompressed_size: u32,
+  /// Offsets of the compressed blocks within the file, followed by the end of the last one.
+  block_pointers: Vec<u32>,
+}
+
+impl Zisofs {
+  /// Measures the compressed blocks of `content`, returning `None` if compression doesn't
+  /// make it smaller.
+  fn compress(
+    content: &FileEntryContent,
+    options: &ZisofsOptions,
+  ) -> std::io::Result<Option<Self>> {
+    let Ok(uncompressed_size) = u32::try_from(content.extent()) else {
+      return Ok(None);
+    };
+
+    if uncompressed_size == 0 {
+      return Ok(None);
+    }
+
+    let block_count = uncompressed_size.div_ceil(options.block_size()) as usize;
+    let mut reader = content.reader()?;
+    let mut block_pointers =
+      vec![spec::zisofs::HEADER_SIZE as u32 * 4 + 4 * (block_count as u32 + 1)];
+
+    for _ in 0..block_count {
+      let block = options.compress_block(&mut reader)?;
+      let end = block_pointers[block_pointers.len() - 1] as u64 + block.len() as u64;
+
+      if end >= uncompressed_size as u64 {
+        return Ok(None);
+      }
+
+      block_pointers.push(end as u32);
+    }
+
+    Ok(Some(Self {
+      options: *options,
+      uncompressed_size,
+      block_pointers,
+    }))
+  }
+
+  fn compressed_size(&self) -> u32 {
+    self.block_pointers[self.block_pointers.len() - 1]
+  }
+
+  fn system_use(&self) -> spec::SystemUseEntry {
+    spec::SystemUseEntry::CompressedFile {
+      algorithm: spec::zisofs::ALGORITHM,
+      header_size: spec::zisofs::HEADER_SIZE,
+      block_size_log2: self.options.block_size_log2,
+      uncompressed_size: self.uncompressed_size,
+    }
+  }
+
+  /// Writes the header, the block pointers and the compressed blocks of `content`.
+  fn write<W: std::io::Write>(&self, content: &FileEntryContent, writer: &mut W) -> Result<()> {
+    let mut header = spec::zisofs::MAGIC.to_vec();
+
+    header.extend(self.uncompressed_size.to_le_bytes());
+    header.extend([
+      spec::zisofs::HEADER_SIZE,
+      self.options.block_size_log2,
+      0,
+      0,
+    ]);
+    header.extend(self.block_pointers.iter().flat_map(|x| x.to_le_bytes()));
+    writer.write_all(&header)?;
+
+    let mut reader = content.reader()?;
+
+    for pointers in self.block_pointers.windows(2) {
+      let block = self.options.compress_block(&mut reader)?;
+
+      if block.len() as u32 != pointers[1] - pointers[0] {
+        return Err(Error::Io(std::io::Error::new(
+          std::io::ErrorKind::InvalidData,
+          "file content changed while compressing it",
+        )));
+      }
+
+      writer.write_all(&block)?;
+    }
+
+    Ok(())
+  }
+}
+
 /// Represents a file in the filesystem.
 #[derive(Debug, Clone)]
 pub struct FileEntry {
-  /// LBA of the start of the file's data.
-  data_lba: Option<u32>,
+  /// LBAs of the extents holding the file's data, which follow each other.
+  extent_lbas: Vec<u32>,
   name: ArrayStringU255,
+  /// Identifier within the primary hierarchy, mapped from the name when finalizing.
+  identifier: Option<spec::Identifier>,
+  /// Identifier within the Joliet hierarchy, mapped from the name when finalizing.
+  joliet_identifier: Option<spec::Identifier>,
   content: FileEntryContent,
+  /// Serial number recorded for the file, unique within the image.
+  serial: Option<u32>,
+  /// Layout of the content if compressed with zisofs.
+  zisofs: Option<Zisofs>,
 }
 
 impl FileEntry {
@@ -217,28 +887,126 @@ impl FileEntry {
     &self.name
   }
 
-  pub(crate) fn directory_record(&self, context: &Context) -> spec::DirectoryRecord {
-    let file_identifier = spec::Identifier::standard_file_identifier(&self.name)
-      // TODO(meowesque): Handle different identifier types (e.g., Joliet).
-      .expect("File name should be valid");
+  /// Largest length of an extent that is followed by another one of the same file. Only the last
+  /// extent may end within a block, and a record's data length takes up 32 bits.
+  const MAX_EXTENT_LENGTH: u64 = 0xFFFF_F800;
+
+  /// Records of the file, one per extent. Every record but the last is flagged as
+  /// [spec::FileFlags::MULTI_EXTENT], and all of them carry the same System Use entries.
+  pub(crate) fn directory_records(&self, context: &Context) -> Vec<spec::DirectoryRecord> {
+    let file_identifier = self.identifier(context);
+    let extent_lengths = self.extent_lengths();
+    let system_use = self.system_use(context);
+
+    extent_lengths
+      .iter()
+      .enumerate()
+      .map(|(ix, &length)| {
+        let mut record = spec::DirectoryRecord {
+          extended_attribute_length: 0,
+          extent_location: self.extent_lbas.get(ix).copied().unwrap_or(0),
+          data_length: length,
+          recording_date: context.recording_date.into(),
+          file_flags: match ix + 1 < extent_lengths.len() {
+            true => spec::FileFlags::MULTI_EXTENT,
+            false => spec::FileFlags::empty(),
+          },
+          file_unit_size: 0,
+          interleave_gap_size: 0,
+          volume_sequence_number: 0,
+          file_identifier_length: file_identifier.extent() as u8,
+          file_identifier: file_identifier.clone(),
+          system_use: vec![],
+        };
 
-    spec::DirectoryRecord {
-      extended_attribute_length: 0,
-      extent_location: self.data_lba.unwrap_or(0),
-      data_length: self.content.extent() as u32,
-      recording_date: chrono::Utc::now().into(),
-      file_flags: spec::FileFlags::empty(),
-      file_unit_size: 0,
-      interleave_gap_size: 0,
-      volume_sequence_number: 0,
-      file_identifier_length: file_identifier.extent() as u8,
-      // TODO(meowesque): Handle different identifier types (e.g., Joliet).
-      file_identifier,
+        ContinuationAreas::attach(&mut record, system_use.clone());
+
+        record
+      })
+      .collect()
+  }
+
+  /// Identifier of the file within the hierarchy of `context`.
+  fn identifier(&self, context: &Context) -> &spec::Identifier {
+    match context.is_joliet() {
+      true => &self.joliet_identifier,
+      false => &self.identifier,
+    }
+    .as_ref()
+    .expect("Identifiers should be mapped before laying out the hierarchy")
+  }
+
+  /// LBA of the start of the file's data.
+  fn data_lba(&self) -> Option<u32> {
+    self.extent_lbas.first().copied()
+  }
+
+  /// System Use entries of the file's record within the hierarchy of `context`.
+  fn system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    let Some(options) = context.rock_ridge else {
+      return vec![];
+    };
+
+    let mut entries = self.attributes(context).system_use(&options);
+
+    entries.extend(RockRidgeOptions::alternate_name(self.name.as_bytes()));
+
+    if let Some(target) = self.content.symbolic_link_target() {
+      entries.extend(
+        RockRidgeOptions::symbolic_link(target)
+          .expect("Symbolic link targets should be checked before laying out the hierarchy"),
+      );
+    }
+
+    entries.extend(self.zisofs.as_ref().map(Zisofs::system_use));
+
+    entries
+  }
+
+  fn attributes(&self, context: &Context) -> PosixAttributes {
+    PosixAttributes {
+      serial: self.serial.unwrap_or(0),
+      ..self
+        .content
+        .attributes(context.recording_date)
+        .recorded_at(context.recording_date)
+    }
+  }
+
+  /// Size of the file's data as written, compressed or not.
+  fn data_length(&self) -> u64 {
+    self
+      .zisofs
+      .as_ref()
+      .map_or(self.content.extent(), |x| x.compressed_size() as u64)
+  }
+
+  fn compress(&mut self, options: &ZisofsOptions) -> Result<()> {
+    self.zisofs = Zisofs::compress(&self.content, options)?;
+
+    Ok(())
+  }
+
+  /// Lengths of the extents holding the file's data, all but the last being as long as allowed.
+  fn extent_lengths(&self) -> Vec<u32> {
+    let mut remaining = self.data_length();
+    let mut lengths = vec![];
+
+    while remaining > Self::MAX_EXTENT_LENGTH {
+      lengths.push(Self::MAX_EXTENT_LENGTH as u32);
+      remaining -= Self::MAX_EXTENT_LENGTH;
     }
+
+    lengths.push(remaining as u32);
+    lengths
   }
 
-  fn allocate_lbas(&mut self, allocator: &mut LbaAllocator, context: &Context) {
-    self.data_lba = Some(allocator.allocate(self.directory_record(context).data_length));
+  fn allocate_lbas(&mut self, allocator: &mut LbaAllocator) {
+    self.extent_lbas = self
+      .extent_lengths()
+      .into_iter()
+      .map(|length| allocator.allocate(length))
+      .collect();
   }
 }
 
@@ -247,9 +1015,28 @@ impl FileEntry {
 pub struct DirectoryEntry {
   /// LBA of the start of the directory's data.
   data_lba: Option<u32>,
+  /// LBA of the start of the directory's data within the Joliet hierarchy.
+  joliet_data_lba: Option<u32>,
+  /// Length of the directory's extent, measured once identifiers are mapped.
+  data_length: Option<u32>,
+  /// Length of the directory's extent within the Joliet hierarchy.
+  joliet_data_length: Option<u32>,
   name: ArrayStringU255,
-  dirs: HashMap<ArrayStringU255, DirectoryEntry>,
-  files: HashMap<ArrayStringU255, FileEntry>,
+  /// Identifier within the primary hierarchy, mapped from the name when finalizing.
+  identifier: Option<spec::Identifier>,
+  /// Identifier within the Joliet hierarchy, mapped from the name when finalizing.
+  joliet_identifier: Option<spec::Identifier>,
+  /// Subdirectories and files by name, kept in name order so that the same tree always lays out
+  /// the same way.
+  dirs: BTreeMap<ArrayStringU255, DirectoryEntry>,
+  files: BTreeMap<ArrayStringU255, FileEntry>,
+  /// Whether the directory lies deeper than ISO 9660 allows, and is relocated to `RR_MOVED`
+  /// within hierarchies recording Rock Ridge.
+  relocated: bool,
+  /// Metadata of the directory, if captured from the host filesystem.
+  metadata: Option<std::fs::Metadata>,
+  /// Serial number recorded for the directory, unique within the image.
+  serial: Option<u32>,
 }
 
 impl DirectoryEntry {
@@ -257,18 +1044,30 @@ impl DirectoryEntry {
   pub fn into_root_directory(self) -> RootDirectory {
     RootDirectory {
       data_lba: self.data_lba,
+      joliet_data_lba: self.joliet_data_lba,
+      relocation_lba: None,
+      data_length: self.data_length,
+      joliet_data_length: self.joliet_data_length,
+      relocation_data_length: None,
       dirs: self.dirs,
       files: self.files,
+      metadata: self.metadata,
+      serial: self.serial,
+      relocation_serial: None,
     }
   }
 
   pub fn merge(&mut self, other: DirectoryEntry, on_file_conflict: &OnFileConflict) {
+    if other.metadata.is_some() {
+      self.metadata = other.metadata;
+    }
+
     for (name, dir) in other.dirs {
       match self.dirs.entry(name) {
-        hash_map::Entry::Vacant(vacant) => {
+        btree_map::Entry::Vacant(vacant) => {
           vacant.insert(dir);
         }
-        hash_map::Entry::Occupied(mut occupied) => {
+        btree_map::Entry::Occupied(mut occupied) => {
           occupied.get_mut().merge(dir, on_file_conflict);
         }
       }
@@ -295,65 +1094,393 @@ impl DirectoryEntry {
     }
   }
 
-  pub(crate) fn directory_record(&self, context: &Context) -> spec::DirectoryRecord {
-    let file_identifier = spec::Identifier::standard_directory_identifier(&self.name)
-      // TODO(meowesque): Handle different identifier types (e.g., Joliet).
-      .expect("Directory name should be valid");
+  fn data_lba(&self, context: &Context) -> Option<u32> {
+    match context.is_joliet() {
+      true => self.joliet_data_lba,
+      false => self.data_lba,
+    }
+  }
 
-    spec::DirectoryRecord {
-      extended_attribute_length: 0,
-      extent_location: self.data_lba.unwrap_or(0),
-      data_length: self
-        .files
-        .values()
-        .map(|x| x.directory_record(context).extent() as u32)
-        .sum::<u32>()
-        + self
-          .dirs
-          .values()
-          .map(|x| x.directory_record(context).extent() as u32)
-          .sum::<u32>()
-          // Plus 2 entries for `.` and `..`
-          + (2 * 34),
-      recording_date: chrono::Utc::now().into(),
-      file_flags: spec::FileFlags::DIRECTORY,
-      file_unit_size: 0,
-      interleave_gap_size: 0,
-      volume_sequence_number: 0,
-      file_identifier_length: file_identifier.extent() as u8,
-      // TODO(meowesque): Handle different identifier types (e.g., Joliet).
-      file_identifier,
+  /// Identifier of the directory within the hierarchy of `context`.
+  fn identifier(&self, context: &Context) -> &spec::Identifier {
+    match context.is_joliet() {
+      true => &self.joliet_identifier,
+      false => &self.identifier,
     }
+    .as_ref()
+    .expect("Identifiers should be mapped before laying out the hierarchy")
   }
 
-  pub(crate) fn allocate_lbas(&mut self, allocator: &mut LbaAllocator, context: &Context) {
-    self.data_lba = Some(allocator.allocate(self.directory_record(context).data_length));
+  fn is_relocated(&self, context: &Context) -> bool {
+    self.relocated && context.rock_ridge.is_some()
+  }
 
+  /// Marks the subdirectories lying deeper than ISO 9660 allows as relocated, given the level of
+  /// this directory within the hierarchy.
+  fn relocate_directories(&mut self, level: usize) {
     for dir in self.dirs.values_mut() {
-      dir.allocate_lbas(allocator, context);
-    }
+      dir.relocated = level >= RelocationDirectory::MAX_LEVEL;
 
-    for file in self.files.values_mut() {
-      file.allocate_lbas(allocator, context);
+      dir.relocate_directories(match dir.relocated {
+        // Relocated directories continue below `RR_MOVED`, itself on the second level.
+        true => 3,
+        false => level + 1,
+      });
     }
   }
-}
 
-/// Exactly like a [DirectoryEntry], but represents the root of the filesystem.
-#[derive(Debug, Default)]
-pub struct RootDirectory {
-  /// LBA of the start of the root directory's data.
-  data_lba: Option<u32>,
-  dirs: HashMap<ArrayStringU255, DirectoryEntry>,
-  files: HashMap<ArrayStringU255, FileEntry>,
-}
+  /// Length of the directory's extent within the hierarchy of `context`.
+  fn data_length(&self, context: &Context) -> u32 {
+    match context.is_joliet() {
+      true => self.joliet_data_length,
+      false => self.data_length,
+    }
+    .expect("Directories should be measured before laying out the hierarchy")
+  }
+
+  /// Measures the extents of the directory and its subdirectories within the hierarchy of
+  /// `context`. Subdirectories come first, as the records of their parent hold their lengths.
+  fn measure_directories(&mut self, context: &Context) {
+    for dir in self.dirs.values_mut() {
+      dir.measure_directories(context);
+    }
+
+    let data_length = Some(self.measure(context));
+
+    match context.is_joliet() {
+      true => self.joliet_data_length = data_length,
+      false => self.data_length = data_length,
+    }
+  }
+
+  /// Length of the directory's extent within the hierarchy of `context`, measured from its
+  /// records.
+  fn measure(&self, context: &Context) -> u32 {
+    // The `..` record's System Use entries describe the parent instead, but take up as much room.
+    let records = self.records(
+      dot_record(spec::Identifier::current_directory(), 0, 0, vec![], context),
+      (
+        dot_record(spec::Identifier::parent_directory(), 0, 0, vec![], context),
+        self.parent_system_use(self.attribute_system_use(context), 0, context),
+      ),
+      context,
+    );
+
+    directory_data_length(record_extents(&records), context.sector_size)
+  }
+
+  /// Records of the directory in the order they are written, along with their System Use
+  /// entries, given its `.` record and its `..` record along with the entries of the latter.
+  fn records(
+    &self,
+    dot: spec::DirectoryRecord,
+    dotdot: (spec::DirectoryRecord, Vec<spec::SystemUseEntry>),
+    context: &Context,
+  ) -> Vec<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)> {
+    directory_records(
+      (dot, self.attribute_system_use(context)),
+      dotdot,
+      &self.dirs,
+      None,
+      &self.files,
+      context,
+    )
+  }
+
+  /// Record of the directory identified by `file_identifier`, holding `system_use`.
+  fn record(
+    &self,
+    file_identifier: &spec::Identifier,
+    system_use: Vec<spec::SystemUseEntry>,
+    context: &Context,
+  ) -> spec::DirectoryRecord {
+    let mut record = spec::DirectoryRecord {
+      extended_attribute_length: 0,
+      extent_location: self.data_lba(context).unwrap_or(0),
+      data_length: self.data_length(context),
+      recording_date: context.recording_date.into(),
+      file_flags: spec::FileFlags::DIRECTORY,
+      file_unit_size: 0,
+      interleave_gap_size: 0,
+      volume_sequence_number: 0,
+      file_identifier_length: file_identifier.extent() as u8,
+      file_identifier: file_identifier.clone(),
+      system_use: vec![],
+    };
+
+    ContinuationAreas::attach(&mut record, system_use);
+
+    record
+  }
+
+  /// Record of the directory within its parent. Once relocated, it's an empty file record
+  /// pointing to the directory instead.
+  pub(crate) fn directory_record(&self, context: &Context) -> spec::DirectoryRecord {
+    let record = self.record(self.identifier(context), self.system_use(context), context);
+
+    match self.is_relocated(context) {
+      true => spec::DirectoryRecord {
+        data_length: 0,
+        file_flags: spec::FileFlags::empty(),
+        ..record
+      },
+      false => record,
+    }
+  }
+
+  /// Record of the relocated directory within `RR_MOVED`, where it's identified by
+  /// `file_identifier`.
+  fn relocated_record(
+    &self,
+    file_identifier: &spec::Identifier,
+    context: &Context,
+  ) -> spec::DirectoryRecord {
+    self.record(file_identifier, self.relocated_system_use(context), context)
+  }
+
+  /// POSIX attributes of the directory, taken from the host filesystem if captured from it.
+  fn attributes(&self, context: &Context) -> PosixAttributes {
+    let attributes = match &self.metadata {
+      Some(metadata) => metadata.into(),
+      None => PosixAttributes::directory(context.recording_date),
+    };
+
+    PosixAttributes {
+      links: 2 + self.dirs.len() as u32,
+      serial: self.serial.unwrap_or(0),
+      ..attributes.recorded_at(context.recording_date)
+    }
+  }
+
+  /// System Use entries of the directory's record within the hierarchy of `context`.
+  fn system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    let mut entries = self.attribute_system_use(context);
+
+    if context.rock_ridge.is_some() {
+      entries.extend(RockRidgeOptions::alternate_name(self.name.as_bytes()));
+    }
+
+    if self.is_relocated(context) {
+      entries.push(spec::SystemUseEntry::ChildLink {
+        block_location: self.data_lba(context).unwrap_or(0),
+      });
+    }
+
+    entries
+  }
+
+  /// System Use entries of the relocated directory's record within `RR_MOVED`.
+  fn relocated_system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    let mut entries = self.attribute_system_use(context);
+
+    entries.extend(RockRidgeOptions::alternate_name(self.name.as_bytes()));
+    entries.push(spec::SystemUseEntry::Relocated);
+
+    entries
+  }
+
+  /// System Use entries of the directory's `..` record, given those of the directory it's
+  /// recorded in. A relocated directory also points back to its actual parent at `parent_lba`.
+  fn parent_system_use(
+    &self,
+    mut entries: Vec<spec::SystemUseEntry>,
+    parent_lba: u32,
+    context: &Context,
+  ) -> Vec<spec::SystemUseEntry> {
+    if self.is_relocated(context) {
+      entries.push(spec::SystemUseEntry::ParentLink {
+        block_location: parent_lba,
+      });
+    }
+
+    entries
+  }
+
+  /// System Use entries recording the directory's attributes within the hierarchy of `context`,
+  /// as found in its `.` record and the `..` records of its subdirectories.
+  fn attribute_system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    context
+      .rock_ridge
+      .map(|options| self.attributes(context).system_use(&options))
+      .unwrap_or_default()
+  }
+
+  /// Allocates LBAs for this directory, its continuation areas and its subdirectories within the
+  /// hierarchy of `context`.
+  pub(crate) fn allocate_directory_lbas(
+    &mut self,
+    allocator: &mut LbaAllocator,
+    context: &Context,
+  ) -> Result<()> {
+    let data_length = self.data_length(context);
+    let lba = allocator.allocate(data_length);
+    // The `..` record describes the parent instead, but takes up as much room.
+    let records = self.records(
+      dot_record(
+        spec::Identifier::current_directory(),
+        lba,
+        data_length,
+        vec![],
+        context,
+      ),
+      (
+        dot_record(spec::Identifier::parent_directory(), 0, 0, vec![], context),
+        self.parent_system_use(self.attribute_system_use(context), 0, context),
+      ),
+      context,
+    );
+    let (continuation_areas, _) = layout_system_use(lba, &records, context.sector_size)?;
+
+    allocator.allocate(continuation_areas.size());
+
+    match context.is_joliet() {
+      true => self.joliet_data_lba = Some(lba),
+      false => self.data_lba = Some(lba),
+    }
+
+    for dir in self.dirs.values_mut() {
+      dir.allocate_directory_lbas(allocator, context)?;
+    }
+
+    Ok(())
+  }
+
+  /// Maps the names of the directory's entries onto identifiers within the hierarchy of
+  /// `context`, recursively.
+  fn map_identifiers(&mut self, path: &Path, context: &Context) -> Result<()> {
+    let path = path.join(self.name.as_str());
+
+    map_identifiers(&path, &mut self.dirs, &mut self.files, &[], context)?;
+
+    for dir in self.dirs.values_mut() {
+      dir.map_identifiers(&path, context)?;
+    }
+
+    Ok(())
+  }
+
+  /// Allocates LBAs for the data of all files within this directory, recursively.
+  /// File data is shared between hierarchies, so this is only done once.
+  pub(crate) fn allocate_file_lbas(&mut self, allocator: &mut LbaAllocator) {
+    for dir in self.dirs.values_mut() {
+      dir.allocate_file_lbas(allocator);
+    }
+
+    for file in self.files.values_mut() {
+      file.allocate_lbas(allocator);
+    }
+  }
+
+  /// Path of a file within the directory, recursively, whose data spans multiple extents.
+  fn multi_extent_file(&self, path: &Path) -> Option<PathBuf> {
+    self
+      .dirs
+      .values()
+      .find_map(|dir| dir.multi_extent_file(&path.join(dir.name.as_str())))
+      .or_else(|| {
+        self
+          .files
+          .values()
+          .find(|x| x.extent_lengths().len() > 1)
+          .map(|x| path.join(x.name()))
+      })
+  }
+
+  /// Checks that Rock Ridge can record the targets of the symbolic links within the directory,
+  /// recursively.
+  fn check_symbolic_links(&self) -> Result<()> {
+    for dir in self.dirs.values() {
+      dir.check_symbolic_links()?;
+    }
+
+    for target in self
+      .files
+      .values()
+      .filter_map(|x| x.content.symbolic_link_target())
+    {
+      RockRidgeOptions::symbolic_link(target)?;
+    }
+
+    Ok(())
+  }
+
+  /// Path of a directory relocated as it lies deeper than ISO 9660 allows, if any.
+  fn relocated_directory(&self, path: &Path) -> Option<PathBuf> {
+    self.dirs.values().find_map(|dir| {
+      let path = path.join(dir.name.as_str());
+
+      match dir.relocated {
+        true => Some(path),
+        false => dir.relocated_directory(&path),
+      }
+    })
+  }
+
+  /// Gives the directory, its subdirectories and files the next serial numbers of `serials`,
+  /// recursively.
+  fn assign_serials(&mut self, serials: &mut impl Iterator<Item = u32>) {
+    self.serial = serials.next();
+
+    for dir in self.dirs.values_mut() {
+      dir.assign_serials(serials);
+    }
+
+    for file in self.files.values_mut() {
+      file.serial = serials.next();
+    }
+  }
+
+  /// Compresses the content of the files with zisofs, except for the `excluded` content.
+  pub(crate) fn compress_files(
+    &mut self,
+    options: &ZisofsOptions,
+    excluded: &[FileEntryContent],
+  ) -> Result<()> {
+    for dir in self.dirs.values_mut() {
+      dir.compress_files(options, excluded)?;
+    }
+
+    for file in self.files.values_mut() {
+      if !excluded.iter().any(|x| x.ptr_eq(&file.content)) {
+        file.compress(options)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Exactly like a [DirectoryEntry], but represents the root of the filesystem.
+#[derive(Debug, Default)]
+pub struct RootDirectory {
+  /// LBA of the start of the root directory's data.
+  data_lba: Option<u32>,
+  /// LBA of the start of the root directory's data within the Joliet hierarchy.
+  joliet_data_lba: Option<u32>,
+  /// LBA of the start of the `RR_MOVED` directory's data, if any directory is relocated.
+  relocation_lba: Option<u32>,
+  /// Length of the root directory's extent, measured once identifiers are mapped.
+  data_length: Option<u32>,
+  /// Length of the root directory's extent within the Joliet hierarchy.
+  joliet_data_length: Option<u32>,
+  /// Length of the `RR_MOVED` directory's extent, if any directory is relocated.
+  relocation_data_length: Option<u32>,
+  dirs: BTreeMap<ArrayStringU255, DirectoryEntry>,
+  files: BTreeMap<ArrayStringU255, FileEntry>,
+  /// Metadata of the root directory, if captured from the host filesystem.
+  metadata: Option<std::fs::Metadata>,
+  /// Serial number recorded for the root directory, unique within the image.
+  serial: Option<u32>,
+  /// Serial number recorded for the `RR_MOVED` directory, if any directory is relocated.
+  relocation_serial: Option<u32>,
+}
 
 impl RootDirectory {
   /// Creates a new root directory by scaffolding from the given path.
   pub fn scaffold(
     components: impl DoubleEndedIterator<Item = impl AsRef<str>>,
-    dirs: HashMap<ArrayStringU255, DirectoryEntry>,
-    files: HashMap<ArrayStringU255, FileEntry>,
+    dirs: BTreeMap<ArrayStringU255, DirectoryEntry>,
+    files: BTreeMap<ArrayStringU255, FileEntry>,
   ) -> Self {
     // TODO(meowesque): See if we can avoid using a DoubleEndedIterator here.
 
@@ -363,23 +1490,52 @@ impl RootDirectory {
       tail = Some(match tail {
         None => DirectoryEntry {
           data_lba: None,
+          joliet_data_lba: None,
+          data_length: None,
+          joliet_data_length: None,
           name: ArrayStringU255::from(part.as_ref()),
+          identifier: None,
+          joliet_identifier: None,
           // TODO(meowesque): Avoid clone for efficiency.
           dirs: dirs.clone(),
           files: files.clone(),
+          relocated: false,
+          metadata: None,
+          serial: None,
         },
         Some(tail) => DirectoryEntry {
           data_lba: None,
+          joliet_data_lba: None,
+          data_length: None,
+          joliet_data_length: None,
           name: ArrayStringU255::from(part.as_ref()),
-          dirs: HashMap::from([(ArrayStringU255::from(tail.name), tail)]),
-          files: HashMap::new(),
+          identifier: None,
+          joliet_identifier: None,
+          dirs: BTreeMap::from([(ArrayStringU255::from(tail.name), tail)]),
+          files: BTreeMap::new(),
+          relocated: false,
+          metadata: None,
+          serial: None,
         },
       });
     }
 
+    // Without any components, the entries belong to the root itself.
     tail
       .map(|tail| Self::from_directory(tail, false))
-      .unwrap_or_default()
+      .unwrap_or(Self {
+        data_lba: None,
+        joliet_data_lba: None,
+        relocation_lba: None,
+        data_length: None,
+        joliet_data_length: None,
+        relocation_data_length: None,
+        dirs,
+        files,
+        metadata: None,
+        serial: None,
+        relocation_serial: None,
+      })
   }
 
   pub fn insert_file(
@@ -394,20 +1550,24 @@ impl RootDirectory {
       return Err(Error::NotAFile(path.to_path_buf()));
     };
 
-    let mut scaffold = path
+    let scaffold = path
       .parent()
       .map(|parent| {
         RootDirectory::scaffold(
           parent
             .components()
             .map(|comp| comp.as_os_str().to_string_lossy()),
-          HashMap::new(),
-          HashMap::from([(
+          BTreeMap::new(),
+          BTreeMap::from([(
             file_name.to_string_lossy().as_ref().into(),
             FileEntry {
-              data_lba: None,
+              extent_lbas: vec![],
               name: file_name.to_string_lossy().as_ref().into(),
+              identifier: None,
+              joliet_identifier: None,
               content,
+              serial: None,
+              zisofs: None,
             },
           )]),
         )
@@ -421,6 +1581,61 @@ impl RootDirectory {
     Ok(())
   }
 
+  /// Looks up the file at `path`.
+  pub fn file(&self, path: impl AsRef<Path>) -> Option<&FileEntry> {
+    let path = path.as_ref();
+    let mut dirs = &self.dirs;
+    let mut files = &self.files;
+
+    for component in path.parent()?.components() {
+      if let std::path::Component::Normal(name) = component {
+        let dir = dirs.get(name.to_string_lossy().as_ref())?;
+
+        dirs = &dir.dirs;
+        files = &dir.files;
+      }
+    }
+
+    files.get(path.file_name()?.to_string_lossy().as_ref())
+  }
+
+  /// Inserts the directory at `path` along with its missing parents, recording the `metadata` it
+  /// was captured with. The root itself is found at an empty `path`.
+  fn insert_directory(&mut self, path: impl AsRef<Path>, metadata: std::fs::Metadata) {
+    let names = path
+      .as_ref()
+      .components()
+      .filter_map(|component| match component {
+        std::path::Component::Normal(name) => {
+          Some(ArrayStringU255::from(name.to_string_lossy().as_ref()))
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    let directory = |name: &ArrayStringU255| DirectoryEntry {
+      name: *name,
+      ..DirectoryEntry::default()
+    };
+
+    let Some((name, parents)) = names.split_last() else {
+      self.metadata = Some(metadata);
+      return;
+    };
+    let mut dirs = &mut self.dirs;
+
+    for parent in parents {
+      dirs = &mut dirs
+        .entry(*parent)
+        .or_insert_with(|| directory(parent))
+        .dirs;
+    }
+
+    dirs
+      .entry(*name)
+      .or_insert_with(|| directory(name))
+      .metadata = Some(metadata);
+  }
+
   /// Creates a new root directory from a directory.
   ///
   /// * If `emplace` is true, the contents of `dir` will be placed at the root, otherwise `dir` will become a subdirectory.
@@ -428,25 +1643,45 @@ impl RootDirectory {
     match emplace {
       true => Self {
         data_lba: dir.data_lba,
+        joliet_data_lba: dir.joliet_data_lba,
+        relocation_lba: None,
+        data_length: dir.data_length,
+        joliet_data_length: dir.joliet_data_length,
+        relocation_data_length: None,
         dirs: dir.dirs,
         files: dir.files,
+        metadata: dir.metadata,
+        serial: dir.serial,
+        relocation_serial: None,
       },
       false => Self {
         data_lba: None,
-        dirs: HashMap::from([(dir.name.clone(), dir)]),
-        files: HashMap::new(),
+        joliet_data_lba: None,
+        relocation_lba: None,
+        data_length: None,
+        joliet_data_length: None,
+        relocation_data_length: None,
+        dirs: BTreeMap::from([(dir.name, dir)]),
+        files: BTreeMap::new(),
+        metadata: None,
+        serial: None,
+        relocation_serial: None,
       },
     }
   }
 
   /// Merges another root directory into this one, resolving file conflicts according to `on_file_conflict`.
   pub fn merge(&mut self, other: RootDirectory, on_file_conflict: &OnFileConflict) {
+    if other.metadata.is_some() {
+      self.metadata = other.metadata;
+    }
+
     for (name, dir) in other.dirs {
       match self.dirs.entry(name) {
-        hash_map::Entry::Vacant(vacant) => {
+        btree_map::Entry::Vacant(vacant) => {
           vacant.insert(dir);
         }
-        hash_map::Entry::Occupied(mut occupied) => {
+        btree_map::Entry::Occupied(mut occupied) => {
           occupied.get_mut().merge(dir, on_file_conflict);
         }
       }
@@ -473,22 +1708,66 @@ impl RootDirectory {
     }
   }
 
+  fn data_lba(&self, context: &Context) -> Option<u32> {
+    match context.is_joliet() {
+      true => self.joliet_data_lba,
+      false => self.data_lba,
+    }
+  }
+
+  /// POSIX attributes of the root directory, taken from the host filesystem if captured from it.
+  fn attributes(&self, context: &Context) -> PosixAttributes {
+    let attributes = match &self.metadata {
+      Some(metadata) => metadata.into(),
+      None => PosixAttributes::directory(context.recording_date),
+    };
+
+    PosixAttributes {
+      // Counting `RR_MOVED`, if any.
+      links: 2 + self.dirs.len() as u32 + self.relocation_directory(context).is_some() as u32,
+      serial: self.serial.unwrap_or(0),
+      ..attributes.recorded_at(context.recording_date)
+    }
+  }
+
+  /// System Use entries recording the root directory's attributes within the hierarchy of
+  /// `context`, as found in its `..` record and those of its subdirectories.
+  fn attribute_system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    context
+      .rock_ridge
+      .map(|options| self.attributes(context).system_use(&options))
+      .unwrap_or_default()
+  }
+
+  /// System Use entries of the root directory's `.` record, which also announce the
+  /// extensions in use.
+  fn dot_system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    let Some(options) = context.rock_ridge else {
+      return vec![];
+    };
+
+    let mut entries = vec![spec::SystemUseEntry::SharingProtocolIndicator { bytes_skipped: 0 }];
+
+    entries.extend(self.attributes(context).system_use(&options));
+    entries.push(spec::SystemUseEntry::ExtensionsReference {
+      version: 1,
+      identifier: RockRidgeOptions::IDENTIFIER.to_string(),
+      descriptor: RockRidgeOptions::DESCRIPTOR.to_string(),
+      source: RockRidgeOptions::SOURCE.to_string(),
+    });
+
+    entries
+  }
+
   pub(crate) fn root_directory_record(&self, context: &Context) -> spec::RootDirectoryRecord {
     spec::RootDirectoryRecord {
-      extent_location: self.data_lba.unwrap_or(0),
-      data_length: self
-        .files
-        .values()
-        .map(|x| x.directory_record(context).extent() as u32)
-        .sum::<u32>()
-        + self
-          .dirs
-          .values()
-          .map(|x| x.directory_record(context).extent() as u32)
-          .sum::<u32>()
-          // Plus 2 entries for `.` and `..`
-          + (2 * 34),
-      recording_date: chrono::Utc::now().into(),
+      extent_location: self.data_lba(context).unwrap_or(0),
+      data_length: match context.is_joliet() {
+        true => self.joliet_data_length,
+        false => self.data_length,
+      }
+      .expect("Directories should be measured before laying out the hierarchy"),
+      recording_date: context.recording_date.into(),
       file_flags: spec::FileFlags::DIRECTORY,
       file_unit_size: 0,
       interleave_gap_size: 0,
@@ -496,112 +1775,1458 @@ impl RootDirectory {
     }
   }
 
-  pub(crate) fn allocate_lbas(&mut self, allocator: &mut LbaAllocator, context: &Context) {
-    self.data_lba = Some(allocator.allocate(self.root_directory_record(context).data_length));
-
+  /// Measures the extents of the root, `RR_MOVED` and all subdirectories within the hierarchy of
+  /// `context`, each only once.
+  pub(crate) fn measure_directories(&mut self, context: &Context) {
     for dir in self.dirs.values_mut() {
-      dir.allocate_lbas(allocator, context);
+      dir.measure_directories(context);
     }
 
-    for file in self.files.values_mut() {
-      file.allocate_lbas(allocator, context);
+    // Only the primary hierarchy relocates directories.
+    if !context.is_joliet() {
+      self.relocation_data_length = self
+        .relocation_directory(context)
+        .map(|relocation| relocation.measure(context));
     }
-  }
-}
 
-/// Represents a generic filesystem to be included in the ISO image.
-#[derive(Debug, Default)]
-pub struct Filesystem {
-  root: RootDirectory,
-}
-
-impl Filesystem {
-  pub fn new() -> Self {
-    Self::default()
+    let records = self.records(
+      dot_record(spec::Identifier::current_directory(), 0, 0, vec![], context),
+      dot_record(spec::Identifier::parent_directory(), 0, 0, vec![], context),
+      context,
+    );
+    let data_length = Some(directory_data_length(
+      record_extents(&records),
+      context.sector_size,
+    ));
+
+    match context.is_joliet() {
+      true => self.joliet_data_length = data_length,
+      false => self.data_length = data_length,
+    }
   }
 
-  /// Captures the file or directory at `path` and inserts it into the filesystem at `destination`.
-  /// * If `destination` is the root (i.e., `""`), the captured entry will become the root directory.
-  pub fn capture(destination: impl AsRef<Path>, path: impl AsRef<Path>) -> Result<Self> {
-    // TODO(meowesque): Handle destination properly.
-    let mut root = RootDirectory::default();
+  /// Records of the root directory in the order they are written, along with their System Use
+  /// entries, given its `.` and `..` records.
+  fn records(
+    &self,
+    dot: spec::DirectoryRecord,
+    dotdot: spec::DirectoryRecord,
+    context: &Context,
+  ) -> Vec<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)> {
+    directory_records(
+      (dot, self.dot_system_use(context)),
+      (dotdot, self.attribute_system_use(context)),
+      &self.dirs,
+      self
+        .relocation_directory(context)
+        .map(|x| (x.directory_record(context), x.system_use(context))),
+      &self.files,
+      context,
+    )
+  }
 
-    for entry in walkdir::WalkDir::new(&path) {
-      let entry = entry?;
+  /// Marks the directories lying deeper than ISO 9660 allows as relocated.
+  pub(crate) fn relocate_directories(&mut self) {
+    for dir in self.dirs.values_mut() {
+      dir.relocate_directories(2);
+    }
+  }
 
-      if entry.file_type().is_file() {
-        let file = std::fs::File::open(entry.path())?;
-        let content = FileEntryContent::try_from(file)?;
+  /// Directories relocated within the hierarchy of `context`, along with the LBA of their
+  /// original parent.
+  fn relocated_directories(&self, context: &Context) -> Vec<(&DirectoryEntry, u32)> {
+    fn aggregate<'a>(
+      dirs: &'a BTreeMap<ArrayStringU255, DirectoryEntry>,
+      parent_lba: u32,
+      relocated: &mut Vec<(&'a DirectoryEntry, u32)>,
+      context: &Context,
+    ) {
+      for dir in dirs.values() {
+        if dir.is_relocated(context) {
+          relocated.push((dir, parent_lba));
+        }
 
-        root.insert_file(
-          destination
-            .as_ref()
-            .join(entry.path().strip_prefix(path.as_ref()).unwrap()),
-          content,
-          &OnFileConflict::Overwrite,
-        )?;
+        aggregate(
+          &dir.dirs,
+          dir.data_lba(context).unwrap_or(0),
+          relocated,
+          context,
+        );
       }
     }
 
-    Ok(Self { root })
+    let mut relocated = vec![];
+
+    aggregate(
+      &self.dirs,
+      self.data_lba(context).unwrap_or(0),
+      &mut relocated,
+      context,
+    );
+
+    relocated
   }
 
-  /// Merge another filesystem into this one, resolving file conflicts according to `on_file_conflict`.
-  pub fn merge(&mut self, other: Filesystem, on_file_conflict: &OnFileConflict) -> Result<()> {
-    self.root.merge(other.root, on_file_conflict);
-    Ok(())
+  /// `RR_MOVED` directory of the hierarchy of `context`, if it relocates any directory.
+  fn relocation_directory(&self, context: &Context) -> Option<RelocationDirectory<'_>> {
+    let relocated = self.relocated_directories(context);
+
+    if relocated.is_empty() {
+      return None;
+    }
+
+    // Relocated directories may share an identifier, which must be unique within `RR_MOVED`.
+    let names = relocated
+      .iter()
+      .map(|(dir, _)| dir.identifier(context).to_string())
+      .collect::<Vec<_>>();
+    let names = names
+      .iter()
+      .map(|name| (name.as_str(), true))
+      .collect::<Vec<_>>();
+    let mut dirs = mangle::identifiers(&names, &[], context.compatibility_mode)
+      .into_iter()
+      .zip(relocated)
+      .map(|(identifier, (dir, parent_lba))| {
+        let identifier = identifier.expect("Mapped identifiers should remain valid");
+
+        (identifier, dir, parent_lba)
+      })
+      .collect::<Vec<_>>();
+
+    // Kept in the order of their records, which the path table follows too.
+    dirs.sort_by(|(a, _, _), (b, _, _)| a.cmp_record_order(b));
+
+    Some(RelocationDirectory { root: self, dirs })
   }
 
-  /// Inserts a file into the filesystem at the specified `path`.
-  ///
-  /// * If a file already exists at that path, the behavior is determined by `on_file_conflict`.
-  pub fn insert_file(
+  /// Allocates LBAs for the root, its continuation areas, `RR_MOVED` and all subdirectories
+  /// within the hierarchy of `context`.
+  pub(crate) fn allocate_directory_lbas(
     &mut self,
-    path: impl AsRef<Path>,
-    content: FileEntryContent,
-    on_file_conflict: &OnFileConflict,
+    allocator: &mut LbaAllocator,
+    context: &Context,
   ) -> Result<()> {
-    self.root.insert_file(path, content, on_file_conflict)
-  }
+    let data_length = self.root_directory_record(context).data_length;
+    let lba = allocator.allocate(data_length);
+    let records = self.records(
+      dot_record(
+        spec::Identifier::current_directory(),
+        lba,
+        data_length,
+        vec![],
+        context,
+      ),
+      dot_record(
+        spec::Identifier::parent_directory(),
+        lba,
+        data_length,
+        vec![],
+        context,
+      ),
+      context,
+    );
+    let (continuation_areas, _) = layout_system_use(lba, &records, context.sector_size)?;
 
-  /// Iterator over all top-level directories in the filesystem.
-  pub fn directories_iter(&self) -> impl Iterator<Item = &DirectoryEntry> {
-    self.root.dirs.values()
-  }
+    allocator.allocate(continuation_areas.size());
 
-  /// Iterator over all top-level files in the filesystem.
-  pub fn files_iter(&self) -> impl Iterator<Item = &FileEntry> {
-    self.root.files.values()
-  }
+    match context.is_joliet() {
+      true => self.joliet_data_lba = Some(lba),
+      false => self.data_lba = Some(lba),
+    }
 
-  pub(crate) fn allocate_lbas(&mut self, allocator: &mut LbaAllocator, context: &Context) {
-    self.root.allocate_lbas(allocator, context);
-  }
-}
+    let relocation_lba = self
+      .relocation_directory(context)
+      .map(|relocation| -> Result<u32> {
+        let data_length = relocation.data_length();
+        let lba = allocator.allocate(data_length);
+        let records = relocation.records(
+          dot_record(
+            spec::Identifier::current_directory(),
+            lba,
+            data_length,
+            vec![],
+            context,
+          ),
+          dot_record(spec::Identifier::parent_directory(), 0, 0, vec![], context),
+          context,
+        );
+        let (continuation_areas, _) = layout_system_use(lba, &records, context.sector_size)?;
 
-/// Behavior when a file is already present in the filesystem.
-pub enum OnFileConflict {
-  /// Replace the existing file with the new one.
-  Overwrite,
-  /// Ignore the new file and keep the existing one.
-  Ignore,
-  /// Custom handler that takes the existing and new file entries and returns the one to keep.
-  Handler(Rc<dyn Fn(FileEntry, FileEntry) -> FileEntry>),
-}
+        allocator.allocate(continuation_areas.size());
 
-#[derive(Debug, Default)]
-struct BootRecord {}
+        Ok(lba)
+      })
+      .transpose()?;
 
-#[derive(Debug, Clone)]
-pub struct IsoWriterOptions {
-  pub joliet: bool,
-  pub sector_size: u32,
-}
+    if !context.is_joliet() {
+      self.relocation_lba = relocation_lba;
+    }
+
+    for dir in self.dirs.values_mut() {
+      dir.allocate_directory_lbas(allocator, context)?;
+    }
+
+    Ok(())
+  }
+
+  /// Maps the names of all entries onto identifiers within the hierarchy of `context`. Once it
+  /// relocates any directory, `RR_MOVED` takes its place among the root's entries.
+  pub(crate) fn map_identifiers(&mut self, path: &Path, context: &Context) -> Result<()> {
+    let reserved = match self.relocated_directories(context).is_empty() {
+      true => vec![],
+      false => vec![RelocationDirectory::NAME],
+    };
+
+    map_identifiers(path, &mut self.dirs, &mut self.files, &reserved, context)?;
+
+    for dir in self.dirs.values_mut() {
+      dir.map_identifiers(path, context)?;
+    }
+
+    Ok(())
+  }
+
+  /// Allocates LBAs for the data of all files, recursively.
+  pub(crate) fn allocate_file_lbas(&mut self, allocator: &mut LbaAllocator) {
+    for dir in self.dirs.values_mut() {
+      dir.allocate_file_lbas(allocator);
+    }
+
+    for file in self.files.values_mut() {
+      file.allocate_lbas(allocator);
+    }
+  }
+
+  /// Path of a file within the filesystem, recursively, whose data spans multiple extents.
+  fn multi_extent_file(&self, path: &Path) -> Option<PathBuf> {
+    self
+      .dirs
+      .values()
+      .find_map(|dir| dir.multi_extent_file(&path.join(dir.name.as_str())))
+      .or_else(|| {
+        self
+          .files
+          .values()
+          .find(|x| x.extent_lengths().len() > 1)
+          .map(|x| path.join(x.name()))
+      })
+  }
+
+  /// Checks that Rock Ridge can record the targets of the symbolic links within the directory,
+  /// recursively.
+  fn check_symbolic_links(&self) -> Result<()> {
+    for dir in self.dirs.values() {
+      dir.check_symbolic_links()?;
+    }
+
+    for target in self
+      .files
+      .values()
+      .filter_map(|x| x.content.symbolic_link_target())
+    {
+      RockRidgeOptions::symbolic_link(target)?;
+    }
+
+    Ok(())
+  }
+
+  /// Path of a directory relocated as it lies deeper than ISO 9660 allows, if any.
+  fn relocated_directory(&self, path: &Path) -> Option<PathBuf> {
+    self.dirs.values().find_map(|dir| {
+      let path = path.join(dir.name.as_str());
+
+      match dir.relocated {
+        true => Some(path),
+        false => dir.relocated_directory(&path),
+      }
+    })
+  }
+
+  /// Gives the root directory, `RR_MOVED` if any directory is relocated, and all directories and
+  /// files within the next serial numbers of `serials`.
+  fn assign_serials(&mut self, serials: &mut impl Iterator<Item = u32>) {
+    self.serial = serials.next();
+    self.relocation_serial = self
+      .relocated_directory(Path::new("/"))
+      .and_then(|_| serials.next());
+
+    for dir in self.dirs.values_mut() {
+      dir.assign_serials(serials);
+    }
+
+    for file in self.files.values_mut() {
+      file.serial = serials.next();
+    }
+  }
+
+  /// Compresses the content of the files with zisofs, except for the `excluded` content.
+  pub(crate) fn compress_files(
+    &mut self,
+    options: &ZisofsOptions,
+    excluded: &[FileEntryContent],
+  ) -> Result<()> {
+    for dir in self.dirs.values_mut() {
+      dir.compress_files(options, excluded)?;
+    }
+
+    for file in self.files.values_mut() {
+      if !excluded.iter().any(|x| x.ptr_eq(&file.content)) {
+        file.compress(options)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// `RR_MOVED` directory of the primary hierarchy, holding the directories relocated as they lie
+/// deeper than ISO 9660 allows. Rock Ridge records where they actually belong through `CL`, `PL`
+/// and `RE` entries.
+struct RelocationDirectory<'a> {
+  root: &'a RootDirectory,
+  /// Relocated directories along with their unique identifiers within `RR_MOVED`, and the LBA of
+  /// the parent they were relocated from.
+  dirs: Vec<(spec::Identifier, &'a DirectoryEntry, u32)>,
+}
+
+impl RelocationDirectory<'_> {
+  /// Deepest level of a directory hierarchy, the root being on the first.
+  const MAX_LEVEL: usize = 8;
+  const NAME: &'static str = "rr_moved";
+
+  fn attributes(&self, context: &Context) -> PosixAttributes {
+    PosixAttributes {
+      links: 2 + self.dirs.len() as u32,
+      serial: self.root.relocation_serial.unwrap_or(0),
+      ..PosixAttributes::directory(context.recording_date)
+    }
+  }
+
+  /// System Use entries recording the directory's attributes, as found in its `.` record and the
+  /// `..` records of the relocated directories.
+  fn attribute_system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    context
+      .rock_ridge
+      .map(|options| self.attributes(context).system_use(&options))
+      .unwrap_or_default()
+  }
+
+  /// System Use entries of the directory's record within the root.
+  fn system_use(&self, context: &Context) -> Vec<spec::SystemUseEntry> {
+    let mut entries = self.attribute_system_use(context);
+
+    entries.extend(RockRidgeOptions::alternate_name(Self::NAME.as_bytes()));
+
+    entries
+  }
+
+  /// Records of the directory in the order they are written, along with their System Use
+  /// entries, given its `.` and `..` records.
+  fn records(
+    &self,
+    dot: spec::DirectoryRecord,
+    dotdot: spec::DirectoryRecord,
+    context: &Context,
+  ) -> Vec<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)> {
+    [
+      (dot, self.attribute_system_use(context)),
+      (dotdot, self.root.attribute_system_use(context)),
+    ]
+    .into_iter()
+    .chain(self.dirs.iter().map(|(identifier, dir, _)| {
+      (
+        dir.relocated_record(identifier, context),
+        dir.relocated_system_use(context),
+      )
+    }))
+    .collect()
+  }
+
+  fn data_length(&self) -> u32 {
+    self
+      .root
+      .relocation_data_length
+      .expect("Directories should be measured before laying out the hierarchy")
+  }
+
+  /// Length of the directory's extent, measured from its records.
+  fn measure(&self, context: &Context) -> u32 {
+    let records = self.records(
+      dot_record(spec::Identifier::current_directory(), 0, 0, vec![], context),
+      dot_record(spec::Identifier::parent_directory(), 0, 0, vec![], context),
+      context,
+    );
+
+    directory_data_length(record_extents(&records), context.sector_size)
+  }
+
+  fn directory_record(&self, context: &Context) -> spec::DirectoryRecord {
+    let file_identifier =
+      spec::Identifier::directory_identifier(Self::NAME, context.compatibility_mode)
+        .expect("RR_MOVED should be valid at every interchange level");
+
+    let mut record = spec::DirectoryRecord {
+      extended_attribute_length: 0,
+      extent_location: self.root.relocation_lba.unwrap_or(0),
+      data_length: self.data_length(),
+      recording_date: context.recording_date.into(),
+      file_flags: spec::FileFlags::DIRECTORY,
+      file_unit_size: 0,
+      interleave_gap_size: 0,
+      volume_sequence_number: 0,
+      file_identifier_length: file_identifier.extent() as u8,
+      file_identifier,
+      system_use: vec![],
+    };
+
+    ContinuationAreas::attach(&mut record, self.system_use(context));
+
+    record
+  }
+
+  /// `..` record of the relocated directories, along with its System Use entries.
+  fn dotdot(&self, context: &Context) -> (spec::DirectoryRecord, Vec<spec::SystemUseEntry>) {
+    (
+      dot_record(
+        spec::Identifier::parent_directory(),
+        self.root.relocation_lba.unwrap_or(0),
+        self.data_length(),
+        vec![],
+        context,
+      ),
+      self.attribute_system_use(context),
+    )
+  }
+}
+
+/// Represents a generic filesystem to be included in the ISO image.
+#[derive(Debug, Default)]
+pub struct Filesystem {
+  root: RootDirectory,
+}
+
+impl Filesystem {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Captures the file or directory at `path` and inserts it into the filesystem at `destination`.
+  /// * If `destination` is the root (i.e., `""`), the captured entry will become the root directory.
+  pub fn capture(destination: impl AsRef<Path>, path: impl AsRef<Path>) -> Result<Self> {
+    // TODO(meowesque): Handle destination properly.
+    let mut root = RootDirectory::default();
+
+    for entry in walkdir::WalkDir::new(&path) {
+      let entry = entry?;
+      let iso_path = destination
+        .as_ref()
+        .join(entry.path().strip_prefix(path.as_ref()).unwrap());
+
+      #[cfg(unix)]
+      let special = {
+        use std::os::unix::fs::FileTypeExt;
+
+        let file_type = entry.file_type();
+
+        file_type.is_block_device() || file_type.is_char_device() || file_type.is_fifo()
+      };
+      #[cfg(not(unix))]
+      let special = false;
+
+      let content = if entry.file_type().is_file() {
+        FileEntryContent::try_from(std::fs::File::open(entry.path())?)?
+      } else if special {
+        // Devices and FIFOs are recorded through their attributes alone, never opened.
+        FileEntryContent::special(entry.metadata()?)
+      } else if entry.path_is_symlink() {
+        FileEntryContent(Rc::new(FileEntryContentInner::SymbolicLink {
+          target: std::fs::read_link(entry.path())?,
+          metadata: Some(entry.metadata()?),
+        }))
+      } else {
+        // Directories keep their attributes, even when empty.
+        if entry.file_type().is_dir() {
+          root.insert_directory(&iso_path, entry.metadata()?);
+        }
+
+        continue;
+      };
+
+      root.insert_file(iso_path, content, &OnFileConflict::Overwrite)?;
+    }
+
+    Ok(Self { root })
+  }
+
+  /// Merge another filesystem into this one, resolving file conflicts according to `on_file_conflict`.
+  pub fn merge(&mut self, other: Filesystem, on_file_conflict: &OnFileConflict) -> Result<()> {
+    self.root.merge(other.root, on_file_conflict);
+    Ok(())
+  }
+
+  /// Inserts a file into the filesystem at the specified `path`.
+  ///
+  /// * If a file already exists at that path, the behavior is determined by `on_file_conflict`.
+  pub fn insert_file(
+    &mut self,
+    path: impl AsRef<Path>,
+    content: FileEntryContent,
+    on_file_conflict: &OnFileConflict,
+  ) -> Result<()> {
+    self.root.insert_file(path, content, on_file_conflict)
+  }
+
+  /// Looks up the file at `path`.
+  pub fn file(&self, path: impl AsRef<Path>) -> Option<&FileEntry> {
+    self.root.file(path)
+  }
+
+  /// Iterator over all top-level directories in the filesystem.
+  pub fn directories_iter(&self) -> impl Iterator<Item = &DirectoryEntry> {
+    self.root.dirs.values()
+  }
+
+  /// Iterator over all top-level files in the filesystem.
+  pub fn files_iter(&self) -> impl Iterator<Item = &FileEntry> {
+    self.root.files.values()
+  }
+
+  pub(crate) fn allocate_directory_lbas(
+    &mut self,
+    allocator: &mut LbaAllocator,
+    context: &Context,
+  ) -> Result<()> {
+    self.root.allocate_directory_lbas(allocator, context)
+  }
+
+  pub(crate) fn allocate_file_lbas(&mut self, allocator: &mut LbaAllocator) {
+    self.root.allocate_file_lbas(allocator);
+  }
+
+  pub(crate) fn map_identifiers(&mut self, context: &Context) -> Result<()> {
+    self.root.map_identifiers(Path::new("/"), context)
+  }
+
+  pub(crate) fn measure_directories(&mut self, context: &Context) {
+    self.root.measure_directories(context);
+  }
+
+  /// Identifier of the file at `path` within the hierarchy of `context`, for volume descriptors
+  /// to refer to. Volume descriptors only refer to files within the root directory.
+  pub(crate) fn root_file_identifier(
+    &self,
+    path: impl AsRef<Path>,
+    context: &Context,
+  ) -> Result<&spec::Identifier> {
+    let path = path.as_ref();
+    let file = self
+      .file(path)
+      .ok_or_else(|| Error::NotFound(path.display().to_string()))?;
+    let depth = path
+      .components()
+      .filter(|x| matches!(x, std::path::Component::Normal(_)))
+      .count();
+
+    match depth {
+      1 => Ok(file.identifier(context)),
+      _ => Err(Error::NotInRootDirectory(path.to_path_buf())),
+    }
+  }
+
+  /// Gives every file and directory a serial number that no other has, in the order they are
+  /// laid out rather than that of the host's inode numbers. Readers such as libarchive take files
+  /// sharing a serial number for hard links.
+  pub(crate) fn assign_serials(&mut self) {
+    self.root.assign_serials(&mut (1..));
+  }
+
+  pub(crate) fn compress_files(
+    &mut self,
+    options: &ZisofsOptions,
+    excluded: &[FileEntryContent],
+  ) -> Result<()> {
+    self.root.compress_files(options, excluded)
+  }
+
+  /// Path of a file whose data spans multiple extents, if any.
+  pub(crate) fn multi_extent_file(&self) -> Option<PathBuf> {
+    self.root.multi_extent_file(Path::new("/"))
+  }
+
+  /// Path of a directory relocated as it lies deeper than ISO 9660 allows, if any.
+  pub(crate) fn relocated_directory(&self) -> Option<PathBuf> {
+    self.root.relocated_directory(Path::new("/"))
+  }
+
+  /// Checks that Rock Ridge can record the targets of all symbolic links.
+  pub(crate) fn check_symbolic_links(&self) -> Result<()> {
+    self.root.check_symbolic_links()
+  }
+}
+
+/// Behavior when a file is already present in the filesystem.
+pub enum OnFileConflict {
+  /// Replace the existing file with the new one.
+  Overwrite,
+  /// Ignore the new file and keep the existing one.
+  Ignore,
+  /// Custom handler that takes the existing and new file entries and returns the one to keep.
+  Handler(Rc<dyn Fn(FileEntry, FileEntry) -> FileEntry>),
+}
+
+/// El Torito boot configuration.
+#[derive(Debug, Clone)]
+pub struct BootOptions {
+  /// Platform of the validation entry, which also applies to the default entry.
+  pub platform_id: spec::ElToritoPlatformId,
+  /// Manufacturer or developer of the image, at most 24 ASCII characters.
+  pub manufacturer_id: String,
+  /// Entry booted by default.
+  pub default_entry: BootEntry,
+  /// Further sections, e.g. an EFI section next to a BIOS default entry.
+  pub sections: Vec<BootSection>,
+}
+
+impl BootOptions {
+  pub fn new(platform_id: spec::ElToritoPlatformId, default_entry: BootEntry) -> Self {
+    Self {
+      platform_id,
+      manufacturer_id: String::new(),
+      default_entry,
+      sections: vec![],
+    }
+  }
+
+  /// Every entry of the catalog, starting with the default entry.
+  fn entries(&self) -> impl Iterator<Item = &BootEntry> {
+    std::iter::once(&self.default_entry).chain(self.sections.iter().flat_map(|x| &x.entries))
+  }
+
+  /// Size of the boot catalog in bytes.
+  fn catalog_size(&self) -> u32 {
+    let section_records = self
+      .sections
+      .iter()
+      .map(|section| {
+        1 + section
+          .entries
+          .iter()
+          .map(BootEntry::record_count)
+          .sum::<usize>()
+      })
+      .sum::<usize>();
+
+    // The validation and default entries precede the sections.
+    (2 + section_records) as u32 * 32
+  }
+
+  /// Serializes the boot catalog, referencing boot images at their allocated LBAs.
+  fn catalog(&self, images: &BootImages, sector_size: u32) -> Result<Vec<u8>> {
+    let manufacturer_id =
+      spec::ElToritoManufacturerId::new(&self.manufacturer_id).ok_or_else(|| {
+        Error::InvalidBootConfiguration(format!(
+          "manufacturer ID {:?} is not at most 24 ASCII characters",
+          self.manufacturer_id
+        ))
+      })?;
+
+    if self.default_entry.selection_criteria.is_some() {
+      return Err(Error::InvalidBootConfiguration(
+        "the default entry cannot carry selection criteria".to_string(),
+      ));
+    }
+
+    let mut catalog = vec![0u8; (self.catalog_size().div_ceil(sector_size) * sector_size) as usize];
+
+    spec::ElToritoValidationEntry::new(self.platform_id, manufacturer_id)
+      .serialize(&mut (), &mut catalog[0..32])?;
+    self
+      .default_entry
+      .initial_entry(images)?
+      .serialize(&mut (), &mut catalog[32..64])?;
+
+    let mut records = catalog[64..].chunks_exact_mut(32);
+
+    for (ix, section) in self.sections.iter().enumerate() {
+      let section_id = spec::ElToritoSectionId::new(&section.id).ok_or_else(|| {
+        Error::InvalidBootConfiguration(format!(
+          "section ID {:?} is not at most 28 ASCII characters",
+          section.id
+        ))
+      })?;
+
+      if section.entries.is_empty() {
+        return Err(Error::InvalidBootConfiguration(format!(
+          "section {:?} has no entries",
+          section.id
+        )));
+      }
+
+      let header_indicator = match ix + 1 == self.sections.len() {
+        true => spec::ElToritoHeaderIndicator::FinalHeader,
+        false => spec::ElToritoHeaderIndicator::MoreHeadersFollow,
+      };
+
+      spec::ElToritoSectionHeaderEntry {
+        header_indicator,
+        platform_id: section.platform_id,
+        // Extension records don't count towards the entries of a section.
+        succeeding_section_entries: section.entries.len() as u16,
+        section_id,
+      }
+      .serialize(&mut (), records.next().unwrap())?;
+
+      for entry in &section.entries {
+        let (section_entry, extensions) = entry.section_entry(images)?;
+
+        section_entry.serialize(&mut (), records.next().unwrap())?;
+
+        for extension in extensions {
+          extension.serialize(&mut (), records.next().unwrap())?;
+        }
+      }
+    }
+
+    Ok(catalog)
+  }
+}
+
+/// Section of the boot catalog, grouping entries for a single platform.
+#[derive(Debug, Clone)]
+pub struct BootSection {
+  pub platform_id: spec::ElToritoPlatformId,
+  /// Identifier of the section, at most 28 ASCII characters.
+  pub id: String,
+  pub entries: Vec<BootEntry>,
+}
+
+impl BootSection {
+  pub fn new(platform_id: spec::ElToritoPlatformId, entries: Vec<BootEntry>) -> Self {
+    Self {
+      platform_id,
+      id: String::new(),
+      entries,
+    }
+  }
+}
+
+/// Vendor specific criteria used to pick among the entries of a section.
+#[derive(Debug, Clone)]
+pub struct SelectionCriteria {
+  pub criteria_type: spec::ElToritoSelectionCriteriaType,
+  /// The first 19 bytes are stored in the section entry, the remainder in
+  /// extension records of 30 bytes each.
+  pub data: Vec<u8>,
+}
+
+/// Image booted by a [BootEntry].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootImage {
+  /// Path of the image within the filesystem.
+  File(PathBuf),
+  /// EFI system partition appended through [GptOptions].
+  EfiSystemPartition,
+}
+
+impl BootImage {
+  fn resolve<'a>(&self, images: &BootImages<'a>) -> Result<&'a FileEntry> {
+    match self {
+      Self::File(path) => images
+        .filesystem
+        .file(path)
+        .ok_or_else(|| Error::NotFound(path.display().to_string())),
+      Self::EfiSystemPartition => images.efi_system_partition.ok_or_else(|| {
+        Error::InvalidBootConfiguration(
+          "boot image references an EFI system partition, but none is appended".to_string(),
+        )
+      }),
+    }
+  }
+}
+
+impl std::fmt::Display for BootImage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::File(path) => write!(f, "{}", path.display()),
+      Self::EfiSystemPartition => write!(f, "[EFI system partition]"),
+    }
+  }
+}
+
+impl From<PathBuf> for BootImage {
+  fn from(path: PathBuf) -> Self {
+    Self::File(path)
+  }
+}
+
+impl From<&Path> for BootImage {
+  fn from(path: &Path) -> Self {
+    Self::File(path.to_owned())
+  }
+}
+
+impl From<&str> for BootImage {
+  fn from(path: &str) -> Self {
+    Self::File(path.into())
+  }
+}
+
+impl From<String> for BootImage {
+  fn from(path: String) -> Self {
+    Self::File(path.into())
+  }
+}
+
+/// Files boot entries may reference, with their LBAs allocated.
+struct BootImages<'a> {
+  filesystem: &'a Filesystem,
+  efi_system_partition: Option<&'a FileEntry>,
+}
+
+/// Boot image referenced by the El Torito boot catalog.
+#[derive(Debug, Clone)]
+pub struct BootEntry {
+  pub image: BootImage,
+  pub emulation_type: spec::ElToritoEmulationType,
+  /// Segment the image is loaded at, zero selecting the traditional `0x7C0`.
+  pub load_segment: u16,
+  /// Partition type of the image. Hard disk emulation ignores this and reads
+  /// it from the partition table of the image instead.
+  pub system_type: u8,
+  /// Number of 512 byte virtual sectors loaded by the BIOS. Entries booting
+  /// the EFI system partition use the size of the partition instead.
+  pub sector_count: u16,
+  pub bootable: bool,
+  /// Patch a [spec::BootInfoTable] into the image at offset 8. Only the
+  /// written image is patched, the filesystem content is left untouched.
+  pub boot_info_table: bool,
+  /// Selection criteria, only available to entries of a [BootSection].
+  pub selection_criteria: Option<SelectionCriteria>,
+}
+
+impl BootEntry {
+  pub fn new(image: impl Into<BootImage>, emulation_type: spec::ElToritoEmulationType) -> Self {
+    Self {
+      image: image.into(),
+      emulation_type,
+      load_segment: 0,
+      system_type: 0,
+      sector_count: 1,
+      bootable: true,
+      boot_info_table: false,
+      selection_criteria: None,
+    }
+  }
+
+  /// No emulation entry loading the first 2048 bytes of `image`, as isolinux and GRUB expect.
+  pub fn no_emulation(image: impl Into<BootImage>) -> Self {
+    Self {
+      sector_count: 4,
+      ..Self::new(image, spec::ElToritoEmulationType::NoEmulation)
+    }
+  }
+
+  fn image<'a>(&self, images: &BootImages<'a>) -> Result<&'a FileEntry> {
+    self.image.resolve(images)
+  }
+
+  fn sector_count(&self, image: &FileEntry) -> u16 {
+    match self.image {
+      // UEFI treats a count of zero as extending to the end of the medium.
+      BootImage::EfiSystemPartition => {
+        u16::try_from(image.content.extent().div_ceil(512)).unwrap_or(0)
+      }
+      BootImage::File(_) => self.sector_count,
+    }
+  }
+
+  /// Checks the image against the emulation type, returning the system type
+  /// of the entry.
+  fn system_type(&self, image: &FileEntry) -> Result<u8> {
+    let invalid = |reason: String| {
+      Error::InvalidBootConfiguration(format!("boot image {} {reason}", self.image))
+    };
+
+    if self.boot_info_table && self.emulation_type != spec::ElToritoEmulationType::NoEmulation {
+      return Err(invalid(
+        "requests a boot info table, which requires no emulation".to_string(),
+      ));
+    }
+
+    match self.emulation_type {
+      spec::ElToritoEmulationType::NoEmulation => Ok(self.system_type),
+      spec::ElToritoEmulationType::Floppy12M
+      | spec::ElToritoEmulationType::Floppy144M
+      | spec::ElToritoEmulationType::Floppy288M => {
+        let expected = self.emulation_type.floppy_size().unwrap();
+
+        match image.content.extent() {
+          size if size == expected => Ok(self.system_type),
+          size => Err(invalid(format!(
+            "is {size} bytes, {:?} emulation requires {expected}",
+            self.emulation_type
+          ))),
+        }
+      }
+      spec::ElToritoEmulationType::HardDisk => {
+        let mut mbr = [0u8; 512];
+
+        image
+          .content
+          .reader()?
+          .read_exact(&mut mbr)
+          .map_err(|_| invalid("is smaller than a master boot record".to_string()))?;
+
+        let table =
+          spec::MbrPartitionTable::deserialize(&mut (), &mbr[spec::MbrPartitionTable::OFFSET..])
+            .map_err(|_| invalid("has no master boot record signature".to_string()))?;
+
+        // The emulated disk must hold exactly one partition.
+        let mut partitions = table.entries.iter().filter(|x| !x.is_empty());
+
+        match (partitions.next(), partitions.next()) {
+          (Some(partition), None) => Ok(partition.partition_type),
+          _ => Err(invalid(
+            "must contain exactly one partition for hard disk emulation".to_string(),
+          )),
+        }
+      }
+    }
+  }
+
+  fn boot_indicator(&self) -> spec::ElToritoBootIndicator {
+    match self.bootable {
+      true => spec::ElToritoBootIndicator::Bootable,
+      false => spec::ElToritoBootIndicator::NonBootable,
+    }
+  }
+
+  fn write_boot_info_table<W: std::io::Write + std::io::Seek>(
+    &self,
+    writer: &mut W,
+    images: &BootImages,
+    sector_size: u32,
+  ) -> Result<()> {
+    let image = self.image(images)?;
+    let Some(lba) = image.data_lba() else {
+      unreachable!("File LBA should have been allocated by now");
+    };
+
+    let mut data = vec![];
+    image.content.reader()?.read_to_end(&mut data)?;
+
+    if data.len() < 64 {
+      return Err(Error::InvalidBootConfiguration(format!(
+        "boot image {} is too small for a boot info table",
+        self.image
+      )));
+    }
+
+    let table = spec::BootInfoTable {
+      // Descriptors start at LBA 16, beginning with the primary volume descriptor.
+      primary_volume_descriptor: 16,
+      boot_file_location: lba,
+      boot_file_length: data.len() as u32,
+      checksum: spec::BootInfoTable::checksum(&data),
+    };
+    let mut table_bytes = [0u8; 56];
+
+    table.serialize(&mut (), &mut table_bytes)?;
+    writer.seek(std::io::SeekFrom::Start(
+      lba as u64 * sector_size as u64 + spec::BootInfoTable::OFFSET as u64,
+    ))?;
+    writer.write_all(&table_bytes)?;
+
+    Ok(())
+  }
+
+  /// Selection criteria type and bytes of the entry.
+  fn criteria(&self) -> (spec::ElToritoSelectionCriteriaType, &[u8]) {
+    match &self.selection_criteria {
+      Some(criteria) => (criteria.criteria_type, &criteria.data),
+      None => (
+        spec::ElToritoSelectionCriteriaType::NoSelectionCriteria,
+        &[],
+      ),
+    }
+  }
+
+  /// Number of catalog records taken up as a section entry, including extensions.
+  fn record_count(&self) -> usize {
+    1 + self.criteria().1.len().saturating_sub(19).div_ceil(30)
+  }
+
+  fn section_entry(
+    &self,
+    images: &BootImages,
+  ) -> Result<(
+    spec::ElToritoSectionEntry,
+    Vec<spec::ElToritoSectionEntryExtension>,
+  )> {
+    let image = self.image(images)?;
+    let Some(lba) = image.data_lba() else {
+      unreachable!("File LBA should have been allocated by now");
+    };
+
+    let (selection_criteria_type, criteria) = self.criteria();
+    let (head, tail) = criteria.split_at(criteria.len().min(19));
+    let mut vendor_selection_criteria = [0u8; 19];
+    vendor_selection_criteria[..head.len()].copy_from_slice(head);
+
+    let chunks = tail.chunks(30).collect::<Vec<_>>();
+    let extensions = chunks
+      .iter()
+      .enumerate()
+      .map(|(ix, chunk)| {
+        let mut vendor_unique_selection_criteria = [0u8; 30];
+        vendor_unique_selection_criteria[..chunk.len()].copy_from_slice(chunk);
+
+        spec::ElToritoSectionEntryExtension {
+          extension_record_follows_indicator: match ix + 1 < chunks.len() {
+            true => spec::ElToritoExtensionRecordFollowsIndicator::EXTENSION_RECORD_FOLLOWS,
+            false => spec::ElToritoExtensionRecordFollowsIndicator::empty(),
+          },
+          vendor_unique_selection_criteria,
+        }
+      })
+      .collect::<Vec<_>>();
+
+    let entry = spec::ElToritoSectionEntry {
+      boot_indicator: self.boot_indicator(),
+      boot_media_type: spec::ElToritoBootMediaTypeExt {
+        emulation_type: self.emulation_type,
+        continuation_entry_follows: !extensions.is_empty(),
+        contains_atapi_driver: false,
+        contains_scsi_drivers: false,
+      },
+      load_segment: self.load_segment,
+      system_type: self.system_type(image)?,
+      sector_count: self.sector_count(image),
+      virtual_disk_location: lba,
+      selection_criteria_type,
+      vendor_selection_criteria,
+    };
+
+    Ok((entry, extensions))
+  }
+
+  fn initial_entry(&self, images: &BootImages) -> Result<spec::ElToritoInitialSectionEntry> {
+    let image = self.image(images)?;
+    let Some(lba) = image.data_lba() else {
+      unreachable!("File LBA should have been allocated by now");
+    };
+
+    Ok(spec::ElToritoInitialSectionEntry {
+      boot_indicator: self.boot_indicator(),
+      boot_media_type: self.emulation_type.into(),
+      load_segment: self.load_segment,
+      system_type: self.system_type(image)?,
+      sector_count: self.sector_count(image),
+      virtual_disk_location: lba,
+    })
+  }
+}
+
+/// Isohybrid master boot record written to the system area, so that the image
+/// boots when copied onto a USB drive.
+#[derive(Debug, Clone)]
+pub struct IsohybridOptions {
+  /// Boot code template such as syslinux's `isohdpfx.bin`. Only the first 432
+  /// bytes are used, the remainder of the sector is generated.
+  pub mbr_template: Vec<u8>,
+  /// Partition type of the entry covering the image.
+  pub partition_type: u8,
+  pub disk_signature: u32,
+  /// Disk geometry, the image being padded to a whole number of cylinders.
+  pub heads: u8,
+  pub sectors_per_track: u8,
+}
+
+impl IsohybridOptions {
+  /// Size of the boot code area preceding the disk signature.
+  const BOOT_CODE_SIZE: usize = 432;
+
+  /// Options matching the defaults of syslinux's `isohybrid`.
+  pub fn new(mbr_template: Vec<u8>) -> Self {
+    Self {
+      mbr_template,
+      partition_type: 0x17,
+      disk_signature: 0,
+      heads: 64,
+      sectors_per_track: 32,
+    }
+  }
+
+  /// Size of a cylinder in bytes.
+  fn cylinder_size(&self) -> u64 {
+    self.heads as u64 * self.sectors_per_track as u64 * 512
+  }
+
+  /// Size the image is padded to a multiple of, spanning whole cylinders as well as whole
+  /// sectors of `sector_size` bytes. Common geometries such as 255 heads and 63 sectors per
+  /// track have cylinders spanning partial sectors.
+  fn alignment(&self, sector_size: u32) -> u64 {
+    let (mut a, mut b) = (self.cylinder_size(), sector_size as u64);
+
+    while b != 0 {
+      (a, b) = (b, a % b);
+    }
+
+    self.cylinder_size() / a * sector_size as u64
+  }
+
+  /// Cylinder-head-sector address of a 512 byte sector, clamped to the last
+  /// cylinder addressable through CHS.
+  fn chs(&self, sector: u64) -> [u8; 3] {
+    let heads = self.heads as u64;
+    let sectors = self.sectors_per_track as u64;
+    let sector = sector.min(1024 * heads * sectors - 1);
+    let cylinder = sector / (heads * sectors);
+
+    [
+      ((sector / sectors) % heads) as u8,
+      (sector % sectors + 1) as u8 | ((cylinder >> 2) & 0xC0) as u8,
+      cylinder as u8,
+    ]
+  }
+
+  fn validate(&self) -> Result<()> {
+    if self.mbr_template.len() > 512 {
+      return Err(Error::InvalidBootConfiguration(format!(
+        "MBR template is {} bytes, expected at most 512",
+        self.mbr_template.len()
+      )));
+    }
+
+    if self.heads == 0 || !(1..=63).contains(&self.sectors_per_track) {
+      return Err(Error::InvalidBootConfiguration(format!(
+        "invalid disk geometry of {} heads and {} sectors per track",
+        self.heads, self.sectors_per_track
+      )));
+    }
+
+    Ok(())
+  }
+
+  /// Builds the MBR for an image of `image_size` bytes, with the boot code
+  /// loading the boot image found at `boot_image_lba`.
+  fn mbr(&self, boot_image_lba: u32, sector_size: u32, image_size: u64) -> Result<[u8; 512]> {
+    let sectors = image_size / 512;
+    let mut mbr = [0u8; 512];
+    let template = &self.mbr_template[..self.mbr_template.len().min(Self::BOOT_CODE_SIZE)];
+
+    mbr[..template.len()].copy_from_slice(template);
+    // The boot code loads the boot image through its 512 byte sector address.
+    mbr[432..436].copy_from_slice(&(boot_image_lba * (sector_size / 512)).to_le_bytes());
+    mbr[440..444].copy_from_slice(&self.disk_signature.to_le_bytes());
+
+    let mut table = spec::MbrPartitionTable::default();
+    table.entries[0] = spec::MbrPartitionEntry {
+      boot_indicator: 0x80,
+      start_chs: self.chs(0),
+      partition_type: self.partition_type,
+      end_chs: self.chs(sectors - 1),
+      start_lba: 0,
+      sector_count: sectors.try_into().map_err(|_| {
+        Error::InvalidBootConfiguration("image is too large for an MBR partition".to_string())
+      })?,
+    };
+    table.serialize(&mut (), &mut mbr[spec::MbrPartitionTable::OFFSET..])?;
+
+    Ok(mbr)
+  }
+}
+
+/// GUID partition table written alongside a protective MBR, so that the image
+/// boots through UEFI when copied onto a USB drive.
+#[derive(Debug, Clone)]
+pub struct GptOptions {
+  /// GUID of the disk, from which the partition GUIDs are derived.
+  pub disk_guid: spec::Guid,
+  /// FAT image appended after the ISO 9660 data as an EFI system partition,
+  /// bootable through [BootImage::EfiSystemPartition].
+  pub efi_system_partition: Option<FileEntryContent>,
+}
+
+impl GptOptions {
+  /// Number of entries in each partition array.
+  const PARTITION_ENTRIES: u32 = 128;
+
+  /// Partitions start past the system area.
+  const FIRST_USABLE_LBA: u64 = 64;
+
+  pub fn new(disk_guid: spec::Guid) -> Self {
+    Self {
+      disk_guid,
+      efi_system_partition: None,
+    }
+  }
+
+  /// Size in bytes of a partition array.
+  fn partition_array_size() -> u64 {
+    Self::PARTITION_ENTRIES as u64 * spec::GptPartitionEntry::SIZE as u64
+  }
+
+  /// Size in bytes of the backup partition array and header at the end of the image.
+  fn backup_size() -> u64 {
+    Self::partition_array_size() + 512
+  }
+
+  /// Unique GUID of the `index`th partition.
+  fn partition_guid(&self, index: u8) -> spec::Guid {
+    let mut guid = self.disk_guid;
+    guid.0[15] ^= index + 1;
+    guid
+  }
+
+  /// Protective MBR partition table covering the whole image.
+  fn protective_mbr(image_size: u64) -> spec::MbrPartitionTable {
+    let mut table = spec::MbrPartitionTable::default();
+
+    table.entries[0] = spec::MbrPartitionEntry {
+      boot_indicator: 0,
+      start_chs: [0x00, 0x02, 0x00],
+      partition_type: 0xEE,
+      end_chs: [0xFF, 0xFF, 0xFF],
+      start_lba: 1,
+      sector_count: u32::try_from(image_size / 512 - 1).unwrap_or(u32::MAX),
+    };
+
+    table
+  }
+
+  /// Writes the primary GPT following the MBR and the backup GPT at the end
+  /// of an image of `image_size` bytes, whose ISO 9660 data ends at `iso_size`.
+  /// The primary partition array starts at the 512 byte sector `partition_entry_lba`.
+  fn write<W: std::io::Write + std::io::Seek>(
+    &self,
+    writer: &mut W,
+    image_size: u64,
+    iso_size: u64,
+    partition_entry_lba: u64,
+    efi_system_partition: Option<&FileEntry>,
+    sector_size: u32,
+  ) -> Result<()> {
+    let name = |name| spec::GptPartitionEntry::encode_name(name).unwrap();
+    let mut partitions = vec![spec::GptPartitionEntry {
+      partition_type_guid: spec::Guid::BASIC_DATA,
+      unique_partition_guid: self.partition_guid(0),
+      starting_lba: Self::FIRST_USABLE_LBA,
+      ending_lba: iso_size / 512 - 1,
+      attributes: 0,
+      partition_name: name("ISO9660"),
+    }];
+
+    if let Some(esp) = efi_system_partition {
+      let Some(lba) = esp.data_lba() else {
+        unreachable!("File LBA should have been allocated by now");
+      };
+      let starting_lba = lba as u64 * sector_size as u64 / 512;
+
+      partitions.push(spec::GptPartitionEntry {
+        partition_type_guid: spec::Guid::EFI_SYSTEM_PARTITION,
+        unique_partition_guid: self.partition_guid(1),
+        starting_lba,
+        ending_lba: starting_lba + esp.content.extent().div_ceil(512) - 1,
+        attributes: 0,
+        partition_name: name("EFI System Partition"),
+      });
+    }
+
+    let mut partition_array = vec![0u8; Self::partition_array_size() as usize];
+
+    for (partition, out) in partitions
+      .iter()
+      .zip(partition_array.chunks_exact_mut(spec::GptPartitionEntry::SIZE as usize))
+    {
+      partition.serialize(&mut (), out)?;
+    }
+
+    let array_sectors = Self::partition_array_size() / 512;
+    let backup_lba = image_size / 512 - 1;
+    let primary = spec::GptHeader {
+      header_crc32: 0,
+      my_lba: 1,
+      alternate_lba: backup_lba,
+      first_usable_lba: Self::FIRST_USABLE_LBA,
+      last_usable_lba: backup_lba - array_sectors - 1,
+      disk_guid: self.disk_guid,
+      partition_entry_lba,
+      number_of_partition_entries: Self::PARTITION_ENTRIES,
+      size_of_partition_entry: spec::GptPartitionEntry::SIZE,
+      partition_entry_array_crc32: crc32fast::hash(&partition_array),
+    };
+    let backup = spec::GptHeader {
+      my_lba: backup_lba,
+      alternate_lba: 1,
+      partition_entry_lba: backup_lba - array_sectors,
+      ..primary.clone()
+    };
+
+    for mut header in [primary, backup] {
+      let mut header_bytes = [0u8; spec::GptHeader::SIZE as usize];
+
+      // The header checksum is computed with its own field zeroed.
+      header.serialize(&mut (), &mut header_bytes)?;
+      header.header_crc32 = crc32fast::hash(&header_bytes);
+      header.serialize(&mut (), &mut header_bytes)?;
+
+      writer.seek(std::io::SeekFrom::Start(header.my_lba * 512))?;
+      writer.write_all(&header_bytes)?;
+      writer.seek(std::io::SeekFrom::Start(header.partition_entry_lba * 512))?;
+      writer.write_all(&partition_array)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Apple partition map written to the system area alongside the MBR and GPT,
+/// so that Intel Macs boot the image. The driver descriptor map takes up the
+/// first 8 bytes of the MBR boot code, so an isohybrid template must tolerate
+/// them, as the Mac compatible templates of syslinux do.
+#[derive(Debug, Default, Clone)]
+pub struct ApmOptions {
+  pub entries: Vec<ApmEntry>,
+}
+
+/// Partition of an Apple partition map covering a boot image.
+#[derive(Debug, Clone)]
+pub struct ApmEntry {
+  /// Name of the partition, at most 32 ASCII characters.
+  pub name: String,
+  /// Type of the partition, at most 32 ASCII characters.
+  pub partition_type: String,
+  pub image: BootImage,
+}
+
+impl ApmEntry {
+  /// Entry for the EFI system partition appended through [GptOptions].
+  pub fn efi_system_partition() -> Self {
+    Self {
+      name: "EFI".to_owned(),
+      partition_type: "Apple_HFS".to_owned(),
+      image: BootImage::EfiSystemPartition,
+    }
+  }
+
+  /// Entry for an HFS+ image within the filesystem.
+  pub fn hfs_plus(image: impl Into<PathBuf>) -> Self {
+    Self {
+      name: "HFSPLUS".to_owned(),
+      partition_type: "Apple_HFS".to_owned(),
+      image: BootImage::File(image.into()),
+    }
+  }
+}
+
+impl ApmOptions {
+  /// Valid, allocated, readable and writable.
+  const STATUS: u32 = 0x33;
+
+  /// Number of blocks taken up by the map, following the driver descriptor map.
+  fn block_count(&self) -> u32 {
+    // The map describes itself in its first entry.
+    1 + self.entries.len() as u32
+  }
+
+  fn encode(s: &str) -> Result<[u8; 32]> {
+    spec::ApmPartitionEntry::encode_str(s).ok_or_else(|| {
+      Error::InvalidBootConfiguration(format!(
+        "APM string {s:?} is not at most 32 ASCII characters"
+      ))
+    })
+  }
+
+  /// Writes the driver descriptor map into `block_zero` and the partition map
+  /// into the following blocks, blocks being logical sectors.
+  fn write<W: std::io::Write + std::io::Seek>(
+    &self,
+    writer: &mut W,
+    block_zero: &mut [u8; 512],
+    images: &BootImages,
+    image_size: u64,
+    sector_size: u32,
+  ) -> Result<()> {
+    let map_block_count = self.block_count();
+
+    spec::ApmDriverDescriptorMap {
+      block_size: sector_size as u16,
+      block_count: (image_size / sector_size as u64) as u32,
+    }
+    .serialize(&mut (), &mut block_zero[..8])?;
+
+    let mut partitions = vec![spec::ApmPartitionEntry {
+      map_block_count,
+      start_block: 1,
+      block_count: map_block_count,
+      name: Self::encode("Apple")?,
+      partition_type: Self::encode("Apple_partition_map")?,
+      data_start: 0,
+      data_count: map_block_count,
+      status: Self::STATUS,
+    }];
+
+    for entry in &self.entries {
+      let image = entry.image.resolve(images)?;
+      let Some(lba) = image.data_lba() else {
+        unreachable!("File LBA should have been allocated by now");
+      };
+      let block_count = image.content.extent().div_ceil(sector_size as u64) as u32;
+
+      partitions.push(spec::ApmPartitionEntry {
+        map_block_count,
+        start_block: lba,
+        block_count,
+        name: Self::encode(&entry.name)?,
+        partition_type: Self::encode(&entry.partition_type)?,
+        data_start: 0,
+        data_count: block_count,
+        status: Self::STATUS,
+      });
+    }
+
+    for (ix, partition) in partitions.iter().enumerate() {
+      let mut block = [0u8; 512];
+
+      partition.serialize(&mut (), &mut block)?;
+      writer.seek(std::io::SeekFrom::Start(
+        (ix as u64 + 1) * sector_size as u64,
+      ))?;
+      writer.write_all(&block)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct IsoWriterOptions {
+  /// Identification of the volume, recorded by every volume descriptor.
+  pub volume: VolumeMetadata,
+  /// Interchange level of the primary hierarchy, restricting its identifiers and files.
+  pub interchange_level: spec::InterchangeLevel,
+  /// Level of the Joliet hierarchy to write alongside the primary one, if any.
+  pub joliet: Option<spec::JolietLevel>,
+  /// Rock Ridge extensions to record within the primary hierarchy, if any.
+  pub rock_ridge: Option<RockRidgeOptions>,
+  /// Time the image is recorded at, stamped on the volume descriptors, every record and the
+  /// attributes that don't come from the host filesystem. The time of finalizing if unset.
+  ///
+  /// Defaults to `SOURCE_DATE_EPOCH`, so that builds of the same tree are reproducible.
+  pub recording_date: Option<chrono::DateTime<chrono::Utc>>,
+  pub sector_size: u32,
+}
 
 impl IsoWriterOptions {
   pub fn compatibility() -> Self {
     Self {
-      joliet: false,
+      volume: VolumeMetadata::default(),
+      interchange_level: spec::InterchangeLevel::default(),
+      joliet: None,
+      rock_ridge: None,
+      recording_date: source_date_epoch(),
       sector_size: 2048,
     }
   }
@@ -610,17 +3235,273 @@ impl IsoWriterOptions {
 impl Default for IsoWriterOptions {
   fn default() -> Self {
     Self {
-      joliet: true,
+      volume: VolumeMetadata::default(),
+      interchange_level: spec::InterchangeLevel::default(),
+      joliet: Some(spec::JolietLevel::Level3),
+      rock_ridge: Some(RockRidgeOptions::default()),
+      recording_date: source_date_epoch(),
       sector_size: 2048,
     }
   }
 }
 
+/// Time given by the `SOURCE_DATE_EPOCH` environment variable, in seconds since the Unix epoch.
+/// See <https://reproducible-builds.org/specs/source-date-epoch/>.
+fn source_date_epoch() -> Option<chrono::DateTime<chrono::Utc>> {
+  let epoch = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+
+  match epoch.trim().parse() {
+    Ok(seconds) => chrono::DateTime::from_timestamp(seconds, 0),
+    Err(_) => {
+      log::warn!("Ignoring invalid SOURCE_DATE_EPOCH: {epoch:?}");
+      None
+    }
+  }
+}
+
+/// Identification of a volume, e.g. the label readers mount it under.
+///
+/// The primary volume descriptor restricts the identifiers to upper case letters, digits and a
+/// few punctuation characters, while the Joliet one holds the same identifiers in UCS-2 and at
+/// most 16 characters of the volume identifier.
+#[derive(Debug, Clone)]
+pub struct VolumeMetadata {
+  /// System that can act upon the system area of the image.
+  pub system_identifier: String,
+  /// Label of the volume.
+  pub volume_identifier: String,
+  /// Label of the set of volumes the volume belongs to.
+  pub volume_set_identifier: String,
+  /// Publisher of the data on the volume.
+  pub publisher_identifier: String,
+  /// Whoever prepared the data on the volume.
+  pub data_preparer_identifier: String,
+  /// Application that recorded the data on the volume.
+  pub application_identifier: String,
+  /// Path of the file holding the copyright statement, which must lie within the root directory.
+  pub copyright_file: Option<PathBuf>,
+  /// Path of the file holding an abstract of the volume, which must lie within the root
+  /// directory.
+  pub abstract_file: Option<PathBuf>,
+  /// Path of the file holding bibliographic records, which must lie within the root directory.
+  pub bibliographic_file: Option<PathBuf>,
+}
+
+impl Default for VolumeMetadata {
+  fn default() -> Self {
+    Self {
+      system_identifier: "LINUX".to_string(),
+      volume_identifier: "ISOFS".to_string(),
+      volume_set_identifier: "ISOFS".to_string(),
+      publisher_identifier: "ISOFS".to_string(),
+      data_preparer_identifier: "ISOFS".to_string(),
+      application_identifier: "ISOFS".to_string(),
+      copyright_file: None,
+      abstract_file: None,
+      bibliographic_file: None,
+    }
+  }
+}
+
+/// Options of the Rock Ridge Interchange Protocol (RRIP 1.12) extensions, which record POSIX
+/// attributes through the System Use entries of the primary hierarchy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RockRidgeOptions {
+  /// Records `TF` timestamps in the 17 byte form, which has hundredths of seconds.
+  pub long_form_timestamps: bool,
+  /// Compresses file content with zisofs, announced by `ZF` entries. The Joliet hierarchy
+  /// shares the compressed content, as do readers without zisofs support.
+  pub zisofs: Option<ZisofsOptions>,
+}
+
+/// Options of the zisofs compression, which compresses the blocks of each file separately so
+/// that they can be read at random.
+#[derive(Debug, Clone, Copy)]
+pub struct ZisofsOptions {
+  /// Base 2 logarithm of the size of the blocks, from 15 to 17.
+  pub block_size_log2: u8,
+  /// zlib compression level, from 0 to 9.
+  pub level: u32,
+}
+
+impl Default for ZisofsOptions {
+  fn default() -> Self {
+    Self {
+      block_size_log2: 15,
+      level: 9,
+    }
+  }
+}
+
+impl ZisofsOptions {
+  fn block_size(&self) -> u32 {
+    1 << self.block_size_log2
+  }
+
+  fn validate(&self) -> Result<()> {
+    if !(15..=17).contains(&self.block_size_log2) {
+      return Err(Error::InvalidZisofsOptions(format!(
+        "block size of 2^{} bytes, expected from 2^15 to 2^17",
+        self.block_size_log2
+      )));
+    }
+
+    if self.level > 9 {
+      return Err(Error::InvalidZisofsOptions(format!(
+        "compression level {}, expected from 0 to 9",
+        self.level
+      )));
+    }
+
+    Ok(())
+  }
+
+  /// Compresses the next block read from `reader`. Blocks of zeros are left empty, which
+  /// readers fill with zeros.
+  fn compress_block(&self, reader: &mut impl std::io::Read) -> std::io::Result<Vec<u8>> {
+    use std::io::{Read, Write};
+
+    let mut block = vec![];
+
+    reader
+      .take(self.block_size() as u64)
+      .read_to_end(&mut block)?;
+
+    if block.iter().all(|&x| x == 0) {
+      return Ok(vec![]);
+    }
+
+    let mut encoder = flate2::write::ZlibEncoder::new(vec![], flate2::Compression::new(self.level));
+
+    encoder.write_all(&block)?;
+    encoder.finish()
+  }
+}
+
+impl RockRidgeOptions {
+  const IDENTIFIER: &str = "IEEE_P1282";
+  const DESCRIPTOR: &str =
+    "THE IEEE P1282 PROTOCOL PROVIDES SUPPORT FOR POSIX FILE SYSTEM SEMANTICS.";
+  const SOURCE: &str =
+    "PLEASE CONTACT THE IEEE STANDARDS DEPARTMENT, PISCATAWAY, NJ, USA FOR THE P1282 SPECIFICATION.";
+
+  /// Largest content of an `NM` entry, or of the components of an `SL` entry.
+  const MAX_CONTENT_LENGTH: usize = 255 - 5;
+
+  /// `NM` entries recording `name` as is, split across as many entries as needed.
+  fn alternate_name(name: &[u8]) -> Vec<spec::SystemUseEntry> {
+    let chunks = name.chunks(Self::MAX_CONTENT_LENGTH).collect::<Vec<_>>();
+
+    chunks
+      .iter()
+      .enumerate()
+      .map(|(ix, chunk)| spec::SystemUseEntry::AlternateName {
+        flags: match ix + 1 < chunks.len() {
+          true => spec::AlternateNameFlags::CONTINUE,
+          false => spec::AlternateNameFlags::empty(),
+        },
+        content: chunk.to_vec(),
+      })
+      .collect()
+  }
+
+  /// `SL` entries recording `target`, split across as many entries as needed. Targets with a
+  /// Windows path prefix have no Rock Ridge equivalent, nor do those whose components can't be
+  /// split across entries.
+  fn symbolic_link(target: &Path) -> Result<Vec<spec::SystemUseEntry>> {
+    // Readers disagree on whether the last component of an entry flagged as continued ends a
+    // path component, Linux adds a separator while libarchive doesn't. Entries are therefore
+    // filled up and only ever broken within a component, which is flagged as continued. Components
+    // without content, such as ROOT or PARENT, can't be broken and never end an entry.
+    let invalid = || Error::InvalidSymbolicLinkTarget(target.to_path_buf());
+    let mut groups: Vec<Vec<spec::SymbolicLinkComponent>> = vec![vec![]];
+    let mut room = Self::MAX_CONTENT_LENGTH;
+
+    for component in target.components() {
+      let (flags, mut content) = match component {
+        std::path::Component::RootDir => (spec::SymbolicLinkComponentFlags::ROOT, &[][..]),
+        std::path::Component::CurDir => (spec::SymbolicLinkComponentFlags::CURRENT, &[][..]),
+        std::path::Component::ParentDir => (spec::SymbolicLinkComponentFlags::PARENT, &[][..]),
+        std::path::Component::Normal(name) => (
+          spec::SymbolicLinkComponentFlags::empty(),
+          name.as_encoded_bytes(),
+        ),
+        std::path::Component::Prefix(_) => return Err(invalid()),
+      };
+
+      while 2 + content.len() > room {
+        let group = groups.last_mut().unwrap();
+
+        let next = match content.is_empty() || room < 3 {
+          // Nothing of this component fits, break the entry within the last component that can
+          // spare a byte, carrying over its last byte and all components following it.
+          true => {
+            let ix = group
+              .iter()
+              .rposition(|x| x.content.len() > 1)
+              .ok_or_else(invalid)?;
+            let mut next = group.split_off(ix + 1);
+            let previous = &mut group[ix];
+
+            next.insert(
+              0,
+              spec::SymbolicLinkComponent {
+                flags: previous.flags,
+                content: previous.content.split_off(previous.content.len() - 1),
+              },
+            );
+            previous.flags |= spec::SymbolicLinkComponentFlags::CONTINUE;
+            next
+          }
+          false => {
+            let (head, tail) = content.split_at(room - 2);
+
+            group.push(spec::SymbolicLinkComponent {
+              flags: flags | spec::SymbolicLinkComponentFlags::CONTINUE,
+              content: head.to_vec(),
+            });
+            content = tail;
+            vec![]
+          }
+        };
+
+        room = Self::MAX_CONTENT_LENGTH - next.iter().map(|x| 2 + x.content.len()).sum::<usize>();
+        groups.push(next);
+      }
+
+      room -= 2 + content.len();
+      groups
+        .last_mut()
+        .unwrap()
+        .push(spec::SymbolicLinkComponent {
+          flags,
+          content: content.to_vec(),
+        });
+    }
+
+    let count = groups.len();
+
+    Ok(
+      groups
+        .into_iter()
+        .enumerate()
+        .map(|(ix, components)| spec::SystemUseEntry::SymbolicLink {
+          continues: ix + 1 < count,
+          components,
+        })
+        .collect(),
+    )
+  }
+}
+
 #[derive(Debug)]
 pub struct IsoWriter {
   options: IsoWriterOptions,
   filesystem: Filesystem,
-  boot_record: Option<BootRecord>,
+  boot: Option<BootOptions>,
+  isohybrid: Option<IsohybridOptions>,
+  gpt: Option<GptOptions>,
+  apm: Option<ApmOptions>,
 }
 
 impl IsoWriter {
@@ -628,7 +3509,10 @@ impl IsoWriter {
     Self {
       options,
       filesystem: Default::default(),
-      boot_record: Default::default(),
+      boot: Default::default(),
+      isohybrid: Default::default(),
+      gpt: Default::default(),
+      apm: Default::default(),
     }
   }
 
@@ -647,47 +3531,254 @@ impl IsoWriter {
     self.filesystem.merge(filesystem, on_file_conflict)
   }
 
+  /// Makes the image bootable through an El Torito boot catalog.
+  /// Boot images are looked up within the filesystem when finalizing.
+  pub fn set_boot(&mut self, boot: BootOptions) {
+    self.boot = Some(boot);
+  }
+
+  /// Writes an isohybrid MBR into the system area. Requires a boot
+  /// configuration, whose default entry the MBR boot code loads.
+  pub fn set_isohybrid(&mut self, isohybrid: IsohybridOptions) {
+    self.isohybrid = Some(isohybrid);
+  }
+
+  /// Writes a protective MBR and GUID partition tables, optionally appending
+  /// an EFI system partition. Combined with an isohybrid MBR, its boot code is
+  /// kept but the partition table is replaced by the protective one.
+  pub fn set_gpt(&mut self, gpt: GptOptions) {
+    self.gpt = Some(gpt);
+  }
+
+  /// Writes an Apple partition map into the system area. Combined with a GPT,
+  /// the primary GPT partition array moves past the map.
+  pub fn set_apm(&mut self, apm: ApmOptions) {
+    self.apm = Some(apm);
+  }
+
   /// Builds the ISO image according to the current configuration.
   pub fn finalize<W: std::io::Write + std::io::Seek>(mut self, mut writer: W) -> Result<()> {
+    let recording_date = self.options.recording_date.unwrap_or_else(chrono::Utc::now);
     let context = Context {
-      compatibility_mode: if self.options.joliet {
-        spec::CompatibilityMode::Joliet(spec::JolietLevel::Level3)
-      } else {
-        spec::CompatibilityMode::Standard
-      },
+      compatibility_mode: spec::CompatibilityMode::Standard(self.options.interchange_level),
+      rock_ridge: self.options.rock_ridge,
+      recording_date,
+      sector_size: self.options.sector_size,
+    };
+    let joliet_context = self.options.joliet.map(|level| Context {
+      compatibility_mode: spec::CompatibilityMode::Joliet(level),
+      rock_ridge: None,
+      recording_date,
+      sector_size: self.options.sector_size,
+    });
+
+    if let Some(isohybrid) = &self.isohybrid {
+      if self.boot.is_none() {
+        return Err(Error::InvalidBootConfiguration(
+          "an isohybrid MBR requires a boot catalog".to_string(),
+        ));
+      }
+
+      isohybrid.validate()?;
+    }
+
+    // The primary GPT partition array follows the APM, and both must fit in the system area.
+    let gpt_partition_entry_lba = match &self.apm {
+      Some(apm) => (1 + apm.block_count() as u64) * self.options.sector_size as u64 / 512,
+      None => 2,
     };
 
-    // 1. Allocate LBAs for the main volume's filesystem.
+    if self.gpt.is_some()
+      && gpt_partition_entry_lba + GptOptions::partition_array_size() / 512
+        > GptOptions::FIRST_USABLE_LBA
+    {
+      return Err(Error::InvalidBootConfiguration(
+        "the Apple partition map has too many entries to fit a GPT".to_string(),
+      ));
+    }
+
+    // 0. Compress file content, except for the images firmware loads as is.
+
+    if let Some(zisofs) = self.options.rock_ridge.and_then(|x| x.zisofs) {
+      zisofs.validate()?;
+
+      let images = self
+        .boot
+        .iter()
+        .flat_map(|boot| boot.entries().map(|x| &x.image))
+        .chain(
+          self
+            .apm
+            .iter()
+            .flat_map(|apm| apm.entries.iter().map(|x| &x.image)),
+        )
+        .filter_map(|image| match image {
+          BootImage::File(path) => self.filesystem.file(path),
+          BootImage::EfiSystemPartition => None,
+        })
+        .map(|x| x.content.clone())
+        .collect::<Vec<_>>();
+
+      self.filesystem.compress_files(&zisofs, &images)?;
+    }
+
+    // Files larger than an extent span several, which only some interchange levels permit.
+
+    if !self.options.interchange_level.permits_multi_extent() {
+      if let Some(path) = self.filesystem.multi_extent_file() {
+        return Err(Error::FileTooLarge(path));
+      }
+    }
+
+    // 1. Allocate LBAs for the directory hierarchies, followed by the file data they share.
 
     let mut lba_allocator = LbaAllocator::new(
       self.options.sector_size,
       // Descriptors start at LBA 16. System area is LBA 0..=15.
       16 /* System Area */
         + /* Primary Volume Descriptor */ 1
-        + /* Supplementary Volume Descriptor */ self.options.joliet as u32
-        + self.boot_record.is_some() as u32
+        + /* Boot Record Volume Descriptor */ self.boot.is_some() as u32
+        + /* Supplementary Volume Descriptor */ self.options.joliet.is_some() as u32
+        + /* Enhanced Volume Descriptor */ (self.options.interchange_level == spec::InterchangeLevel::Iso9660_1999) as u32
         + /* Volume set terminator */ 1,
     );
 
-    self.filesystem.allocate_lbas(&mut lba_allocator, &context);
+    let boot_catalog_lba = self
+      .boot
+      .as_ref()
+      .map(|boot| lba_allocator.allocate(boot.catalog_size()));
+
+    if self.options.rock_ridge.is_some() {
+      self.filesystem.check_symbolic_links()?;
+    }
+
+    // Only hierarchies recording Rock Ridge act upon the relocations, and ISO 9660:1999 has no
+    // depth limit to relocate past. Without Rock Ridge, nothing would record where relocated
+    // directories belong.
+    if !self.options.interchange_level.permits_deep_hierarchies() {
+      self.filesystem.root.relocate_directories();
+
+      if let Some(path) = self
+        .filesystem
+        .relocated_directory()
+        .filter(|_| self.options.rock_ridge.is_none())
+      {
+        return Err(Error::HierarchyTooDeep(path));
+      }
+    }
+
+    // Serials follow the relocations, as `RR_MOVED` takes one of its own.
+    if self.options.rock_ridge.is_some() {
+      self.filesystem.assign_serials();
+    }
+
+    // Names invalid within a hierarchy are mangled into identifiers that are.
+    self.filesystem.map_identifiers(&context)?;
+
+    if let Some(joliet_context) = &joliet_context {
+      self.filesystem.map_identifiers(joliet_context)?;
+    }
+
+    // Directory extents depend on their identifiers, and are measured once they are settled.
+    self.filesystem.measure_directories(&context);
+
+    if let Some(joliet_context) = &joliet_context {
+      self.filesystem.measure_directories(joliet_context);
+    }
+
+    self
+      .filesystem
+      .allocate_directory_lbas(&mut lba_allocator, &context)?;
+
+    if let Some(joliet_context) = &joliet_context {
+      self
+        .filesystem
+        .allocate_directory_lbas(&mut lba_allocator, joliet_context)?;
+    }
+
+    self.filesystem.allocate_file_lbas(&mut lba_allocator);
 
     // 2. Allocate LBAs for the path table(s).
 
-    let path_table = PathTable::build_from_filesystem(&self.filesystem, &context)
-      .expect("Failed to build path table");
+    let path_table = PathTable::build_from_filesystem(&self.filesystem, &context)?;
 
     let path_table_type_l_lba = path_table.allocate_type_l_lba(&mut lba_allocator);
     let path_table_type_m_lba = path_table.allocate_type_m_lba(&mut lba_allocator);
 
+    let joliet_path_table = joliet_context
+      .as_ref()
+      .map(|joliet_context| -> Result<_> {
+        let path_table = PathTable::build_from_filesystem(&self.filesystem, joliet_context)?;
+
+        let type_l_lba = path_table.allocate_type_l_lba(&mut lba_allocator);
+        let type_m_lba = path_table.allocate_type_m_lba(&mut lba_allocator);
+
+        Ok((path_table, type_l_lba, type_m_lba))
+      })
+      .transpose()?;
+
+    // 2.1. Allocate LBAs past the ISO 9660 data for the appended partitions,
+    // the padding and the backup GPT (if requested).
+
+    let iso_size = lba_allocator.next_lba as u64 * self.options.sector_size as u64;
+
+    let efi_system_partition = self
+      .gpt
+      .as_ref()
+      .and_then(|gpt| gpt.efi_system_partition.clone())
+      .map(|content| {
+        let mut esp = FileEntry {
+          extent_lbas: vec![],
+          name: ArrayStringU255::from("EFI system partition"),
+          identifier: None,
+          joliet_identifier: None,
+          content,
+          serial: None,
+          zisofs: None,
+        };
+
+        esp.allocate_lbas(&mut lba_allocator);
+        esp
+      });
+
+    let backup_gpt_size = match self.gpt {
+      Some(_) => GptOptions::backup_size().next_multiple_of(self.options.sector_size as u64),
+      None => 0,
+    };
+
+    if let Some(isohybrid) = &self.isohybrid {
+      // Pad the image to a whole number of cylinders.
+      let end = lba_allocator.next_lba as u64 * self.options.sector_size as u64 + backup_gpt_size;
+      let padding = end.next_multiple_of(isohybrid.alignment(self.options.sector_size)) - end;
+
+      lba_allocator.allocate(padding as u32);
+    }
+
+    // The backup GPT ends the image, its header taking up the last 512 bytes.
+    lba_allocator.allocate(backup_gpt_size as u32);
+
+    let boot_images = BootImages {
+      filesystem: &self.filesystem,
+      efi_system_partition: efi_system_partition.as_ref(),
+    };
+
     // 3. Write out the various volume descriptors.
 
+    let volume = &self.options.volume;
+    // Volume descriptors refer to files through their identifiers within the same hierarchy.
+    let file_identifier = |path: &Option<PathBuf>, context| -> Result<String> {
+      path
+        .as_ref()
+        .map(|path| self.filesystem.root_file_identifier(path, context))
+        .transpose()
+        .map(|x| x.map(ToString::to_string).unwrap_or_default())
+    };
+
     let primary_volume_descriptor = spec::PrimaryVolumeDescriptor {
       standard_identifier: spec::StandardIdentifier::Cd001,
       version: spec::VolumeDescriptorVersion::Standard,
-      // TODO(meowesque): Allow configuration
-      system_identifier: spec::Identifier::system_identifier("LINUX").unwrap(),
-      // TODO(meowesque): Allow configuration
-      volume_identifier: spec::Identifier::volume_identifier("ISOFS").unwrap(),
+      system_identifier: spec::Identifier::system_identifier(&volume.system_identifier)?,
+      volume_identifier: spec::Identifier::volume_identifier(&volume.volume_identifier)?,
       // TODO(meowesque): Calculate actual size
       volume_space_size: lba_allocator.next_lba,
       volume_set_size: 0,
@@ -699,131 +3790,262 @@ impl IsoWriter {
       type_m_path_table_location: path_table_type_m_lba,
       optional_type_m_path_table_location: path_table_type_m_lba,
       root_directory_record: self.filesystem.root.root_directory_record(&context),
-      volume_set_identifier: spec::Identifier::volume_set_identifier("ISOFS").unwrap(),
-      publisher_identifier: spec::Identifier::publisher_identifier("ISOFS").unwrap(),
-      data_preparer_identifier: spec::Identifier::data_preparer_identifier("ISOFS").unwrap(),
-      application_identifier: spec::Identifier::application_identifier("ISOFS").unwrap(),
-      copyright_file_identifier: spec::Identifier::copyright_file_identifier("ISOFS").unwrap(),
-      abstract_file_identifier: spec::Identifier::abstract_file_identifier("ISOFS").unwrap(),
-      bibliographic_file_identifier: spec::Identifier::bibliographic_file_identifier("ISOFS")
-        .unwrap(),
-      creation_date: chrono::Utc::now().into(),
-      modification_date: chrono::Utc::now().into(),
-      expiration_date: chrono::Utc::now().into(),
-      effective_date: chrono::Utc::now().into(),
+      volume_set_identifier: spec::Identifier::volume_set_identifier(
+        &volume.volume_set_identifier,
+      )?,
+      publisher_identifier: spec::Identifier::publisher_identifier(&volume.publisher_identifier)?,
+      data_preparer_identifier: spec::Identifier::data_preparer_identifier(
+        &volume.data_preparer_identifier,
+      )?,
+      application_identifier: spec::Identifier::application_identifier(
+        &volume.application_identifier,
+      )?,
+      copyright_file_identifier: spec::Identifier::copyright_file_identifier(
+        file_identifier(&volume.copyright_file, &context)?,
+        self.options.interchange_level,
+      )?,
+      abstract_file_identifier: spec::Identifier::abstract_file_identifier(
+        file_identifier(&volume.abstract_file, &context)?,
+        self.options.interchange_level,
+      )?,
+      bibliographic_file_identifier: spec::Identifier::bibliographic_file_identifier(
+        file_identifier(&volume.bibliographic_file, &context)?,
+        self.options.interchange_level,
+      )?,
+      creation_date: recording_date.into(),
+      modification_date: recording_date.into(),
+      expiration_date: recording_date.into(),
+      effective_date: recording_date.into(),
       file_structure_version: spec::FileStructureVersion::Standard,
       application_use: [0; 512],
     };
 
-    /*
-       let supplementary_volume_descriptor = spec::SupplementaryVolumeDescriptor {
-         standard_identifier: spec::StandardIdentifier::Cd001,
-         version: spec::VolumeDescriptorVersion::Standard,
-         volume_flags: spec::VolumeFlags::empty(),
-         system_identifier: spec::Identifier::system_identifier("LINUX").unwrap(),
-         volume_identifier: spec::Identifier::volume_identifier("ISOFS").unwrap(),
-         volume_space_size: 0,
-         escape_sequences: EscapeSequences::joliet_level_3(),
-         volume_set_size: 0,
-         volume_sequence_number: 0,
-         logical_block_size: self.options.sector_size as u16,
-         path_table_size: path_table.joliet_size,
-         type_l_path_table_location: todo!(),
-         optional_type_l_path_table_location: todo!(),
-         type_m_path_table_location: todo!(),
-         optional_type_m_path_table_location: todo!(),
-         root_directory_record: todo!(),
-         volume_set_identifier: todo!(),
-         publisher_identifier: todo!(),
-         data_preparer_identifier: todo!(),
-         application_identifier: todo!(),
-         copyright_file_identifier: todo!(),
-         abstract_file_identifier: todo!(),
-         bibliographic_file_identifier: todo!(),
-         creation_date: todo!(),
-         modification_date: todo!(),
-         expiration_date: todo!(),
-         effective_date: todo!(),
-         file_structure_version: todo!(),
-         application_use: todo!(),
-       };
-    */
-
-    // TODO(meowesque): Add supplementary volume descriptor if Joliet is enabled.
+    let supplementary_volume_descriptor = self
+      .options
+      .joliet
+      .zip(joliet_context.as_ref())
+      .zip(joliet_path_table.as_ref())
+      .map(
+        |((level, joliet_context), (path_table, type_l_lba, type_m_lba))| -> Result<_> {
+          Ok(spec::SupplementaryVolumeDescriptor {
+            standard_identifier: spec::StandardIdentifier::Cd001,
+            version: spec::VolumeDescriptorVersion::Standard,
+            volume_flags: spec::VolumeFlags::empty(),
+            system_identifier: spec::Identifier::joliet_system_identifier(
+              &volume.system_identifier,
+            )?,
+            volume_identifier: spec::Identifier::joliet_volume_identifier(
+              &volume.volume_identifier,
+            )?,
+            volume_space_size: lba_allocator.next_lba,
+            escape_sequences: spec::EscapeSequences::joliet(level),
+            volume_set_size: 0,
+            volume_sequence_number: 0,
+            logical_block_size: self.options.sector_size as u16,
+            path_table_size: path_table.size,
+            type_l_path_table_location: *type_l_lba,
+            optional_type_l_path_table_location: *type_l_lba,
+            type_m_path_table_location: *type_m_lba,
+            optional_type_m_path_table_location: *type_m_lba,
+            root_directory_record: self.filesystem.root.root_directory_record(joliet_context),
+            volume_set_identifier: spec::Identifier::joliet_volume_set_identifier(
+              &volume.volume_set_identifier,
+            )?,
+            publisher_identifier: spec::Identifier::joliet_publisher_identifier(
+              &volume.publisher_identifier,
+            )?,
+            data_preparer_identifier: spec::Identifier::joliet_data_preparer_identifier(
+              &volume.data_preparer_identifier,
+            )?,
+            application_identifier: spec::Identifier::joliet_application_identifier(
+              &volume.application_identifier,
+            )?,
+            copyright_file_identifier: spec::Identifier::joliet_copyright_file_identifier(
+              file_identifier(&volume.copyright_file, joliet_context)?,
+            )?,
+            abstract_file_identifier: spec::Identifier::joliet_abstract_file_identifier(
+              file_identifier(&volume.abstract_file, joliet_context)?,
+            )?,
+            bibliographic_file_identifier: spec::Identifier::joliet_bibliographic_file_identifier(
+              file_identifier(&volume.bibliographic_file, joliet_context)?,
+            )?,
+            creation_date: recording_date.into(),
+            modification_date: recording_date.into(),
+            expiration_date: recording_date.into(),
+            effective_date: recording_date.into(),
+            file_structure_version: spec::FileStructureVersion::Standard,
+            application_use: [0; 512],
+          })
+        },
+      )
+      .transpose()?;
+
+    // ISO 9660:1999 is announced by an enhanced volume descriptor, which describes the primary
+    // hierarchy once more.
+    let enhanced_volume_descriptor = (self.options.interchange_level
+      == spec::InterchangeLevel::Iso9660_1999)
+      .then(|| spec::SupplementaryVolumeDescriptor {
+        standard_identifier: spec::StandardIdentifier::Cd001,
+        version: spec::VolumeDescriptorVersion::Other(2),
+        volume_flags: spec::VolumeFlags::empty(),
+        system_identifier: primary_volume_descriptor.system_identifier.clone(),
+        volume_identifier: primary_volume_descriptor.volume_identifier.clone(),
+        volume_space_size: primary_volume_descriptor.volume_space_size,
+        escape_sequences: spec::EscapeSequences([0; 32]),
+        volume_set_size: primary_volume_descriptor.volume_set_size,
+        volume_sequence_number: primary_volume_descriptor.volume_sequence_number,
+        logical_block_size: primary_volume_descriptor.logical_block_size,
+        path_table_size: primary_volume_descriptor.path_table_size,
+        type_l_path_table_location: primary_volume_descriptor.type_l_path_table_location,
+        optional_type_l_path_table_location: primary_volume_descriptor
+          .optional_type_l_path_table_location,
+        type_m_path_table_location: primary_volume_descriptor.type_m_path_table_location,
+        optional_type_m_path_table_location: primary_volume_descriptor
+          .optional_type_m_path_table_location,
+        root_directory_record: primary_volume_descriptor.root_directory_record.clone(),
+        volume_set_identifier: primary_volume_descriptor.volume_set_identifier.clone(),
+        publisher_identifier: primary_volume_descriptor.publisher_identifier.clone(),
+        data_preparer_identifier: primary_volume_descriptor.data_preparer_identifier.clone(),
+        application_identifier: primary_volume_descriptor.application_identifier.clone(),
+        copyright_file_identifier: primary_volume_descriptor.copyright_file_identifier.clone(),
+        abstract_file_identifier: primary_volume_descriptor.abstract_file_identifier.clone(),
+        bibliographic_file_identifier: primary_volume_descriptor
+          .bibliographic_file_identifier
+          .clone(),
+        creation_date: primary_volume_descriptor.creation_date.clone(),
+        modification_date: primary_volume_descriptor.modification_date.clone(),
+        expiration_date: primary_volume_descriptor.expiration_date.clone(),
+        effective_date: primary_volume_descriptor.effective_date.clone(),
+        file_structure_version: spec::FileStructureVersion::Other(2),
+        application_use: [0; 512],
+      });
+
+    let boot_catalog = self
+      .boot
+      .as_ref()
+      .map(|boot| boot.catalog(&boot_images, self.options.sector_size))
+      .transpose()?;
 
     {
       let mut descriptor_bytes = [0u8; 2048];
+      // Descriptors start at LBA 16. System area is LBA 0..=15.
+      let mut descriptor_lba = 16;
+      let mut write_descriptor = |descriptor_bytes: &[u8]| -> Result<()> {
+        writer.seek(std::io::SeekFrom::Start(
+          descriptor_lba * self.options.sector_size as u64,
+        ))?;
+        writer.write_all(descriptor_bytes)?;
+        descriptor_lba += 1;
+        Ok(())
+      };
 
       // 3.1. Write Primary Volume Descriptor
 
       primary_volume_descriptor.serialize(&mut (), &mut descriptor_bytes)?;
-      writer.seek(std::io::SeekFrom::Start(
-        16 * self.options.sector_size as u64,
-      ))?;
-      writer.write_all(&descriptor_bytes)?;
+      write_descriptor(&descriptor_bytes)?;
+
+      // 3.2. Write Boot Record (if present), which El Torito requires at LBA 17
+
+      if let Some(boot_catalog_lba) = boot_catalog_lba {
+        spec::ElToritoBootRecordVolumeDescriptor {
+          standard_identifier: spec::StandardIdentifier::Cd001,
+          version: spec::VolumeDescriptorVersion::Standard,
+          boot_catalog_pointer: boot_catalog_lba,
+        }
+        .serialize(&mut (), &mut descriptor_bytes)?;
+        write_descriptor(&descriptor_bytes)?;
+      }
 
-      // 3.2. Write Supplementary Volume Descriptor (if Joliet is enabled)
+      // 3.3. Write Supplementary Volume Descriptor (if Joliet is enabled)
 
-      if self.options.joliet {
-        // TODO(meowesque)
+      if let Some(supplementary_volume_descriptor) = &supplementary_volume_descriptor {
+        supplementary_volume_descriptor.serialize(&mut (), &mut descriptor_bytes)?;
+        write_descriptor(&descriptor_bytes)?;
       }
 
-      // 3.3. Write Boot Record (if present)
+      // 3.4. Write Enhanced Volume Descriptor (if ISO 9660:1999 is enabled)
 
-      if let Some(_boot_record) = &self.boot_record {
-        // TODO(meowesque)
+      if let Some(enhanced_volume_descriptor) = &enhanced_volume_descriptor {
+        enhanced_volume_descriptor.serialize(&mut (), &mut descriptor_bytes)?;
+        write_descriptor(&descriptor_bytes)?;
       }
 
-      // 3.4. Write Volume Descriptor Set Terminator
+      // 3.5. Write Volume Descriptor Set Terminator
 
       spec::VolumeDescriptorSetTerminator.serialize(&mut (), &mut descriptor_bytes)?;
+      write_descriptor(&descriptor_bytes)?;
+    }
+
+    // 3.6. Write Boot Catalog (if present)
+
+    if let Some((lba, catalog)) = boot_catalog_lba.zip(boot_catalog) {
       writer.seek(std::io::SeekFrom::Start(
-        (16 + 1 + self.options.joliet as u64 + self.boot_record.is_some() as u64)
-          * self.options.sector_size as u64,
+        lba as u64 * self.options.sector_size as u64,
       ))?;
-      writer.write_all(&descriptor_bytes)?;
+      writer.write_all(&catalog)?;
     }
 
     // 4. Write Path Table(s)
 
-    // 4.1 Write Type L Path Table
-
-    {
+    fn write_path_table<W: std::io::Write + std::io::Seek>(
+      writer: &mut W,
+      path_table: &PathTable,
+      lba: u32,
+      mut endianness: serialize::Endianness,
+      options: &IsoWriterOptions,
+    ) -> Result<()> {
       let mut record_bytes = vec![];
-      let mut sector_writer = SectorWriter::new(
-        &mut writer,
-        path_table_type_l_lba as u64,
-        self.options.sector_size as u64,
-      );
+      let mut sector_writer = SectorWriter::new(writer, lba as u64, options.sector_size as u64);
 
       for record in &path_table.records {
-        log::debug!("Writing (type L) path table record: {:?}", record);
+        log::debug!("Writing ({:?}) path table record: {:?}", endianness, record);
 
         record_bytes.resize(record.extent(), 0);
-        record.serialize(&mut serialize::Endianness::Little, &mut record_bytes)?;
+        record.serialize(&mut endianness, &mut record_bytes)?;
         sector_writer.write_aligned(&record_bytes)?;
         record_bytes.clear();
       }
+
+      Ok(())
     }
 
+    // 4.1 Write Type L Path Table
+
+    write_path_table(
+      &mut writer,
+      &path_table,
+      path_table_type_l_lba,
+      serialize::Endianness::Little,
+      &self.options,
+    )?;
+
     // 4.2 Write Type M Path Table
 
-    {
-      let mut record_bytes = vec![];
-      let mut sector_writer = SectorWriter::new(
-        &mut writer,
-        path_table_type_m_lba as u64,
-        self.options.sector_size as u64,
-      );
+    write_path_table(
+      &mut writer,
+      &path_table,
+      path_table_type_m_lba,
+      serialize::Endianness::Big,
+      &self.options,
+    )?;
 
-      for record in &path_table.records {
-        log::debug!("Writing (type M) path table record: {:?}", record);
+    // 4.3 Write Joliet Path Tables (if Joliet is enabled)
 
-        record_bytes.resize(record.extent(), 0);
-        record.serialize(&mut serialize::Endianness::Big, &mut record_bytes)?;
-        sector_writer.write_aligned(&record_bytes)?;
-        record_bytes.clear();
-      }
+    if let Some((path_table, type_l_lba, type_m_lba)) = &joliet_path_table {
+      write_path_table(
+        &mut writer,
+        path_table,
+        *type_l_lba,
+        serialize::Endianness::Little,
+        &self.options,
+      )?;
+      write_path_table(
+        &mut writer,
+        path_table,
+        *type_m_lba,
+        serialize::Endianness::Big,
+        &self.options,
+      )?;
     }
 
     // 5. Write Directory Records and File Data
@@ -833,7 +4055,7 @@ impl IsoWriter {
       file: &FileEntry,
       options: &IsoWriterOptions,
     ) -> Result<()> {
-      let Some(lba) = file.data_lba else {
+      let Some(lba) = file.data_lba() else {
         unreachable!("File LBA should have been allocated by now");
       };
 
@@ -843,213 +4065,308 @@ impl IsoWriter {
         lba as u64 * options.sector_size as u64,
       ))?;
 
-      match file.content.0.as_ref() {
-        FileEntryContentInner::File { handle, .. } => {
-          std::io::copy(&mut std::io::BufReader::new(handle), writer)?;
+      match &file.zisofs {
+        Some(zisofs) => zisofs.write(&file.content, writer)?,
+        None => {
+          std::io::copy(&mut file.content.reader()?, writer)?;
         }
-        FileEntryContentInner::InMemory(data) => writer.write_all(data)?,
       }
 
       Ok(())
     }
 
-    fn write_directory_entry<W: std::io::Write + std::io::Seek>(
+    /// Writes the records of the directory at `lba`, given in the order they are written, followed
+    /// by the continuation areas of their System Use entries.
+    fn write_directory_records<W: std::io::Write + std::io::Seek>(
       writer: &mut W,
-      parent_dir_extent_location: u32,
-      parent_dir_data_length: u32,
-      dir: &DirectoryEntry,
+      lba: u32,
+      records: Vec<(spec::DirectoryRecord, Vec<spec::SystemUseEntry>)>,
       options: &IsoWriterOptions,
-      context: &Context,
     ) -> Result<()> {
-      let Some(lba) = dir.data_lba else {
-        unreachable!("Directory LBA should have been allocated by now");
-      };
+      let (continuation_areas, system_use) = layout_system_use(lba, &records, options.sector_size)?;
       let mut sector_writer =
         SectorWriter::new(&mut *writer, lba as u64, options.sector_size as u64);
-      let dir_record = dir.directory_record(context);
       let mut buf = vec![];
 
-      log::debug!("Writing directory record: {:?}", dir_record);
+      for ((mut record, _), system_use) in records.into_iter().zip(system_use) {
+        record.system_use = system_use;
 
-      {
-        let dot_entry = spec::DirectoryRecord {
-          extended_attribute_length: 0,
-          extent_location: lba,
-          data_length: dir_record.data_length,
-          recording_date: chrono::Utc::now().into(),
-          file_flags: spec::FileFlags::DIRECTORY,
-          file_unit_size: 0,
-          interleave_gap_size: 0,
-          volume_sequence_number: 1,
-          file_identifier_length: 1,
-          file_identifier: spec::Identifier::current_directory(),
-        };
+        log::debug!("Writing directory record: {:?}", record);
 
-        buf.resize(dot_entry.extent(), 0);
-        dot_entry.serialize(&mut (), &mut buf)?;
+        // Records that don't fit within the current sector start the next one.
+        buf.resize(record.extent(), 0);
+        record.serialize(&mut (), &mut buf)?;
         sector_writer.write_aligned(&buf)?;
         buf.clear();
       }
 
-      {
-        let dotdot_entry = spec::DirectoryRecord {
-          extended_attribute_length: 0,
-          extent_location: parent_dir_extent_location,
-          data_length: parent_dir_data_length,
-          recording_date: chrono::Utc::now().into(),
-          file_flags: spec::FileFlags::DIRECTORY,
-          file_unit_size: 0,
-          interleave_gap_size: 0,
-          volume_sequence_number: 1,
-          file_identifier_length: 1,
-          file_identifier: spec::Identifier::parent_directory(),
-        };
+      continuation_areas.write(&mut *writer)
+    }
 
-        buf.resize(dotdot_entry.extent(), 0);
-        dotdot_entry.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
-      }
+    /// Writes `dir`, given its `..` record along with the System Use entries of the latter.
+    fn write_directory_entry<W: std::io::Write + std::io::Seek>(
+      writer: &mut W,
+      dotdot: (spec::DirectoryRecord, Vec<spec::SystemUseEntry>),
+      dir: &DirectoryEntry,
+      relocation: Option<&RelocationDirectory>,
+      options: &IsoWriterOptions,
+      context: &Context,
+    ) -> Result<()> {
+      let Some(lba) = dir.data_lba(context) else {
+        unreachable!("Directory LBA should have been allocated by now");
+      };
+      let data_length = dir.data_length(context);
+
+      log::debug!("Writing directory: {:?}", dir.name);
+
+      write_directory_records(
+        &mut *writer,
+        lba,
+        dir.records(
+          dot_record(
+            spec::Identifier::current_directory(),
+            lba,
+            data_length,
+            vec![],
+            context,
+          ),
+          dotdot,
+          context,
+        ),
+        options,
+      )?;
 
       for subdir in dir.dirs.values() {
-        let subdir_record = subdir.directory_record(context);
+        // Relocated directories are recorded within `RR_MOVED`, pointing back to this one.
+        let dotdot = match relocation.filter(|_| subdir.is_relocated(context)) {
+          Some(relocation) => relocation.dotdot(context),
+          None => (
+            dot_record(
+              spec::Identifier::parent_directory(),
+              lba,
+              data_length,
+              vec![],
+              context,
+            ),
+            dir.attribute_system_use(context),
+          ),
+        };
+        let dotdot = (dotdot.0, subdir.parent_system_use(dotdot.1, lba, context));
 
-        log::debug!("Writing directory record: {:?}", subdir_record);
+        write_directory_entry(&mut *writer, dotdot, subdir, relocation, options, context)?;
+      }
 
-        buf.resize(subdir_record.extent(), 0);
-        subdir_record.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
+      Ok(())
+    }
+
+    fn write_directory_files<W: std::io::Write + std::io::Seek>(
+      writer: &mut W,
+      dir: &DirectoryEntry,
+      options: &IsoWriterOptions,
+    ) -> Result<()> {
+      for subdir in dir.dirs.values() {
+        write_directory_files(&mut *writer, subdir, options)?;
       }
 
       for file in dir.files.values() {
-        let file_record = file.directory_record(context);
+        write_file_entry(&mut *writer, file, options)?;
+      }
 
-        log::debug!("Writing file record: {:?}", file_record);
+      Ok(())
+    }
 
-        buf.resize(file_record.extent(), 0);
-        file_record.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
-      }
+    fn write_root_directory<W: std::io::Write + std::io::Seek>(
+      writer: &mut W,
+      root: &RootDirectory,
+      options: &IsoWriterOptions,
+      context: &Context,
+    ) -> Result<()> {
+      let Some(lba) = root.data_lba(context) else {
+        unreachable!("Directory LBA should have been allocated by now");
+      };
+      let root_record = root.root_directory_record(context);
+      let relocation = root.relocation_directory(context);
 
-      for subdir in dir.dirs.values() {
+      log::debug!("Writing root directory record: {:?}", root_record);
+
+      write_directory_records(
+        &mut *writer,
+        lba,
+        root.records(
+          dot_record(
+            spec::Identifier::current_directory(),
+            lba,
+            root_record.data_length,
+            vec![],
+            context,
+          ),
+          dot_record(
+            spec::Identifier::parent_directory(),
+            lba,
+            root_record.data_length,
+            vec![],
+            context,
+          ),
+          context,
+        ),
+        options,
+      )?;
+
+      for dir in root.dirs.values() {
         write_directory_entry(
           &mut *writer,
-          lba,
-          dir_record.data_length,
-          subdir,
+          (
+            dot_record(
+              spec::Identifier::parent_directory(),
+              root_record.extent_location,
+              root_record.data_length,
+              vec![],
+              context,
+            ),
+            root.attribute_system_use(context),
+          ),
+          dir,
+          relocation.as_ref(),
           options,
           context,
         )?;
       }
 
-      for file in dir.files.values() {
-        write_file_entry(&mut *writer, file, options)?;
+      if let Some(relocation) = &relocation {
+        write_relocation_directory(&mut *writer, relocation, &root_record, options, context)?;
       }
 
       Ok(())
     }
 
-    fn write_root_directory<W: std::io::Write + std::io::Seek>(
+    fn write_relocation_directory<W: std::io::Write + std::io::Seek>(
       writer: &mut W,
-      root: &RootDirectory,
+      relocation: &RelocationDirectory,
+      root_record: &spec::RootDirectoryRecord,
       options: &IsoWriterOptions,
       context: &Context,
     ) -> Result<()> {
-      let Some(lba) = root.data_lba else {
+      let Some(lba) = relocation.root.relocation_lba else {
         unreachable!("Directory LBA should have been allocated by now");
       };
-      let mut sector_writer =
-        SectorWriter::new(&mut *writer, lba as u64, options.sector_size as u64);
-      let root_record = root.root_directory_record(context);
-      let mut buf = vec![];
+      let data_length = relocation.data_length();
+
+      log::debug!("Writing relocation directory");
+
+      write_directory_records(
+        &mut *writer,
+        lba,
+        relocation.records(
+          dot_record(
+            spec::Identifier::current_directory(),
+            lba,
+            data_length,
+            vec![],
+            context,
+          ),
+          dot_record(
+            spec::Identifier::parent_directory(),
+            root_record.extent_location,
+            root_record.data_length,
+            vec![],
+            context,
+          ),
+          context,
+        ),
+        options,
+      )
+    }
 
-      log::debug!("Writing root directory record: {:?}", root_record);
+    write_root_directory(&mut writer, &self.filesystem.root, &self.options, &context)?;
 
-      {
-        let dot_entry = spec::DirectoryRecord {
-          extended_attribute_length: 0,
-          extent_location: lba,
-          data_length: root_record.data_length,
-          recording_date: chrono::Utc::now().into(),
-          file_flags: spec::FileFlags::DIRECTORY,
-          file_unit_size: 0,
-          interleave_gap_size: 0,
-          volume_sequence_number: 1,
-          file_identifier_length: 1,
-          file_identifier: spec::Identifier::current_directory(),
-        };
+    if let Some(joliet_context) = &joliet_context {
+      write_root_directory(
+        &mut writer,
+        &self.filesystem.root,
+        &self.options,
+        joliet_context,
+      )?;
+    }
 
-        buf.resize(dot_entry.extent(), 0);
-        dot_entry.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
-      }
+    // File data is shared by both hierarchies and only written once.
 
-      {
-        let dotdot_entry = spec::DirectoryRecord {
-          extended_attribute_length: 0,
-          extent_location: root_record.extent_location,
-          data_length: root_record.data_length,
-          recording_date: chrono::Utc::now().into(),
-          file_flags: spec::FileFlags::DIRECTORY,
-          file_unit_size: 0,
-          interleave_gap_size: 0,
-          volume_sequence_number: 1,
-          file_identifier_length: 1,
-          file_identifier: spec::Identifier::parent_directory(),
-        };
+    for dir in self.filesystem.root.dirs.values() {
+      write_directory_files(&mut writer, dir, &self.options)?;
+    }
 
-        buf.resize(dotdot_entry.extent(), 0);
-        dotdot_entry.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
-      }
+    for file in self.filesystem.root.files.values() {
+      write_file_entry(&mut writer, file, &self.options)?;
+    }
 
-      for subdir in root.dirs.values() {
-        let subdir_record = subdir.directory_record(context);
+    if let Some(efi_system_partition) = &efi_system_partition {
+      write_file_entry(&mut writer, efi_system_partition, &self.options)?;
+    }
 
-        log::debug!("Writing root directory record: {:?}", subdir_record);
+    // 6. Patch boot info tables into boot images
 
-        buf.resize(subdir_record.extent(), 0);
-        subdir_record.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
+    if let Some(boot) = &self.boot {
+      for entry in boot.entries().filter(|x| x.boot_info_table) {
+        entry.write_boot_info_table(&mut writer, &boot_images, self.options.sector_size)?;
       }
+    }
 
-      for file in root.files.values() {
-        let file_record = file.directory_record(context);
+    // 7. Write the MBR, GPTs and APM (if requested)
 
-        log::debug!("Writing root file record: {:?}", file_record);
+    let image_size = lba_allocator.next_lba as u64 * self.options.sector_size as u64;
 
-        buf.resize(file_record.extent(), 0);
-        file_record.serialize(&mut (), &mut buf)?;
-        sector_writer.write_aligned(&buf)?;
-        buf.clear();
-      }
+    let mut mbr = match self.isohybrid.as_ref().zip(self.boot.as_ref()) {
+      Some((isohybrid, boot)) => {
+        let Some(boot_image_lba) = boot.default_entry.image(&boot_images)?.data_lba() else {
+          unreachable!("File LBA should have been allocated by now");
+        };
 
-      for dir in root.dirs.values() {
-        write_directory_entry(
-          &mut *writer,
-          root_record.extent_location,
-          root_record.data_length,
-          dir,
-          options,
-          context,
-        )?;
+        Some(isohybrid.mbr(boot_image_lba, self.options.sector_size, image_size)?)
       }
+      None => None,
+    };
 
-      for file in root.files.values() {
-        write_file_entry(writer, file, options)?;
-      }
+    if let Some(gpt) = &self.gpt {
+      let mbr = mbr.get_or_insert([0; 512]);
 
-      Ok(())
+      GptOptions::protective_mbr(image_size)
+        .serialize(&mut (), &mut mbr[spec::MbrPartitionTable::OFFSET..])?;
+      gpt.write(
+        &mut writer,
+        image_size,
+        iso_size,
+        gpt_partition_entry_lba,
+        efi_system_partition.as_ref(),
+        self.options.sector_size,
+      )?;
     }
 
-    write_root_directory(&mut writer, &self.filesystem.root, &self.options, &context)?;
+    if let Some(apm) = &self.apm {
+      apm.write(
+        &mut writer,
+        mbr.get_or_insert([0; 512]),
+        &boot_images,
+        image_size,
+        self.options.sector_size,
+      )?;
+    }
 
-    // 6. Done!
+    if let Some(mbr) = mbr {
+      writer.seek(std::io::SeekFrom::Start(0))?;
+      writer.write_all(&mbr)?;
+    }
+
+    // 8. Pad the image to the volume space size
+
+    let written = writer.seek(std::io::SeekFrom::End(0))?;
+
+    if written < image_size {
+      std::io::copy(
+        &mut std::io::Read::take(std::io::repeat(0), image_size - written),
+        &mut writer,
+      )?;
+    }
+
+    // 9. Done!
 
     Ok(())
   }
@@ -1098,4 +4415,152 @@ mod tests {
     assert_eq!(writer.bytes_offset as usize, b"Goodbye!".len());
     assert_eq!(writer.sector_ix, 2);
   }
+
+  #[test]
+  fn continuation_areas() {
+    use super::ContinuationAreas;
+    use crate::{serialize::IsoSerialize, spec::SystemUseEntry};
+
+    let padding = |length| SystemUseEntry::Padding { length };
+
+    // Entries that fit are kept as is.
+    let mut areas = ContinuationAreas::new(100, 2048);
+
+    assert_eq!(
+      areas.place(vec![padding(96), padding(96)], 200).unwrap(),
+      vec![padding(96), padding(96)]
+    );
+    assert_eq!(areas.size(), 0);
+
+    // Overflowing entries move to a continuation area, leaving room for the `CE` entry.
+    assert_eq!(
+      areas
+        .place(vec![padding(96), padding(96), padding(96)], 200)
+        .unwrap(),
+      vec![
+        padding(96),
+        SystemUseEntry::ContinuationArea {
+          block_location: 100,
+          offset: 0,
+          length: 200,
+        },
+      ]
+    );
+
+    // Continuation areas share blocks while they fit.
+    assert_eq!(
+      areas.place(vec![padding(196), padding(96)], 200).unwrap(),
+      vec![SystemUseEntry::ContinuationArea {
+        block_location: 100,
+        offset: 200,
+        length: 300,
+      }]
+    );
+    assert_eq!(areas.size(), 2048);
+
+    // Continuation areas exceeding a block are chained, the tail still fitting within the first.
+    let entries = vec![padding(251); 10];
+    let in_record = areas.place(entries, 100).unwrap();
+    let [SystemUseEntry::ContinuationArea {
+      block_location,
+      offset,
+      length,
+    }] = in_record[..]
+    else {
+      panic!("Expected a single continuation area, got {in_record:?}");
+    };
+
+    assert_eq!(areas.size(), 2 * 2048);
+    assert_eq!((block_location, offset), (101, 0));
+    assert_eq!(length as usize, 7 * 255 + 28);
+
+    let block = &areas.blocks[1];
+    let mut ce = [0; 28];
+
+    SystemUseEntry::ContinuationArea {
+      block_location: 100,
+      offset: 500,
+      length: 3 * 255,
+    }
+    .serialize(&mut (), &mut ce)
+    .unwrap();
+
+    assert_eq!(&block[7 * 255..7 * 255 + 28], &ce);
+  }
+
+  #[test]
+  fn symbolic_link_entries() {
+    use super::RockRidgeOptions;
+    use crate::{
+      error::Error,
+      spec::{SymbolicLinkComponentFlags, SystemUseEntry},
+    };
+    use std::path::Path;
+
+    // Long targets full of `..` components straddle the entry boundary wherever it falls.
+    for length in 2..16 {
+      let target = format!(
+        "{name}/{parents}{name}/{parents}{name}/{parents}{name}",
+        name = "x".repeat(length),
+        parents = "../".repeat(60),
+      );
+      let entries = RockRidgeOptions::symbolic_link(Path::new(&target)).unwrap();
+      let mut path = String::new();
+      let mut separator = "";
+
+      assert!(entries.len() > 1);
+
+      for (ix, entry) in entries.iter().enumerate() {
+        let SystemUseEntry::SymbolicLink {
+          continues,
+          components,
+        } = entry
+        else {
+          panic!("Expected a symbolic link entry, got {entry:?}");
+        };
+        let last = components.last().unwrap();
+
+        assert_eq!(*continues, ix + 1 < entries.len());
+        assert!(
+          components
+            .iter()
+            .map(|x| 2 + x.content.len())
+            .sum::<usize>()
+            <= 250
+        );
+
+        // Entries are only broken within a component with content.
+        if *continues {
+          assert!(last.flags.contains(SymbolicLinkComponentFlags::CONTINUE));
+          assert!(!last.content.is_empty());
+        }
+
+        for component in components {
+          path.push_str(separator);
+          path.push_str(&match component.flags {
+            x if x.contains(SymbolicLinkComponentFlags::PARENT) => "..".to_string(),
+            _ => String::from_utf8(component.content.clone()).unwrap(),
+          });
+
+          separator = match component
+            .flags
+            .contains(SymbolicLinkComponentFlags::CONTINUE)
+          {
+            true => "",
+            false => "/",
+          };
+        }
+      }
+
+      assert_eq!(path, target);
+    }
+
+    // Without a component to break within, the target can't be recorded.
+    let target = "../".repeat(200);
+
+    assert!(matches!(
+      RockRidgeOptions::symbolic_link(Path::new(&target)),
+      Err(Error::InvalidSymbolicLinkTarget(_))
+    ));
+  }
 }
@@ -1,3 +1,2075 @@
 extern crate isofs;
 
-fn main() {}
+use isofs::{
+  deserialize::IsoDeserialize, reader::IsoReader, serialize::IsoSerialize, spec, writer::*,
+};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+fn filesystem(files: &[(&str, &[u8])]) -> Filesystem {
+  let mut filesystem = Filesystem::new();
+
+  for (path, content) in files {
+    filesystem
+      .insert_file(path, content.to_vec().into(), &OnFileConflict::Overwrite)
+      .unwrap();
+  }
+
+  filesystem
+}
+
+fn build(files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+  build_with(IsoWriterOptions::compatibility(), files)
+}
+
+fn build_with(options: IsoWriterOptions, files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+  let mut writer = IsoWriter::new(options);
+  writer
+    .upsert_filesystem(filesystem(files), &OnFileConflict::Overwrite)
+    .unwrap();
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+  image
+}
+
+#[test]
+fn read_primary_volume_descriptor() {
+  let reader = IsoReader::new(build(&[("hello.txt", b"Hello, world!")])).unwrap();
+  let pvd = reader.primary_volume_descriptor();
+
+  assert_eq!(pvd.logical_block_size, 2048);
+  assert_eq!(pvd.volume_identifier.to_string(), "ISOFS");
+  assert!(reader.root().is_directory());
+}
+
+#[test]
+fn list_directories() {
+  let mut reader = IsoReader::new(build(&[
+    ("hello.txt", b"Hello, world!"),
+    ("docs/readme.txt", b"Read me"),
+    ("docs/guide/intro.txt", b"Introduction"),
+  ]))
+  .unwrap();
+
+  let mut root = reader
+    .read_dir("/")
+    .unwrap()
+    .into_iter()
+    .map(|x| (x.name().to_owned(), x.is_directory()))
+    .collect::<Vec<_>>();
+  root.sort();
+
+  assert_eq!(
+    root,
+    [("DOCS".to_owned(), true), ("HELLO.TXT".to_owned(), false)]
+  );
+
+  let mut docs = reader
+    .read_dir("docs")
+    .unwrap()
+    .into_iter()
+    .map(|x| x.name().to_owned())
+    .collect::<Vec<_>>();
+  docs.sort();
+
+  assert_eq!(docs, ["GUIDE", "README.TXT"]);
+}
+
+#[test]
+fn lookup_entries() {
+  let mut reader = IsoReader::new(build(&[
+    ("hello.txt", b"Hello, world!"),
+    ("docs/guide/intro.txt", b"Introduction"),
+  ]))
+  .unwrap();
+
+  let entry = reader.lookup("/docs/guide/intro.txt").unwrap();
+  assert!(!entry.is_directory());
+  assert_eq!(entry.size(), b"Introduction".len() as u64);
+
+  assert!(matches!(
+    reader.lookup("/docs/missing.txt"),
+    Err(isofs::error::Error::NotFound(_))
+  ));
+  assert!(matches!(
+    reader.read_dir("/hello.txt"),
+    Err(isofs::error::Error::NotADirectory(_))
+  ));
+}
+
+#[test]
+fn reject_non_iso() {
+  assert!(IsoReader::new(Cursor::new(vec![0u8; 64 * 1024])).is_err());
+}
+
+#[test]
+fn read_file() {
+  let mut reader = IsoReader::new(build(&[
+    ("hello.txt", b"Hello, world!"),
+    ("docs/readme.txt", b"Read me"),
+  ]))
+  .unwrap();
+
+  let mut content = String::new();
+  reader
+    .open_file("/docs/readme.txt")
+    .unwrap()
+    .read_to_string(&mut content)
+    .unwrap();
+
+  assert_eq!(content, "Read me");
+
+  let mut file = reader.open_file("hello.txt").unwrap();
+  let mut content = String::new();
+
+  file.seek(SeekFrom::Start(7)).unwrap();
+  file.read_to_string(&mut content).unwrap();
+  assert_eq!(content, "world!");
+
+  assert_eq!(file.seek(SeekFrom::End(-6)).unwrap(), 7);
+  assert!(file.seek(SeekFrom::Current(-8)).is_err());
+
+  assert!(matches!(
+    reader.open_file("/docs"),
+    Err(isofs::error::Error::IsADirectory(_))
+  ));
+}
+
+#[test]
+fn read_multi_extent_file() {
+  let head = vec![b'a'; 2048];
+  let mut image = build(&[("data/a.bin", &head), ("data/b.bin", b"tail")]).into_inner();
+
+  // The writer only splits files past 4 GiB, so join the two records into a
+  // single multi-extent file by flagging the first and renaming the second.
+  let find = |image: &[u8], name: &[u8]| image.windows(name.len()).position(|x| x == name).unwrap();
+  let a = find(&image, b"A.BIN;1");
+  let b = find(&image, b"B.BIN;1");
+  let (first, second) = (a.min(b), a.max(b));
+  let name = image[first..first + 7].to_vec();
+
+  image[first - 33 + 25] |= 1 << 7;
+  image[second..second + 7].copy_from_slice(&name);
+
+  let mut reader = IsoReader::new(Cursor::new(image)).unwrap();
+  let entries = reader.read_dir("/data").unwrap();
+
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].extents().len(), 2);
+  assert_eq!(entries[0].size(), 2048 + 4);
+
+  let mut content = vec![];
+  reader
+    .open_entry(&entries[0])
+    .unwrap()
+    .read_to_end(&mut content)
+    .unwrap();
+
+  let expected = if a < b {
+    [head.as_slice(), b"tail"].concat()
+  } else {
+    [b"tail".as_slice(), head.as_slice()].concat()
+  };
+
+  assert_eq!(content, expected);
+}
+
+/// Lists the records of the Joliet directory at `lba`, excluding `.` and `..`.
+fn joliet_records(image: &[u8], lba: u32, length: u32) -> Vec<spec::DirectoryRecord> {
+  let data = &image[lba as usize * 2048..][..length as usize];
+  let mut records = vec![];
+  let mut offset = 0;
+
+  while offset < data.len() && data[offset] != 0 {
+    let record = spec::DirectoryRecord::deserialize(
+      &mut spec::CompatibilityMode::Joliet(spec::JolietLevel::Level3).into(),
+      &data[offset..],
+    )
+    .unwrap();
+
+    offset += data[offset] as usize;
+
+    if record.file_identifier.kind() != spec::IdentifierKind::CurrentDirectory
+      && record.file_identifier.kind() != spec::IdentifierKind::ParentDirectory
+    {
+      records.push(record);
+    }
+  }
+
+  records.sort_by_key(|x| x.file_identifier.to_string());
+  records
+}
+
+#[test]
+fn joliet_hierarchy() {
+  let image = build_with(
+    IsoWriterOptions::default(),
+    &[
+      ("Hello.txt", b"Hello, world!"),
+      ("docs/Read_Me_Please.markdown", b"Read me"),
+    ],
+  )
+  .into_inner();
+
+  let svd = spec::SupplementaryVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+    .unwrap();
+
+  assert_eq!(
+    svd.escape_sequences,
+    spec::EscapeSequences::joliet_level_3()
+  );
+  assert_eq!(svd.volume_identifier.as_bytes(), b"\0I\0S\0O\0F\0S");
+
+  let root = joliet_records(
+    &image,
+    svd.root_directory_record.extent_location,
+    svd.root_directory_record.data_length,
+  );
+  let names = root
+    .iter()
+    .map(|x| x.file_identifier.to_string())
+    .collect::<Vec<_>>();
+
+  assert_eq!(names, ["Hello.txt", "docs"]);
+
+  let docs = joliet_records(&image, root[1].extent_location, root[1].data_length);
+
+  assert_eq!(docs.len(), 1);
+  assert_eq!(
+    docs[0].file_identifier.to_string(),
+    "Read_Me_Please.markdown"
+  );
+
+  // File data is shared with the primary hierarchy.
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let primary = reader.lookup("/docs/read_me_please.markdown").unwrap();
+
+  assert_eq!(docs[0].extent_location, primary.record().extent_location);
+  assert_eq!(docs[0].data_length, 7);
+
+  let path_table = &image[svd.type_l_path_table_location as usize * 2048..];
+  let root_record =
+    spec::PathTableRecord::deserialize(&mut isofs::serialize::Endianness::Little, path_table)
+      .unwrap();
+  let docs_record = spec::PathTableRecord::deserialize(
+    &mut isofs::serialize::Endianness::Little,
+    // The root's record is 8 bytes, plus its 1 byte identifier and padding.
+    &path_table[10..],
+  )
+  .unwrap();
+
+  assert_eq!(
+    root_record.extent_location,
+    svd.root_directory_record.extent_location
+  );
+  assert_eq!(docs_record.extent_location, root[1].extent_location);
+  assert_eq!(docs_record.directory_identifier.as_bytes(), b"\0d\0o\0c\0s");
+}
+
+#[test]
+fn joliet_level() {
+  let image = build_with(
+    IsoWriterOptions {
+      joliet: Some(spec::JolietLevel::Level1),
+      ..IsoWriterOptions::default()
+    },
+    &[("hello.txt", b"Hello, world!")],
+  )
+  .into_inner();
+
+  let svd = spec::SupplementaryVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+    .unwrap();
+
+  assert_eq!(
+    svd.escape_sequences,
+    spec::EscapeSequences::joliet_level_1()
+  );
+  assert_eq!(
+    svd.escape_sequences.joliet_level(),
+    Some(spec::JolietLevel::Level1)
+  );
+
+  // Without Joliet, the terminator directly follows the primary volume descriptor.
+  let image = build(&[("hello.txt", b"Hello, world!")]).into_inner();
+
+  assert_eq!(image[17 * 2048], 255);
+}
+
+#[test]
+fn boot_catalog() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("boot/boot.img", &[0xEB; 2048]), ("hello.txt", b"Hello")]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut boot = BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry {
+      sector_count: 4,
+      ..BootEntry::new("boot/boot.img", spec::ElToritoEmulationType::NoEmulation)
+    },
+  );
+  boot.manufacturer_id = "ISOFS".to_owned();
+  writer.set_boot(boot);
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+  let image = image.into_inner();
+
+  // The boot record precedes the supplementary volume descriptor.
+  let brvd =
+    spec::ElToritoBootRecordVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+      .unwrap();
+  assert_eq!(image[18 * 2048], 2);
+  assert_eq!(image[19 * 2048], 255);
+
+  let catalog = &image[brvd.boot_catalog_pointer as usize * 2048..][..2048];
+  let validation = spec::ElToritoValidationEntry::deserialize(&mut (), &catalog[..32]).unwrap();
+
+  assert_eq!(validation.platform_id, spec::ElToritoPlatformId::X86);
+  assert_eq!(validation.checksum, validation.expected_checksum());
+  assert_eq!(
+    catalog[..32]
+      .chunks_exact(2)
+      .map(|x| u16::from_le_bytes([x[0], x[1]]))
+      .fold(0u16, u16::wrapping_add),
+    0
+  );
+
+  let initial = spec::ElToritoInitialSectionEntry::deserialize(&mut (), &catalog[32..64]).unwrap();
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let boot_image = reader.lookup("/boot/boot.img").unwrap();
+
+  assert_eq!(
+    initial.boot_indicator,
+    spec::ElToritoBootIndicator::Bootable
+  );
+  assert_eq!(u8::from(initial.boot_media_type), 0);
+  assert_eq!(initial.sector_count, 4);
+  assert_eq!(
+    initial.virtual_disk_location,
+    boot_image.record().extent_location
+  );
+}
+
+#[test]
+fn boot_image_not_found() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("hello.txt", b"Hello")]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::new("boot/boot.img", spec::ElToritoEmulationType::NoEmulation),
+  ));
+
+  assert!(matches!(
+    writer.finalize(Cursor::new(vec![])),
+    Err(isofs::error::Error::NotFound(_))
+  ));
+}
+
+#[test]
+fn boot_info_table() {
+  let boot_image = (0..4096u32).map(|x| (x % 251) as u8).collect::<Vec<_>>();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("isolinux/isolinux.bin", &boot_image)]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry {
+      load_segment: 0x1000,
+      boot_info_table: true,
+      ..BootEntry::no_emulation("isolinux/isolinux.bin")
+    },
+  ));
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let mut reader = IsoReader::new(image).unwrap();
+  let entry = reader.lookup("/isolinux/isolinux.bin").unwrap();
+  let mut patched = vec![];
+  reader
+    .open_entry(&entry)
+    .unwrap()
+    .read_to_end(&mut patched)
+    .unwrap();
+
+  let table = spec::BootInfoTable::deserialize(&mut (), &patched[8..64]).unwrap();
+
+  assert_eq!(table.primary_volume_descriptor, 16);
+  assert_eq!(table.boot_file_location, entry.record().extent_location);
+  assert_eq!(table.boot_file_length, 4096);
+  assert_eq!(table.checksum, spec::BootInfoTable::checksum(&boot_image));
+  assert!(patched[24..64].iter().all(|&x| x == 0));
+  assert_eq!(patched[..8], boot_image[..8]);
+  assert_eq!(patched[64..], boot_image[64..]);
+
+  let storage = reader.into_inner().into_inner();
+  let brvd =
+    spec::ElToritoBootRecordVolumeDescriptor::deserialize(&mut (), &storage[17 * 2048..18 * 2048])
+      .unwrap();
+  let catalog = &storage[brvd.boot_catalog_pointer as usize * 2048..];
+  let initial = spec::ElToritoInitialSectionEntry::deserialize(&mut (), &catalog[32..64]).unwrap();
+
+  assert_eq!(initial.load_segment, 0x1000);
+  assert_eq!(initial.sector_count, 4);
+}
+
+fn build_bootable(
+  files: &[(&str, &[u8])],
+  entry: BootEntry,
+) -> Result<Vec<u8>, isofs::error::Error> {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(filesystem(files), &OnFileConflict::Overwrite)
+    .unwrap();
+  writer.set_boot(BootOptions::new(spec::ElToritoPlatformId::X86, entry));
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image)?;
+
+  Ok(image.into_inner())
+}
+
+fn initial_entry(image: &[u8]) -> spec::ElToritoInitialSectionEntry {
+  let brvd =
+    spec::ElToritoBootRecordVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+      .unwrap();
+  let catalog = &image[brvd.boot_catalog_pointer as usize * 2048..];
+
+  spec::ElToritoInitialSectionEntry::deserialize(&mut (), &catalog[32..64]).unwrap()
+}
+
+#[test]
+fn floppy_emulation() {
+  let floppy = vec![0xF6; 1_474_560];
+  let image = build_bootable(
+    &[("boot/floppy.img", &floppy)],
+    BootEntry::new("boot/floppy.img", spec::ElToritoEmulationType::Floppy144M),
+  )
+  .unwrap();
+  let initial = initial_entry(&image);
+
+  assert_eq!(u8::from(initial.boot_media_type), 2);
+  assert_eq!(initial.sector_count, 1);
+
+  assert!(matches!(
+    build_bootable(
+      &[("boot/floppy.img", &floppy)],
+      BootEntry::new("boot/floppy.img", spec::ElToritoEmulationType::Floppy288M),
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+  assert!(matches!(
+    build_bootable(
+      &[("boot/floppy.img", &floppy)],
+      BootEntry {
+        boot_info_table: true,
+        ..BootEntry::new("boot/floppy.img", spec::ElToritoEmulationType::Floppy144M)
+      },
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+#[test]
+fn hard_disk_emulation() {
+  let mut disk = vec![0u8; 64 * 512];
+  let mut table = spec::MbrPartitionTable::default();
+  table.entries[0] = spec::MbrPartitionEntry {
+    boot_indicator: 0x80,
+    partition_type: 0x0C,
+    start_lba: 1,
+    sector_count: 63,
+    ..Default::default()
+  };
+  table
+    .serialize(&mut (), &mut disk[spec::MbrPartitionTable::OFFSET..512])
+    .unwrap();
+
+  let image = build_bootable(
+    &[("boot/disk.img", &disk)],
+    BootEntry {
+      // Ignored in favour of the partition table.
+      system_type: 0x83,
+      ..BootEntry::new("boot/disk.img", spec::ElToritoEmulationType::HardDisk)
+    },
+  )
+  .unwrap();
+  let initial = initial_entry(&image);
+
+  assert_eq!(u8::from(initial.boot_media_type), 4);
+  assert_eq!(initial.system_type, 0x0C);
+
+  // A second partition makes the emulated disk ambiguous.
+  table.entries[1] = table.entries[0];
+  table
+    .serialize(&mut (), &mut disk[spec::MbrPartitionTable::OFFSET..512])
+    .unwrap();
+
+  assert!(matches!(
+    build_bootable(
+      &[("boot/disk.img", &disk)],
+      BootEntry::new("boot/disk.img", spec::ElToritoEmulationType::HardDisk),
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+  assert!(matches!(
+    build_bootable(
+      &[("boot/disk.img", &[0u8; 1024])],
+      BootEntry::new("boot/disk.img", spec::ElToritoEmulationType::HardDisk),
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+#[test]
+fn boot_sections() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[
+        ("boot/bios.img", &[0xEB; 2048]),
+        ("boot/efi.img", &[0xEF; 4096]),
+        ("boot/ppc.img", &[0x01; 2048]),
+      ]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut boot = BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("boot/bios.img"),
+  );
+  boot.sections = vec![
+    BootSection {
+      id: "UEFI".to_owned(),
+      ..BootSection::new(
+        spec::ElToritoPlatformId::Efi,
+        vec![
+          BootEntry::no_emulation("boot/efi.img"),
+          BootEntry {
+            selection_criteria: Some(SelectionCriteria {
+              criteria_type: spec::ElToritoSelectionCriteriaType::LanguageAndVersionInformation,
+              data: (0..50).collect(),
+            }),
+            ..BootEntry::no_emulation("boot/efi.img")
+          },
+        ],
+      )
+    },
+    BootSection::new(
+      spec::ElToritoPlatformId::PowerPc,
+      vec![BootEntry::no_emulation("boot/ppc.img")],
+    ),
+  ];
+  writer.set_boot(boot);
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+  let image = image.into_inner();
+
+  let brvd =
+    spec::ElToritoBootRecordVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+      .unwrap();
+  let catalog = &image[brvd.boot_catalog_pointer as usize * 2048..][..2048];
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let efi_lba = reader
+    .lookup("/boot/efi.img")
+    .unwrap()
+    .record()
+    .extent_location;
+  let ppc_lba = reader
+    .lookup("/boot/ppc.img")
+    .unwrap()
+    .record()
+    .extent_location;
+
+  let efi_header =
+    spec::ElToritoSectionHeaderEntry::deserialize(&mut (), &catalog[64..96]).unwrap();
+
+  assert_eq!(
+    efi_header.header_indicator,
+    spec::ElToritoHeaderIndicator::MoreHeadersFollow
+  );
+  assert_eq!(efi_header.platform_id, spec::ElToritoPlatformId::Efi);
+  assert_eq!(efi_header.succeeding_section_entries, 2);
+  assert_eq!(catalog[65], 0xEF);
+  assert_eq!(&catalog[68..72], b"UEFI");
+
+  let plain = spec::ElToritoSectionEntry::deserialize(&mut (), &catalog[96..128]).unwrap();
+
+  assert_eq!(plain.virtual_disk_location, efi_lba);
+  assert!(!plain.boot_media_type.continuation_entry_follows);
+  assert_eq!(
+    plain.selection_criteria_type,
+    spec::ElToritoSelectionCriteriaType::NoSelectionCriteria
+  );
+
+  // 50 bytes of criteria fill the entry and two extension records.
+  let selected = spec::ElToritoSectionEntry::deserialize(&mut (), &catalog[128..160]).unwrap();
+  let first =
+    spec::ElToritoSectionEntryExtension::deserialize(&mut (), &catalog[160..192]).unwrap();
+  let second =
+    spec::ElToritoSectionEntryExtension::deserialize(&mut (), &catalog[192..224]).unwrap();
+
+  assert!(selected.boot_media_type.continuation_entry_follows);
+  assert_eq!(
+    selected.vendor_selection_criteria,
+    std::array::from_fn::<u8, 19, _>(|x| x as u8)
+  );
+  assert_eq!(
+    first.extension_record_follows_indicator,
+    spec::ElToritoExtensionRecordFollowsIndicator::EXTENSION_RECORD_FOLLOWS
+  );
+  assert_eq!(first.vendor_unique_selection_criteria[0], 19);
+  assert!(second.extension_record_follows_indicator.is_empty());
+  assert_eq!(second.vendor_unique_selection_criteria[..1], [49]);
+  assert!(
+    second.vendor_unique_selection_criteria[1..]
+      .iter()
+      .all(|&x| x == 0)
+  );
+
+  let ppc_header =
+    spec::ElToritoSectionHeaderEntry::deserialize(&mut (), &catalog[224..256]).unwrap();
+  let ppc = spec::ElToritoSectionEntry::deserialize(&mut (), &catalog[256..288]).unwrap();
+
+  assert_eq!(
+    ppc_header.header_indicator,
+    spec::ElToritoHeaderIndicator::FinalHeader
+  );
+  assert_eq!(ppc_header.platform_id, spec::ElToritoPlatformId::PowerPc);
+  assert_eq!(ppc.virtual_disk_location, ppc_lba);
+  assert!(catalog[288..].iter().all(|&x| x == 0));
+}
+
+#[test]
+fn default_entry_selection_criteria() {
+  assert!(matches!(
+    build_bootable(
+      &[("boot/bios.img", &[0xEB; 2048])],
+      BootEntry {
+        selection_criteria: Some(SelectionCriteria {
+          criteria_type: spec::ElToritoSelectionCriteriaType::LanguageAndVersionInformation,
+          data: vec![1],
+        }),
+        ..BootEntry::no_emulation("boot/bios.img")
+      },
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+#[test]
+fn isohybrid_mbr() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("isolinux/isolinux.bin", &[0xEB; 2048])]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("isolinux/isolinux.bin"),
+  ));
+  writer.set_isohybrid(IsohybridOptions {
+    disk_signature: 0x1234_5678,
+    ..IsohybridOptions::new(vec![0xFA; 512])
+  });
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let mut reader = IsoReader::new(image).unwrap();
+  let boot_lba = reader
+    .lookup("/isolinux/isolinux.bin")
+    .unwrap()
+    .record()
+    .extent_location;
+  let volume_space_size = reader.primary_volume_descriptor().volume_space_size;
+  let image = reader.into_inner().into_inner();
+
+  // Padded to a whole number of 64 head, 32 sector cylinders.
+  assert_eq!(image.len() % (1024 * 1024), 0);
+  assert_eq!(image.len(), volume_space_size as usize * 2048);
+
+  assert!(image[..432].iter().all(|&x| x == 0xFA));
+  assert_eq!(image[432..436], (boot_lba * 4).to_le_bytes());
+  assert_eq!(image[440..444], 0x1234_5678u32.to_le_bytes());
+
+  let table = spec::MbrPartitionTable::deserialize(&mut (), &image[446..512]).unwrap();
+  let partition = table.entries[0];
+
+  assert_eq!(partition.boot_indicator, 0x80);
+  assert_eq!(partition.partition_type, 0x17);
+  assert_eq!(partition.start_lba, 0);
+  assert_eq!(partition.sector_count as usize, image.len() / 512);
+  assert_eq!(partition.start_chs, [0, 1, 0]);
+  assert_eq!(partition.end_chs, [63, 32, 0]);
+  assert!(table.entries[1..].iter().all(|x| x.is_empty()));
+}
+
+#[test]
+fn isohybrid_geometry() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("isolinux/isolinux.bin", &[0xEB; 2048])]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("isolinux/isolinux.bin"),
+  ));
+  writer.set_isohybrid(IsohybridOptions {
+    heads: 255,
+    sectors_per_track: 63,
+    ..IsohybridOptions::new(vec![0xFA; 432])
+  });
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let reader = IsoReader::new(image).unwrap();
+  let volume_space_size = reader.primary_volume_descriptor().volume_space_size;
+  let image = reader.into_inner().into_inner();
+
+  // Cylinders of 255 heads and 63 sectors don't span whole 2048 byte sectors.
+  assert_eq!(image.len() % (255 * 63 * 512), 0);
+  assert_eq!(image.len(), volume_space_size as usize * 2048);
+}
+
+#[test]
+fn isohybrid_requires_boot() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("hello.txt", b"Hello")]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_isohybrid(IsohybridOptions::new(vec![0xFA; 432]));
+
+  assert!(matches!(
+    writer.finalize(Cursor::new(vec![])),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+#[test]
+fn gpt_efi_system_partition() {
+  let esp = (0..64 * 512u32)
+    .map(|x| (x % 253) as u8)
+    .collect::<Vec<_>>();
+  let disk_guid = spec::Guid::new(0x0123_4567, 0x89AB, 0xCDEF, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[("isolinux/isolinux.bin", &[0xEB; 2048])]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut boot = BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("isolinux/isolinux.bin"),
+  );
+  boot.sections = vec![BootSection::new(
+    spec::ElToritoPlatformId::Efi,
+    vec![BootEntry::no_emulation(BootImage::EfiSystemPartition)],
+  )];
+  writer.set_boot(boot);
+  writer.set_isohybrid(IsohybridOptions::new(vec![0xFA; 432]));
+  writer.set_gpt(GptOptions {
+    efi_system_partition: Some(esp.clone().into()),
+    ..GptOptions::new(disk_guid)
+  });
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+  let image = image.into_inner();
+  let sectors = image.len() as u64 / 512;
+
+  // Isohybrid boot code is kept, its partition table replaced by a protective one.
+  assert_eq!(image.len() % (1024 * 1024), 0);
+  assert!(image[..432].iter().all(|&x| x == 0xFA));
+
+  let mbr = spec::MbrPartitionTable::deserialize(&mut (), &image[446..512]).unwrap();
+
+  assert_eq!(mbr.entries[0].partition_type, 0xEE);
+  assert_eq!(mbr.entries[0].start_lba, 1);
+  assert_eq!(mbr.entries[0].sector_count as u64, sectors - 1);
+  assert!(mbr.entries[1..].iter().all(|x| x.is_empty()));
+
+  let header_at = |lba: u64| {
+    let bytes = &image[lba as usize * 512..][..92];
+    let header = spec::GptHeader::deserialize(&mut (), bytes).unwrap();
+    let mut zeroed = bytes.to_vec();
+    zeroed[16..20].fill(0);
+
+    assert_eq!(header.header_crc32, crc32fast::hash(&zeroed));
+    header
+  };
+  let primary = header_at(1);
+  let backup = header_at(sectors - 1);
+
+  assert_eq!(primary.alternate_lba, sectors - 1);
+  assert_eq!(backup.alternate_lba, 1);
+  assert_eq!(primary.disk_guid, disk_guid);
+  assert_eq!(primary.last_usable_lba, sectors - 34);
+
+  for header in [&primary, &backup] {
+    let array = &image[header.partition_entry_lba as usize * 512..][..128 * 128];
+
+    assert_eq!(header.partition_entry_array_crc32, crc32fast::hash(array));
+    assert_eq!(array, &image[2 * 512..][..128 * 128]);
+  }
+
+  let iso = spec::GptPartitionEntry::deserialize(&mut (), &image[1024..1152]).unwrap();
+  let efi = spec::GptPartitionEntry::deserialize(&mut (), &image[1152..1280]).unwrap();
+
+  assert_eq!(iso.partition_type_guid, spec::Guid::BASIC_DATA);
+  assert_eq!(iso.starting_lba, 64);
+  assert_eq!(efi.partition_type_guid, spec::Guid::EFI_SYSTEM_PARTITION);
+  assert_eq!(efi.starting_lba, iso.ending_lba + 1);
+  assert_eq!(efi.ending_lba - efi.starting_lba + 1, 64);
+  assert_ne!(iso.unique_partition_guid, efi.unique_partition_guid);
+  assert_eq!(
+    image[efi.starting_lba as usize * 512..][..esp.len()],
+    esp[..]
+  );
+
+  // The El Torito EFI entry boots the appended partition.
+  let brvd =
+    spec::ElToritoBootRecordVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..18 * 2048])
+      .unwrap();
+  let catalog = &image[brvd.boot_catalog_pointer as usize * 2048..];
+  let header = spec::ElToritoSectionHeaderEntry::deserialize(&mut (), &catalog[64..96]).unwrap();
+  let entry = spec::ElToritoSectionEntry::deserialize(&mut (), &catalog[96..128]).unwrap();
+
+  assert_eq!(header.platform_id, spec::ElToritoPlatformId::Efi);
+  assert_eq!(entry.virtual_disk_location as u64 * 4, efi.starting_lba);
+  assert_eq!(entry.sector_count, 64);
+
+  // The ISO 9660 volume remains readable.
+  let mut reader = IsoReader::new(Cursor::new(image)).unwrap();
+  assert!(reader.lookup("/isolinux/isolinux.bin").is_ok());
+}
+
+#[test]
+fn efi_system_partition_missing() {
+  assert!(matches!(
+    build_bootable(
+      &[("hello.txt", b"Hello")],
+      BootEntry::no_emulation(BootImage::EfiSystemPartition),
+    ),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+#[test]
+fn apple_partition_map() {
+  let esp = vec![0xEF; 8 * 2048];
+  let hfs = vec![0x48; 4 * 2048 + 1];
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(
+      filesystem(&[
+        ("isolinux/isolinux.bin", &[0xEB; 2048]),
+        ("boot/macboot.img", &hfs),
+      ]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("isolinux/isolinux.bin"),
+  ));
+  writer.set_gpt(GptOptions {
+    efi_system_partition: Some(esp.into()),
+    ..GptOptions::new(spec::Guid([0x42; 16]))
+  });
+  writer.set_apm(ApmOptions {
+    entries: vec![
+      ApmEntry::efi_system_partition(),
+      ApmEntry::hfs_plus("boot/macboot.img"),
+    ],
+  });
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+  let image = image.into_inner();
+
+  let ddm = spec::ApmDriverDescriptorMap::deserialize(&mut (), &image[..8]).unwrap();
+
+  assert_eq!(ddm.block_size, 2048);
+  assert_eq!(ddm.block_count as usize, image.len() / 2048);
+
+  let entry_at = |block: usize| {
+    spec::ApmPartitionEntry::deserialize(&mut (), &image[block * 2048..][..512]).unwrap()
+  };
+  let map = entry_at(1);
+  let efi = entry_at(2);
+  let hfs_entry = entry_at(3);
+
+  assert_eq!(map.map_block_count, 3);
+  assert_eq!(&map.partition_type[..19], b"Apple_partition_map");
+  assert_eq!((map.start_block, map.block_count), (1, 3));
+  assert_eq!(&efi.name[..4], b"EFI\0");
+  assert_eq!(&efi.partition_type[..10], b"Apple_HFS\0");
+  assert_eq!(efi.block_count, 8);
+  assert_eq!(hfs_entry.block_count, 5);
+
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  assert_eq!(
+    hfs_entry.start_block,
+    reader
+      .lookup("/boot/macboot.img")
+      .unwrap()
+      .record()
+      .extent_location
+  );
+
+  // The primary GPT partition array moves past the map.
+  let gpt = spec::GptHeader::deserialize(&mut (), &image[512..604]).unwrap();
+  let gpt_efi = spec::GptPartitionEntry::deserialize(
+    &mut (),
+    &image[gpt.partition_entry_lba as usize * 512 + 128..][..128],
+  )
+  .unwrap();
+
+  assert_eq!(gpt.partition_entry_lba, 16);
+  assert_eq!(gpt_efi.starting_lba, efi.start_block as u64 * 4);
+  assert_eq!(
+    gpt.partition_entry_array_crc32,
+    crc32fast::hash(&image[16 * 512..48 * 512])
+  );
+}
+
+#[test]
+fn apple_partition_map_too_large() {
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer.set_gpt(GptOptions::new(spec::Guid([0x42; 16])));
+  writer.set_apm(ApmOptions {
+    entries: vec![ApmEntry::hfs_plus("boot/macboot.img"); 7],
+  });
+
+  assert!(matches!(
+    writer.finalize(Cursor::new(vec![])),
+    Err(isofs::error::Error::InvalidBootConfiguration(_))
+  ));
+}
+
+/// Entries of the System Use area continued at `block_location`.
+fn continuation_area(
+  image: &[u8],
+  block_location: u32,
+  offset: u32,
+  length: u32,
+) -> Vec<spec::SystemUseEntry> {
+  let mut data = &image[block_location as usize * 2048 + offset as usize..][..length as usize];
+  let mut entries = vec![];
+
+  while !data.is_empty() {
+    let entry = spec::SystemUseEntry::deserialize(&mut (), data).unwrap();
+
+    data = &data[entry.extent()..];
+    entries.push(entry);
+  }
+
+  entries
+}
+
+#[cfg(unix)]
+#[test]
+fn rock_ridge_posix_attributes() {
+  use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+  let source = std::env::temp_dir().join(format!("isofs-rock-ridge-{}", std::process::id()));
+  let script = source.join("bin/run.sh");
+
+  std::fs::create_dir_all(script.parent().unwrap()).unwrap();
+  std::fs::write(&script, b"#!/bin/sh\necho hi\n").unwrap();
+  std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+  std::fs::set_permissions(
+    script.parent().unwrap(),
+    std::fs::Permissions::from_mode(0o750),
+  )
+  .unwrap();
+
+  let metadata = std::fs::metadata(&script).unwrap();
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(
+      Filesystem::capture("", &source).unwrap(),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer
+    .upsert_filesystem(
+      filesystem(&[("hello.txt", b"Hello, world!")]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  std::fs::remove_dir_all(&source).unwrap();
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let mut reader = IsoReader::new(Cursor::new(image.get_ref().clone())).unwrap();
+  let px = |entry: &isofs::reader::Entry| {
+    entry
+      .record()
+      .system_use
+      .iter()
+      .find_map(|x| match x {
+        spec::SystemUseEntry::PosixFileAttributes {
+          mode, uid, serial, ..
+        } => Some((*mode, *uid, *serial)),
+        _ => None,
+      })
+      .unwrap()
+  };
+
+  // Files and directories get serial numbers of their own in place of the host's, in the order
+  // they are laid out, lest readers take in-memory files for hard links.
+  assert_eq!(
+    px(&reader.lookup("/bin/run.sh").unwrap()),
+    (spec::file_type::REGULAR | 0o755, metadata.uid(), Some(3))
+  );
+  assert_eq!(
+    px(&reader.lookup("/hello.txt").unwrap()),
+    (spec::file_type::REGULAR | 0o444, 0, Some(4))
+  );
+  // Captured directories keep their attributes too.
+  assert_eq!(
+    px(&reader.lookup("/bin").unwrap()),
+    (spec::file_type::DIRECTORY | 0o750, metadata.uid(), Some(2))
+  );
+  assert!(
+    reader
+      .lookup("/hello.txt")
+      .unwrap()
+      .record()
+      .system_use
+      .iter()
+      .any(|x| matches!(
+        x,
+        spec::SystemUseEntry::TimeStamps(spec::TimeStamps::Short(_))
+      ))
+  );
+
+  // The root `.` record announces SUSP, continuing with the extension reference.
+  let image = image.into_inner();
+  let pvd =
+    spec::PrimaryVolumeDescriptor::deserialize(&mut (), &image[16 * 2048..17 * 2048]).unwrap();
+  let dot = spec::DirectoryRecord::deserialize(
+    &mut spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3).into(),
+    &image[pvd.root_directory_record.extent_location as usize * 2048..],
+  )
+  .unwrap();
+
+  assert_eq!(
+    dot.system_use[0],
+    spec::SystemUseEntry::SharingProtocolIndicator { bytes_skipped: 0 }
+  );
+
+  let Some(&spec::SystemUseEntry::ContinuationArea {
+    block_location,
+    offset,
+    length,
+  }) = dot.system_use.last()
+  else {
+    panic!("Expected a continuation area, got {:?}", dot.system_use);
+  };
+
+  assert!(matches!(
+    &continuation_area(&image, block_location, offset, length)[..],
+    [spec::SystemUseEntry::ExtensionsReference { identifier, .. }] if identifier == "IEEE_P1282"
+  ));
+}
+
+#[test]
+fn rock_ridge_names_and_symbolic_links() {
+  let component = "x".repeat(300);
+  let target = format!("./../{component}/file.txt");
+  let mut filesystem = filesystem(&[("MixedCase.txt", b"Hello, world!")]);
+  filesystem
+    .insert_file(
+      "link.lnk",
+      FileEntryContent::symbolic_link(&target),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(filesystem, &OnFileConflict::Overwrite)
+    .unwrap();
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let image = image.into_inner();
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let system_use = |entry: &isofs::reader::Entry| {
+    let mut entries = vec![];
+
+    for x in &entry.record().system_use {
+      match *x {
+        spec::SystemUseEntry::ContinuationArea {
+          block_location,
+          offset,
+          length,
+        } => entries.extend(continuation_area(&image, block_location, offset, length)),
+        ref x => entries.push(x.clone()),
+      }
+    }
+
+    entries
+  };
+
+  let name = system_use(&reader.lookup("/mixedcase.txt").unwrap())
+    .into_iter()
+    .find_map(|x| match x {
+      spec::SystemUseEntry::AlternateName { flags, content } => Some((flags, content)),
+      _ => None,
+    });
+
+  assert_eq!(
+    name,
+    Some((spec::AlternateNameFlags::empty(), b"MixedCase.txt".to_vec()))
+  );
+
+  let links = system_use(&reader.lookup("/link.lnk").unwrap())
+    .into_iter()
+    .filter_map(|x| match x {
+      spec::SystemUseEntry::SymbolicLink {
+        continues,
+        components,
+      } => Some((continues, components)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+
+  // The long component continues in the next entry, which only the last entry doesn't.
+  assert_eq!(links.len(), 2);
+  assert_eq!(
+    links.iter().map(|(x, _)| *x).collect::<Vec<_>>(),
+    [true, false]
+  );
+
+  let mut path = String::new();
+  let mut separator = "";
+
+  for component in links.into_iter().flat_map(|(_, x)| x) {
+    path.push_str(separator);
+    path.push_str(&match component.flags {
+      x if x.contains(spec::SymbolicLinkComponentFlags::CURRENT) => ".".to_string(),
+      x if x.contains(spec::SymbolicLinkComponentFlags::PARENT) => "..".to_string(),
+      _ => String::from_utf8(component.content).unwrap(),
+    });
+
+    separator = match component
+      .flags
+      .contains(spec::SymbolicLinkComponentFlags::CONTINUE)
+    {
+      true => "",
+      false => "/",
+    };
+  }
+
+  assert_eq!(path, target);
+
+  // Targets without a component to break an entry within are rejected.
+  let mut filesystem = Filesystem::new();
+  filesystem
+    .insert_file(
+      "link.lnk",
+      FileEntryContent::symbolic_link("../".repeat(200)),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(filesystem, &OnFileConflict::Overwrite)
+    .unwrap();
+
+  assert!(matches!(
+    writer.finalize(Cursor::new(vec![])),
+    Err(isofs::error::Error::InvalidSymbolicLinkTarget(_))
+  ));
+}
+
+#[test]
+fn rock_ridge_relocation() {
+  let files: &[(&str, &[u8])] = &[("a/b/c/d/e/f/g/h/i/deep.txt", b"deep")];
+  let image = build_with(IsoWriterOptions::default(), files).into_inner();
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+
+  // The directory on the ninth level is relocated, leaving a file pointing to it behind.
+  let relocated = reader.lookup("/a/b/c/d/e/f/g/h").unwrap();
+  let block_location = relocated.extents()[0].lba;
+
+  assert!(relocated.is_directory());
+  assert!(
+    !relocated
+      .record()
+      .file_flags
+      .contains(spec::FileFlags::DIRECTORY)
+  );
+  assert!(
+    relocated
+      .system_use()
+      .contains(&spec::SystemUseEntry::ChildLink { block_location })
+  );
+
+  // Its record in `RR_MOVED` is hidden from listings.
+  let rr_moved = reader.lookup("/rr_moved").unwrap();
+  let extent = &image[rr_moved.extents()[0].lba as usize * 2048..];
+  let offset = extent[0] as usize + extent[extent[0] as usize] as usize;
+  let record = spec::DirectoryRecord::deserialize(
+    &mut spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3).into(),
+    &extent[offset..],
+  )
+  .unwrap();
+
+  assert!(reader.entries(&rr_moved).unwrap().is_empty());
+  assert_eq!(record.extent_location, block_location);
+  assert!(record.system_use.contains(&spec::SystemUseEntry::Relocated));
+
+  // Its `..` record points to `RR_MOVED`, and back to its actual parent.
+  let extent = &image[block_location as usize * 2048..];
+  let dotdot = spec::DirectoryRecord::deserialize(
+    &mut spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3).into(),
+    &extent[extent[0] as usize..],
+  )
+  .unwrap();
+
+  assert_eq!(dotdot.extent_location, rr_moved.extents()[0].lba);
+  assert!(
+    dotdot
+      .system_use
+      .contains(&spec::SystemUseEntry::ParentLink {
+        block_location: reader.lookup("/a/b/c/d/e/f/g").unwrap().extents()[0].lba,
+      })
+  );
+
+  // `RR_MOVED` is listed among the root's subdirectories within the path table, level by level.
+  let records = path_table(&image);
+
+  assert_eq!(
+    records
+      .iter()
+      .skip(1)
+      .map(|x| (
+        x.directory_identifier.to_string(),
+        x.parent_directory_number
+      ))
+      .collect::<Vec<_>>(),
+    [
+      ("A", 1),
+      ("RR_MOVED", 1),
+      ("B", 2),
+      ("H", 3),
+      ("C", 4),
+      ("I", 5),
+      ("D", 6),
+      ("E", 8),
+      ("F", 9),
+      ("G", 10),
+    ]
+    .map(|(identifier, parent)| (identifier.to_string(), parent))
+  );
+  assert_eq!(records[2].extent_location, rr_moved.extents()[0].lba);
+  assert_eq!(records[4].extent_location, block_location);
+
+  // Every file and directory, the root and `RR_MOVED` included, has a serial number of its own.
+  let serial = |system_use: &[spec::SystemUseEntry]| {
+    system_use.iter().find_map(|x| match *x {
+      spec::SystemUseEntry::PosixFileAttributes { serial, .. } => serial,
+      _ => None,
+    })
+  };
+  let root = &image[records[0].extent_location as usize * 2048..];
+  let dot = spec::DirectoryRecord::deserialize(
+    &mut spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3).into(),
+    root,
+  )
+  .unwrap();
+  let mut serials = vec![serial(&dot.system_use).unwrap()];
+  let mut pending = vec![reader.root()];
+
+  while let Some(dir) = pending.pop() {
+    for entry in reader.entries(&dir).unwrap() {
+      serials.push(serial(entry.system_use()).unwrap());
+
+      if entry.is_directory() {
+        pending.push(entry);
+      }
+    }
+  }
+
+  let count = serials.len();
+
+  serials.sort();
+  serials.dedup();
+
+  assert_eq!(count, 12);
+  assert_eq!(serials.len(), count);
+  assert!(!serials.contains(&0));
+
+  let mut content = vec![];
+  reader
+    .open_file("/a/b/c/d/e/f/g/h/i/deep.txt")
+    .unwrap()
+    .read_to_end(&mut content)
+    .unwrap();
+  assert_eq!(content, b"deep");
+
+  // Without Rock Ridge, nothing would record where relocated directories belong.
+  let mut writer = IsoWriter::new(IsoWriterOptions::compatibility());
+  writer
+    .upsert_filesystem(filesystem(files), &OnFileConflict::Overwrite)
+    .unwrap();
+
+  assert!(matches!(
+    writer.finalize(Cursor::new(vec![])),
+    Err(isofs::error::Error::HierarchyTooDeep(path)) if path == std::path::Path::new("/a/b/c/d/e/f/g/h")
+  ));
+}
+
+#[test]
+fn read_rock_ridge() {
+  let mut filesystem = filesystem(&[
+    ("Docs/ReadMe.md", b"# Hello"),
+    ("MixedCase.txt", b"Hello, world!"),
+  ]);
+  filesystem
+    .insert_file(
+      "link.lnk",
+      FileEntryContent::symbolic_link("/Docs/../MixedCase.txt"),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(filesystem, &OnFileConflict::Overwrite)
+    .unwrap();
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let mut reader = IsoReader::new(image).unwrap();
+
+  assert!(reader.is_rock_ridge());
+
+  let mut names = reader
+    .read_dir("/")
+    .unwrap()
+    .iter()
+    .map(|x| x.name().to_string())
+    .collect::<Vec<_>>();
+  names.sort();
+
+  assert_eq!(names, ["Docs", "MixedCase.txt", "link.lnk"]);
+
+  let entry = reader.lookup("/docs/readme.md").unwrap();
+  let metadata = entry.posix_metadata().unwrap();
+
+  assert_eq!(entry.name(), "ReadMe.md");
+  assert_eq!(metadata.mode, spec::file_type::REGULAR | 0o444);
+  assert!(matches!(
+    metadata.timestamps,
+    Some(spec::TimeStamps::Short(_))
+  ));
+
+  let link = reader.lookup("/link.lnk").unwrap();
+
+  assert_eq!(
+    link.posix_metadata().unwrap().mode & spec::file_type::MASK,
+    spec::file_type::SYMBOLIC_LINK
+  );
+  assert_eq!(
+    link.symbolic_link().as_deref(),
+    Some("/Docs/../MixedCase.txt")
+  );
+  assert_eq!(entry.symbolic_link(), None);
+}
+
+#[test]
+fn zisofs_compression() {
+  let text = b"The quick brown fox jumps over the lazy dog. ".repeat(2000);
+  let mut sparse = vec![0u8; 100_000];
+  sparse[70_000..70_005].copy_from_slice(b"hello");
+
+  // Incompressible content, from a linear congruential generator.
+  let noise = (0..10_000u32)
+    .scan(1u32, |x, _| {
+      *x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+      Some((*x >> 16) as u8)
+    })
+    .collect::<Vec<_>>();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions {
+    rock_ridge: Some(RockRidgeOptions {
+      zisofs: Some(ZisofsOptions::default()),
+      ..Default::default()
+    }),
+    ..IsoWriterOptions::default()
+  });
+  writer
+    .upsert_filesystem(
+      filesystem(&[
+        ("boot/boot.img", &[0; 2048]),
+        ("text.txt", &text),
+        ("sparse.bin", &sparse),
+        ("noise.bin", &noise),
+        ("empty.txt", b""),
+      ]),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  writer.set_boot(BootOptions::new(
+    spec::ElToritoPlatformId::X86,
+    BootEntry::no_emulation("boot/boot.img"),
+  ));
+
+  let mut image = Cursor::new(vec![]);
+  writer.finalize(&mut image).unwrap();
+
+  let mut reader = IsoReader::new(image).unwrap();
+  let compressed = |entry: &isofs::reader::Entry| {
+    entry
+      .system_use()
+      .iter()
+      .any(|x| matches!(x, spec::SystemUseEntry::CompressedFile { .. }))
+  };
+
+  for (path, content) in [("/text.txt", &text), ("/sparse.bin", &sparse)] {
+    let entry = reader.lookup(path).unwrap();
+
+    assert!(compressed(&entry));
+    assert!((entry.record().data_length as usize) < content.len() / 10);
+    assert_eq!(entry.size(), content.len() as u64);
+
+    let mut data = vec![];
+    reader
+      .open_entry(&entry)
+      .unwrap()
+      .read_to_end(&mut data)
+      .unwrap();
+    assert_eq!(&data, content);
+  }
+
+  // Blocks are decompressed at random.
+  let mut file = reader.open_file("/sparse.bin").unwrap();
+  let mut hello = [0u8; 5];
+
+  file.seek(SeekFrom::Start(70_000)).unwrap();
+  file.read_exact(&mut hello).unwrap();
+  assert_eq!(&hello, b"hello");
+
+  // Content that doesn't shrink, and boot images, are left as is.
+  for (path, size) in [
+    ("/noise.bin", 10_000),
+    ("/empty.txt", 0),
+    ("/boot/boot.img", 2048),
+  ] {
+    let entry = reader.lookup(path).unwrap();
+
+    assert!(!compressed(&entry));
+    assert_eq!(entry.record().data_length, size);
+  }
+}
+
+#[test]
+fn zisofs_invalid_options() {
+  for zisofs in [
+    ZisofsOptions {
+      block_size_log2: 40,
+      ..ZisofsOptions::default()
+    },
+    ZisofsOptions {
+      level: 10,
+      ..ZisofsOptions::default()
+    },
+  ] {
+    let mut writer = IsoWriter::new(IsoWriterOptions {
+      rock_ridge: Some(RockRidgeOptions {
+        zisofs: Some(zisofs),
+        ..Default::default()
+      }),
+      ..IsoWriterOptions::default()
+    });
+    writer
+      .upsert_filesystem(
+        filesystem(&[("text.txt", b"text")]),
+        &OnFileConflict::Overwrite,
+      )
+      .unwrap();
+
+    assert!(matches!(
+      writer.finalize(Cursor::new(vec![])),
+      Err(isofs::error::Error::InvalidZisofsOptions(_))
+    ));
+  }
+}
+
+/// Storage keeping the first `limit` bytes written to it, holding the descriptors and directories
+/// of small hierarchies, while discarding the file data past them.
+struct Head {
+  data: Vec<u8>,
+  position: u64,
+  length: u64,
+  limit: u64,
+}
+
+impl std::io::Write for Head {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    if self.position < self.limit {
+      let end = (self.position + buf.len() as u64).min(self.limit) as usize;
+
+      if self.data.len() < end {
+        self.data.resize(end, 0);
+      }
+
+      self.data[self.position as usize..end].copy_from_slice(&buf[..end - self.position as usize]);
+    }
+
+    self.position += buf.len() as u64;
+    self.length = self.length.max(self.position);
+
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+impl Seek for Head {
+  fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+    self.position = match pos {
+      SeekFrom::Start(x) => x,
+      SeekFrom::Current(x) => self.position.checked_add_signed(x).unwrap(),
+      SeekFrom::End(x) => self.length.checked_add_signed(x).unwrap(),
+    };
+
+    Ok(self.position)
+  }
+}
+
+#[test]
+fn multi_extent_file() {
+  const SIZE: u64 = 5 << 30;
+  const MAX_EXTENT_LENGTH: u32 = 0xFFFF_F800;
+
+  // A sparse file, so that it takes up no room on disk.
+  let path = std::env::temp_dir().join(format!("isofs-multi-extent-{}", std::process::id()));
+  let file = std::fs::File::create(&path).unwrap();
+  file.set_len(SIZE).unwrap();
+
+  let mut filesystem = filesystem(&[("small.txt", b"small")]);
+  filesystem
+    .insert_file(
+      "disk.img",
+      std::fs::File::open(&path).unwrap().try_into().unwrap(),
+      &OnFileConflict::Overwrite,
+    )
+    .unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  let mut writer = IsoWriter::new(IsoWriterOptions::default());
+  writer
+    .upsert_filesystem(filesystem, &OnFileConflict::Overwrite)
+    .unwrap();
+
+  let mut head = Head {
+    data: vec![],
+    position: 0,
+    length: 0,
+    limit: 1 << 20,
+  };
+  writer.finalize(&mut head).unwrap();
+
+  let mut reader = IsoReader::new(Cursor::new(head.data)).unwrap();
+  let entry = reader.lookup("/disk.img").unwrap();
+  let extents = entry.extents();
+
+  assert_eq!(entry.size(), SIZE);
+  assert_eq!(extents.len(), 2);
+  assert_eq!(extents[0].length, MAX_EXTENT_LENGTH);
+  assert_eq!(extents[1].length as u64, SIZE - MAX_EXTENT_LENGTH as u64);
+  assert_eq!(extents[1].lba, extents[0].lba + MAX_EXTENT_LENGTH / 2048);
+  assert!(
+    entry
+      .record()
+      .file_flags
+      .contains(spec::FileFlags::MULTI_EXTENT)
+  );
+  assert_eq!(reader.lookup("/small.txt").unwrap().size(), 5);
+}
+
+#[test]
+fn single_extent_interchange_levels() {
+  let path = std::env::temp_dir().join(format!("isofs-single-extent-{}", std::process::id()));
+  let file = std::fs::File::create(&path).unwrap();
+  file.set_len(5 << 30).unwrap();
+
+  for interchange_level in [
+    spec::InterchangeLevel::Level1,
+    spec::InterchangeLevel::Level2,
+  ] {
+    let mut filesystem = Filesystem::new();
+    filesystem
+      .insert_file(
+        "images/disk.img",
+        std::fs::File::open(&path).unwrap().try_into().unwrap(),
+        &OnFileConflict::Overwrite,
+      )
+      .unwrap();
+
+    let mut writer = IsoWriter::new(IsoWriterOptions {
+      interchange_level,
+      ..IsoWriterOptions::compatibility()
+    });
+    writer
+      .upsert_filesystem(filesystem, &OnFileConflict::Overwrite)
+      .unwrap();
+
+    assert!(matches!(
+      writer.finalize(Cursor::new(vec![])),
+      Err(isofs::error::Error::FileTooLarge(path)) if path == std::path::Path::new("/images/disk.img")
+    ));
+  }
+
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn iso9660_1999() {
+  let files: &[(&str, &[u8])] = &[
+    ("Release Notes/Version 1.0 (final).txt", b"notes"),
+    ("a/b/c/d/e/f/g/h/i/deep.txt", b"deep"),
+  ];
+  let options = IsoWriterOptions {
+    interchange_level: spec::InterchangeLevel::Iso9660_1999,
+    ..IsoWriterOptions::default()
+  };
+  let image = build_with(options, files).into_inner();
+
+  // The enhanced volume descriptor follows the Joliet one, describing the primary hierarchy.
+  let primary = spec::PrimaryVolumeDescriptor::deserialize(&mut (), &image[16 * 2048..]).unwrap();
+  let enhanced =
+    spec::SupplementaryVolumeDescriptor::deserialize(&mut (), &image[18 * 2048..]).unwrap();
+
+  assert_eq!(enhanced.version, spec::VolumeDescriptorVersion::Other(2));
+  assert_eq!(
+    enhanced.file_structure_version,
+    spec::FileStructureVersion::Other(2)
+  );
+  assert_eq!(enhanced.escape_sequences.joliet_level(), None);
+  assert_eq!(
+    enhanced.root_directory_record,
+    primary.root_directory_record
+  );
+  assert_eq!(
+    enhanced.type_l_path_table_location,
+    primary.type_l_path_table_location
+  );
+
+  // Identifiers are recorded as is, and deep directories are left in place.
+  let mut reader = IsoReader::new(Cursor::new(image)).unwrap();
+  let notes = reader.lookup("/Release Notes").unwrap();
+
+  assert_eq!(
+    reader.entries(&notes).unwrap()[0]
+      .record()
+      .file_identifier
+      .as_bytes(),
+    b"Version 1.0 (final).txt"
+  );
+  assert!(reader.lookup("/rr_moved").is_err());
+
+  let deep = reader.lookup("/a/b/c/d/e/f/g/h").unwrap();
+
+  assert!(
+    deep
+      .record()
+      .file_flags
+      .contains(spec::FileFlags::DIRECTORY)
+  );
+
+  let mut content = vec![];
+  reader
+    .open_file("/a/b/c/d/e/f/g/h/i/deep.txt")
+    .unwrap()
+    .read_to_end(&mut content)
+    .unwrap();
+  assert_eq!(content, b"deep");
+}
+
+#[test]
+fn mangled_names() {
+  let files: &[(&str, &[u8])] = &[
+    ("My Documents/Quarterly Report (final).pdf", b"final"),
+    ("My Documents/quarterly report (draft).pdf", b"draft"),
+    ("archive.tar.gz", b"archive"),
+    ("README", b"readme"),
+  ];
+  let options = IsoWriterOptions {
+    interchange_level: spec::InterchangeLevel::Level1,
+    ..IsoWriterOptions::compatibility()
+  };
+  let mut reader = IsoReader::new(build_with(options, files)).unwrap();
+  let names = |reader: &mut IsoReader<_>, path| {
+    let mut names = reader
+      .read_dir(path)
+      .unwrap()
+      .into_iter()
+      .map(|x| x.name().to_owned())
+      .collect::<Vec<_>>();
+    names.sort();
+    names
+  };
+
+  assert_eq!(
+    names(&mut reader, "/"),
+    ["ARCHIVE_.GZ", "MY_DOCUM", "README"]
+  );
+  assert_eq!(
+    names(&mut reader, "/MY_DOCUM"),
+    ["QUARTERL.PDF", "QUARTE_1.PDF"]
+  );
+
+  let mut content = vec![];
+  reader
+    .open_file("/MY_DOCUM/QUARTE_1.PDF")
+    .unwrap()
+    .read_to_end(&mut content)
+    .unwrap();
+  assert_eq!(content, b"draft");
+
+  // Rock Ridge and Joliet keep the names as they were.
+  let mut reader = IsoReader::new(build_with(IsoWriterOptions::default(), files)).unwrap();
+
+  assert_eq!(
+    names(&mut reader, "/My Documents"),
+    [
+      "Quarterly Report (final).pdf",
+      "quarterly report (draft).pdf"
+    ]
+  );
+}
+
+/// Records of the path table of the primary hierarchy, in the order they are recorded.
+fn path_table(image: &[u8]) -> Vec<spec::PathTableRecord> {
+  let pvd =
+    spec::PrimaryVolumeDescriptor::deserialize(&mut (), &image[16 * 2048..17 * 2048]).unwrap();
+  let mut data =
+    &image[pvd.type_l_path_table_location as usize * 2048..][..pvd.path_table_size as usize];
+  let mut records = vec![];
+
+  while !data.is_empty() {
+    let record =
+      spec::PathTableRecord::deserialize(&mut isofs::serialize::Endianness::Little, data).unwrap();
+
+    data = &data[record.extent()..];
+    records.push(record);
+  }
+
+  records
+}
+
+#[test]
+fn path_table_order() {
+  // `a-z` comes before `ab`, but `A_Z` after `AB`.
+  let image = build(&[("a-z/inner/x.txt", b"x"), ("ab/y.txt", b"y")]).into_inner();
+  let records = path_table(&image)
+    .into_iter()
+    .map(|x| {
+      (
+        x.directory_identifier.to_string(),
+        x.parent_directory_number,
+      )
+    })
+    .collect::<Vec<_>>();
+
+  // Listed level by level, then by parent and identifier.
+  assert_eq!(
+    records[1..],
+    [
+      ("AB".to_string(), 1),
+      ("A_Z".to_string(), 1),
+      ("INNER".to_string(), 3),
+    ]
+  );
+}
+
+#[test]
+fn volume_metadata() {
+  let files: &[(&str, &[u8])] = &[
+    ("copying", b"All rights reserved."),
+    ("docs/abstract.txt", b"Abstract"),
+  ];
+  let volume = VolumeMetadata {
+    volume_identifier: "INSTALLER_2025".to_string(),
+    publisher_identifier: "EXAMPLE CORP".to_string(),
+    application_identifier: "INSTALLER BUILDER".to_string(),
+    copyright_file: Some("/copying".into()),
+    ..VolumeMetadata::default()
+  };
+  let options = IsoWriterOptions {
+    volume: volume.clone(),
+    ..IsoWriterOptions::default()
+  };
+  let image = build_with(options, files).into_inner();
+  let reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let pvd = reader.primary_volume_descriptor();
+
+  assert_eq!(pvd.system_identifier.to_string(), "LINUX");
+  assert_eq!(pvd.volume_identifier.to_string(), "INSTALLER_2025");
+  assert_eq!(pvd.publisher_identifier.to_string(), "EXAMPLE CORP");
+  assert_eq!(pvd.application_identifier.to_string(), "INSTALLER BUILDER");
+  assert_eq!(pvd.copyright_file_identifier.to_string(), "COPYING.;1");
+  assert_eq!(pvd.abstract_file_identifier.to_string(), "");
+
+  // The Joliet volume descriptor records the same metadata in UCS-2, referring to the file
+  // through its Joliet identifier.
+  let ucs2 = |s: &str| {
+    s.encode_utf16()
+      .flat_map(|x| x.to_be_bytes())
+      .collect::<Vec<_>>()
+  };
+  let joliet =
+    spec::SupplementaryVolumeDescriptor::deserialize(&mut (), &image[17 * 2048..]).unwrap();
+
+  assert_eq!(joliet.volume_identifier.as_bytes(), ucs2("INSTALLER_2025"));
+  assert_eq!(joliet.copyright_file_identifier.as_bytes(), ucs2("copying"));
+
+  // ISO 9660:1999 refers to the file through its identifier as is, beyond d-characters.
+  let options = IsoWriterOptions {
+    interchange_level: spec::InterchangeLevel::Iso9660_1999,
+    volume: volume.clone(),
+    ..IsoWriterOptions::default()
+  };
+  let image = build_with(options, files).into_inner();
+  let reader = IsoReader::new(Cursor::new(image)).unwrap();
+
+  assert_eq!(
+    reader
+      .primary_volume_descriptor()
+      .copyright_file_identifier
+      .to_string(),
+    "copying"
+  );
+
+  // Volume descriptors only refer to files within the root directory.
+  let build = |volume| {
+    let mut writer = IsoWriter::new(IsoWriterOptions {
+      volume,
+      ..IsoWriterOptions::default()
+    });
+    writer
+      .upsert_filesystem(filesystem(files), &OnFileConflict::Overwrite)
+      .unwrap();
+    writer.finalize(Cursor::new(vec![]))
+  };
+
+  assert!(matches!(
+    build(VolumeMetadata {
+      abstract_file: Some("/docs/abstract.txt".into()),
+      ..volume.clone()
+    }),
+    Err(isofs::error::Error::NotInRootDirectory(path)) if path.ends_with("docs/abstract.txt")
+  ));
+  assert!(matches!(
+    build(VolumeMetadata {
+      bibliographic_file: Some("/bibliography".into()),
+      ..volume.clone()
+    }),
+    Err(isofs::error::Error::NotFound(_))
+  ));
+
+  // Labels beyond the characters of the primary volume descriptor are rejected.
+  assert!(matches!(
+    build(VolumeMetadata {
+      volume_identifier: "installer".to_string(),
+      ..volume
+    }),
+    Err(isofs::error::Error::Identifier(
+      spec::IdentifierError::InvalidCharacter {
+        character: 'i',
+        position: 0,
+        ..
+      }
+    ))
+  ));
+}
+
+#[test]
+fn reproducible_builds() {
+  let files: &[(&str, &[u8])] = &[
+    ("b.txt", b"b"),
+    ("a.txt", b"a"),
+    ("docs/z.md", b"z"),
+    ("docs/guide/intro.md", b"intro"),
+    ("Long File Name.text", b"long"),
+    ("a/b/c/d/e/f/g/h/i/deep.txt", b"deep"),
+  ];
+  let recording_date = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+  let options = IsoWriterOptions {
+    recording_date: Some(recording_date),
+    ..IsoWriterOptions::default()
+  };
+
+  // Neither the order files are inserted in nor the time of building changes the image.
+  let image = build_with(options.clone(), files).into_inner();
+  let reversed = files.iter().rev().copied().collect::<Vec<_>>();
+
+  std::thread::sleep(std::time::Duration::from_millis(1100));
+
+  assert!(image == build_with(options, &reversed).into_inner());
+
+  let reader = IsoReader::new(Cursor::new(image)).unwrap();
+
+  assert_eq!(
+    chrono::DateTime::try_from(reader.primary_volume_descriptor().creation_date.clone()).unwrap(),
+    recording_date
+  );
+}
+
+#[test]
+fn reproducible_captures() {
+  let files: &[(&str, &[u8])] = &[
+    ("readme.txt", b"readme"),
+    ("docs/guide.md", b"guide"),
+    ("docs/empty/", b""),
+  ];
+  let capture = |name: &str| {
+    let source =
+      std::env::temp_dir().join(format!("isofs-reproducible-{name}-{}", std::process::id()));
+
+    for (path, content) in files {
+      match path.strip_suffix('/') {
+        Some(path) => std::fs::create_dir_all(source.join(path)).unwrap(),
+        None => {
+          std::fs::create_dir_all(source.join(path).parent().unwrap()).unwrap();
+          std::fs::write(source.join(path), content).unwrap();
+        }
+      }
+    }
+
+    source
+  };
+  let build = |source: &std::path::Path| {
+    let mut writer = IsoWriter::new(IsoWriterOptions {
+      recording_date: chrono::DateTime::from_timestamp(1_700_000_000, 0),
+      ..IsoWriterOptions::default()
+    });
+    writer
+      .upsert_filesystem(
+        Filesystem::capture("", source).unwrap(),
+        &OnFileConflict::Overwrite,
+      )
+      .unwrap();
+
+    let mut image = Cursor::new(vec![]);
+    writer.finalize(&mut image).unwrap();
+    image.into_inner()
+  };
+
+  // Checkouts of the same tree differ in inode numbers and times, which reading files changes
+  // too, none of which ends up within the image.
+  let first = capture("first");
+
+  std::thread::sleep(std::time::Duration::from_millis(1100));
+
+  let second = capture("second");
+  let image = build(&first);
+
+  std::fs::read(first.join("readme.txt")).unwrap();
+
+  let reproduced = build(&first);
+  let copy = build(&second);
+
+  std::fs::remove_dir_all(&first).unwrap();
+  std::fs::remove_dir_all(&second).unwrap();
+
+  assert!(image == reproduced);
+  assert!(image == copy);
+}
+
+#[test]
+fn directory_record_layout() {
+  let names = (0..150)
+    .map(|ix| {
+      format!(
+        "{} file number {ix}.txt",
+        ["beta", "Alpha", "gamma"][ix % 3]
+      )
+    })
+    .collect::<Vec<_>>();
+  let files = names
+    .iter()
+    .map(|name| (name.as_str(), b"x".as_slice()))
+    .chain([("alpha/readme.txt", b"readme".as_slice())])
+    .collect::<Vec<_>>();
+  let image = build_with(IsoWriterOptions::default(), &files).into_inner();
+  let mut reader = IsoReader::new(Cursor::new(image.clone())).unwrap();
+  let root = reader
+    .primary_volume_descriptor()
+    .root_directory_record
+    .clone();
+
+  // The records span several sectors, the last one padded.
+  assert!(root.data_length > 2048);
+  assert_eq!(root.data_length % 2048, 0);
+
+  let data = &image[root.extent_location as usize * 2048..][..root.data_length as usize];
+  let mut identifiers = vec![];
+
+  for sector in data.chunks(2048) {
+    let mut offset = 0;
+
+    while offset < sector.len() && sector[offset] != 0 {
+      // Records never cross into the next sector.
+      assert!(offset + sector[offset] as usize <= sector.len());
+
+      let record = spec::DirectoryRecord::deserialize(
+        &mut spec::CompatibilityMode::Standard(spec::InterchangeLevel::Level3).into(),
+        &sector[offset..],
+      )
+      .unwrap();
+
+      offset += sector[offset] as usize;
+      identifiers.push(record.file_identifier);
+    }
+  }
+
+  assert_eq!(identifiers.len(), files.len() + 2);
+  assert!(identifiers.is_sorted_by(|a, b| a.cmp_record_order(b).is_le()));
+  assert_eq!(reader.read_dir("/").unwrap().len(), files.len());
+}